@@ -0,0 +1,593 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zenoh_perf_cli::{check, Invocation, Layer, Mode};
+
+const SCENARIOS: &str = "SCENARIOS:
+    thr pub | thr sub          Throughput in msg/s of a publisher towards a subscriber
+                               t: t_pub_thr/t_sub_thr, r: r_pub_thr/r_sub_thr,
+                               zn: zn_pub_thr/zn_sub_thr, z: z_put_thr/z_sub_thr
+    lat ping | lat pong        Round-trip time in us of a ping echoed by a pong
+                               {t,r,zn,z}_ping/{t,r,zn,z}_pong
+    query get | query eval     Latency in us of a query answered by queryables
+                               {t,r,zn,z}_query/{t,r,zn,z}_eval
+    query thr | query eval     Throughput in queries/s answered by queryables
+                               {t,r,zn,z}_query_thr/{t,r,zn,z}_eval
+    overhead pub               Bytes on the wire per message, zn: zn_overhead, z: z_overhead
+    overhead query             Bytes on the wire per query, zn: zn_query_overhead
+    overhead analyze           Summary of a capture exported as JSON, zn_analyze
+
+NOT WRAPPED:
+    The following benchmarks have flags of their own and are run directly, see
+    their `--help`:
+    t_sink_tcp, t_sink_udp     Raw TCP and UDP receivers, the baseline without zenoh
+    t_pubsub_thr               Transport peers publishing and counting at the same time
+    t_router_thr               A transport router between publishers and subscribers
+    t_pub_delay, t_sub_delay   One-way transport delay
+    zn_sub_thr_stream          zenoh-net throughput of a subscriber read as a stream
+    z_storage                  Storage get, range, visibility and delete latencies
+    zn_workload_query/_eval    Query workloads with wildcard selectors and predicates
+    t_sweep                    Transport batch size, SN resolution and QoS sweep
+    zn_recovery, t_keepalive   Failure detection and recovery behind the proxy
+    zn_pub_shm/zn_sub_shm      Publication from shared memory or from the heap
+    zn_pub_frag/zn_sub_frag    Fragmented payloads verified byte per byte
+
+LAYERS AND MODES:
+    t   transport    peer, client, router
+    r   router       peer, client, router
+    zn  zenoh-net    peer, client
+    z   zenoh        peer, client
+
+The listening side prints `ready,<locator>` once it serves, give it port 0 to
+bind an ephemeral port. A `unixsock-stream/<path>`, `tls/` or `quic/` locator
+needs the binaries built with the `unixsock-stream`, `tls` or `quic` feature,
+`gen_certs` writes throwaway certificates for the latter two. Arguments after
+`--` are passed as is to the binary, e.g. `-- --conf <tls.conf>`.";
+
+#[derive(Debug, StructOpt)]
+struct Endpoint {
+    #[structopt(
+        long = "layer",
+        default_value = "zn",
+        help = "t (transport), r (router), zn (zenoh-net) or z (zenoh)"
+    )]
+    layer: Layer,
+    #[structopt(
+        short = "l",
+        long = "locator",
+        help = "The locator to listen on or to connect to"
+    )]
+    locator: String,
+    #[structopt(
+        short = "m",
+        long = "mode",
+        default_value = "peer",
+        help = "peer, client or router, depending on the layer"
+    )]
+    mode: Mode,
+    #[structopt(
+        long = "dry-run",
+        help = "Print the command line instead of running it"
+    )]
+    dry_run: bool,
+    #[structopt(last = true, help = "Arguments passed as is to the benchmark binary")]
+    extra: Vec<String>,
+}
+
+#[derive(Debug, StructOpt)]
+struct Report {
+    #[structopt(
+        short = "n",
+        long = "name",
+        help = "The name of the run in the results"
+    )]
+    name: String,
+    #[structopt(
+        short = "s",
+        long = "scenario",
+        help = "The scenario of the run in the results"
+    )]
+    scenario: String,
+}
+
+#[derive(Debug, StructOpt)]
+enum Thr {
+    /// Publish messages as fast as possible
+    Pub {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(short = "p", long = "payload", help = "The payload size in bytes")]
+        payload: usize,
+        #[structopt(short = "t", long = "print", help = "Print the publication rate")]
+        print: bool,
+        #[structopt(
+            long = "wait-ready",
+            help = "Retry for up to SECS until the subscriber is up"
+        )]
+        wait_ready: Option<f64>,
+    },
+    /// Count the messages received every second
+    Sub {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(flatten)]
+        report: Report,
+        #[structopt(short = "p", long = "payload", help = "The payload size in bytes")]
+        payload: usize,
+        #[structopt(long = "loopback", help = "Run the publisher in the same process")]
+        loopback: bool,
+        #[structopt(
+            long = "ready-file",
+            parse(from_os_str),
+            help = "Write the bound locator to this file"
+        )]
+        ready_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum Lat {
+    /// Send pings and measure the round-trip time of each pong
+    Ping {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(flatten)]
+        report: Report,
+        #[structopt(
+            short = "p",
+            long = "payload",
+            help = "The payload size in bytes, at least 8"
+        )]
+        payload: usize,
+        #[structopt(
+            short = "i",
+            long = "interval",
+            default_value = "1",
+            help = "The interval between two pings in seconds"
+        )]
+        interval: f64,
+        #[structopt(
+            long = "parallel",
+            help = "Do not wait for a pong to send the next ping"
+        )]
+        parallel: bool,
+        #[structopt(long = "loopback", help = "Run the pong in the same process")]
+        loopback: bool,
+        #[structopt(
+            long = "wait-ready",
+            help = "Retry for up to SECS until the pong is up"
+        )]
+        wait_ready: Option<f64>,
+    },
+    /// Echo every ping back
+    Pong {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(
+            long = "ready-file",
+            parse(from_os_str),
+            help = "Write the bound locator to this file"
+        )]
+        ready_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum Query {
+    /// Send queries one after the other and measure their latency
+    Get {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(flatten)]
+        report: Report,
+        #[structopt(
+            long = "wait-ready",
+            help = "Retry for up to SECS until the queryables are up"
+        )]
+        wait_ready: Option<f64>,
+    },
+    /// Send queries as fast as possible and count the completed ones
+    Thr {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(flatten)]
+        report: Report,
+        #[structopt(
+            short = "p",
+            long = "payload",
+            help = "The reply payload size in bytes"
+        )]
+        payload: usize,
+        #[structopt(short = "w", long = "window", help = "The number of queries in flight")]
+        window: Option<usize>,
+        #[structopt(
+            long = "wait-ready",
+            help = "Retry for up to SECS until the queryables are up"
+        )]
+        wait_ready: Option<f64>,
+    },
+    /// Reply to every query
+    Eval {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(
+            short = "p",
+            long = "payload",
+            help = "The reply payload size in bytes"
+        )]
+        payload: usize,
+        #[structopt(short = "q", long = "queryables", help = "The number of queryables")]
+        queryables: Option<usize>,
+        #[structopt(
+            long = "ready-file",
+            parse(from_os_str),
+            help = "Write the bound locator to this file"
+        )]
+        ready_file: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+enum Overhead {
+    /// Publish a total amount of data to capture its overhead on the wire
+    Pub {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(short = "p", long = "payload", help = "The payload size in bytes")]
+        payload: usize,
+        #[structopt(
+            short = "t",
+            long = "total",
+            help = "The total amount of bytes to publish"
+        )]
+        total: Option<u64>,
+        #[structopt(
+            short = "i",
+            long = "interval",
+            help = "The interval between two publications in seconds"
+        )]
+        interval: Option<f64>,
+        #[structopt(short = "v", long = "verbose", help = "Print every publication")]
+        verbose: bool,
+    },
+    /// Send a number of queries to capture their overhead on the wire
+    Query {
+        #[structopt(flatten)]
+        endpoint: Endpoint,
+        #[structopt(short = "t", long = "total", help = "The number of queries")]
+        total: Option<u64>,
+        #[structopt(
+            short = "i",
+            long = "interval",
+            help = "The interval between two queries in seconds"
+        )]
+        interval: Option<f64>,
+        #[structopt(short = "v", long = "verbose", help = "Print every query")]
+        verbose: bool,
+    },
+    /// Summarize the overhead of a capture exported as JSON
+    Analyze {
+        #[structopt(
+            short = "j",
+            long = "json",
+            help = "The capture exported with `tshark -T json`"
+        )]
+        file: String,
+        #[structopt(
+            short = "o",
+            long = "op",
+            default_value = "data",
+            help = "The operation to analyze"
+        )]
+        op: String,
+        #[structopt(
+            long = "protocol",
+            default_value = "zenoh-net",
+            help = "The protocol reported in the results"
+        )]
+        protocol: String,
+        #[structopt(
+            long = "dry-run",
+            help = "Print the command line instead of running it"
+        )]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "zperf",
+    about = "Runs the zenoh benchmarks with consistent flags",
+    after_help = SCENARIOS
+)]
+enum Opt {
+    /// Throughput of a publisher towards a subscriber
+    Thr(Thr),
+    /// Round-trip latency between a ping and a pong
+    Lat(Lat),
+    /// Latency and throughput of queries
+    Query(Query),
+    /// Overhead of the protocol on the wire
+    Overhead(Overhead),
+}
+
+const ALL: &[Layer] = &Layer::ALL;
+const ZENOH_NET: &[Layer] = &[Layer::Transport, Layer::Router, Layer::Net];
+
+impl Endpoint {
+    // Validates the layer and the mode, then starts the command line
+    fn invocation(&self, test: &str, layers: &[Layer], bin: &str) -> Result<Invocation, String> {
+        check(test, self.layer, layers, self.mode)?;
+        Ok(Invocation::new(bin).arg("-m", self.mode))
+    }
+
+    // Appends the extra arguments, which must come last
+    fn finish(&self, invocation: Invocation) -> (Invocation, bool) {
+        (invocation.extra(&self.extra), self.dry_run)
+    }
+}
+
+fn only(test: &str, option: &str, set: bool, layer: Layer, layers: &[Layer]) -> Result<(), String> {
+    if set && !layers.contains(&layer) {
+        return Err(format!(
+            "{} is not available for the {} test on layer {}",
+            option, test, layer
+        ));
+    }
+    Ok(())
+}
+
+fn report(invocation: Invocation, report: &Report) -> Invocation {
+    invocation
+        .arg("-n", &report.name)
+        .arg("-s", &report.scenario)
+}
+
+fn thr(opt: Thr) -> Result<(Invocation, bool), String> {
+    match opt {
+        Thr::Pub {
+            endpoint,
+            payload,
+            print,
+            wait_ready,
+        } => {
+            let (bin, locator) = match endpoint.layer {
+                Layer::Transport => ("t_pub_thr", "-c"),
+                Layer::Router => ("r_pub_thr", "-l"),
+                Layer::Net => ("zn_pub_thr", "-l"),
+                Layer::Zenoh => ("z_put_thr", "-l"),
+            };
+            let invocation = endpoint
+                .invocation("thr pub", ALL, bin)?
+                .arg(locator, &endpoint.locator)
+                .arg("-p", payload)
+                .flag("-t", print)
+                .opt("--wait-ready", wait_ready);
+            Ok(endpoint.finish(invocation))
+        }
+        Thr::Sub {
+            endpoint,
+            report,
+            payload,
+            loopback,
+            ready_file,
+        } => {
+            only("thr sub", "--loopback", loopback, endpoint.layer, ZENOH_NET)?;
+            let (bin, locator) = match endpoint.layer {
+                Layer::Transport => ("t_sub_thr", "-e"),
+                Layer::Router => ("r_sub_thr", "-l"),
+                Layer::Net => ("zn_sub_thr", "-l"),
+                Layer::Zenoh => ("z_sub_thr", "-l"),
+            };
+            let invocation = endpoint
+                .invocation("thr sub", ALL, bin)?
+                .arg(locator, &endpoint.locator)
+                .arg("-p", payload)
+                .flag("--loopback", loopback)
+                .opt("--ready-file", ready_file.map(|f| f.display().to_string()));
+            Ok(endpoint.finish(self::report(invocation, &report)))
+        }
+    }
+}
+
+fn lat(opt: Lat) -> Result<(Invocation, bool), String> {
+    match opt {
+        Lat::Ping {
+            endpoint,
+            report,
+            payload,
+            interval,
+            parallel,
+            loopback,
+            wait_ready,
+        } => {
+            if payload < 8 {
+                return Err("The ping payload carries an 8-byte counter, use -p 8 or more".into());
+            }
+            only(
+                "lat ping",
+                "--loopback",
+                loopback,
+                endpoint.layer,
+                ZENOH_NET,
+            )?;
+            let bin = format!("{}_ping", endpoint.layer.prefix());
+            let invocation = endpoint
+                .invocation("lat ping", ALL, &bin)?
+                .arg("-l", &endpoint.locator)
+                .arg("-p", payload)
+                .arg("-i", interval)
+                .flag("--parallel", parallel)
+                .flag("--loopback", loopback)
+                .opt("--wait-ready", wait_ready);
+            Ok(endpoint.finish(self::report(invocation, &report)))
+        }
+        Lat::Pong {
+            endpoint,
+            ready_file,
+        } => {
+            let bin = format!("{}_pong", endpoint.layer.prefix());
+            let invocation = endpoint
+                .invocation("lat pong", ALL, &bin)?
+                .arg("-l", &endpoint.locator)
+                .opt("--ready-file", ready_file.map(|f| f.display().to_string()));
+            Ok(endpoint.finish(invocation))
+        }
+    }
+}
+
+fn query(opt: Query) -> Result<(Invocation, bool), String> {
+    match opt {
+        Query::Get {
+            endpoint,
+            report,
+            wait_ready,
+        } => {
+            let bin = format!("{}_query", endpoint.layer.prefix());
+            let invocation = endpoint
+                .invocation("query get", ALL, &bin)?
+                .arg("-l", &endpoint.locator)
+                .opt("--wait-ready", wait_ready);
+            Ok(endpoint.finish(self::report(invocation, &report)))
+        }
+        Query::Thr {
+            endpoint,
+            report,
+            payload,
+            window,
+            wait_ready,
+        } => {
+            only(
+                "query thr",
+                "--window",
+                window.is_some(),
+                endpoint.layer,
+                ZENOH_NET,
+            )?;
+            let bin = format!("{}_query_thr", endpoint.layer.prefix());
+            let invocation = endpoint
+                .invocation("query thr", ALL, &bin)?
+                .arg("-l", &endpoint.locator)
+                .arg("-p", payload)
+                .opt("-w", window)
+                .opt("--wait-ready", wait_ready);
+            Ok(endpoint.finish(self::report(invocation, &report)))
+        }
+        Query::Eval {
+            endpoint,
+            payload,
+            queryables,
+            ready_file,
+        } => {
+            only(
+                "query eval",
+                "--queryables",
+                queryables.is_some(),
+                endpoint.layer,
+                ZENOH_NET,
+            )?;
+            let bin = format!("{}_eval", endpoint.layer.prefix());
+            let invocation = endpoint
+                .invocation("query eval", ALL, &bin)?
+                .arg("-l", &endpoint.locator)
+                .arg("-p", payload)
+                .opt("-q", queryables)
+                .opt("--ready-file", ready_file.map(|f| f.display().to_string()));
+            Ok(endpoint.finish(invocation))
+        }
+    }
+}
+
+fn overhead(opt: Overhead) -> Result<(Invocation, bool), String> {
+    match opt {
+        Overhead::Pub {
+            endpoint,
+            payload,
+            total,
+            interval,
+            verbose,
+        } => {
+            let bin = format!("{}_overhead", endpoint.layer.prefix());
+            let invocation = endpoint
+                .invocation("overhead pub", &[Layer::Net, Layer::Zenoh], &bin)?
+                .arg("-l", &endpoint.locator)
+                .arg("-p", payload)
+                .opt("-t", total)
+                .opt("-i", interval)
+                .flag("-v", verbose);
+            Ok(endpoint.finish(invocation))
+        }
+        Overhead::Query {
+            endpoint,
+            total,
+            interval,
+            verbose,
+        } => {
+            let invocation = endpoint
+                .invocation("overhead query", &[Layer::Net], "zn_query_overhead")?
+                .arg("-l", &endpoint.locator)
+                .opt("-t", total)
+                .opt("-i", interval)
+                .flag("-v", verbose);
+            Ok(endpoint.finish(invocation))
+        }
+        Overhead::Analyze {
+            file,
+            op,
+            protocol,
+            dry_run,
+        } => {
+            let invocation = Invocation::new("zn_analyze")
+                .arg("-j", file)
+                .arg("-o", op)
+                .arg("--protocol", protocol);
+            Ok((invocation, dry_run))
+        }
+    }
+}
+
+#[cfg(unix)]
+fn run(invocation: Invocation) {
+    use std::os::unix::process::CommandExt;
+    // Replace this process, the benchmark owns the terminal and the signals
+    let e = invocation.command().exec();
+    panic!("Unable to run {}: {}", invocation.path().display(), e);
+}
+
+#[cfg(not(unix))]
+fn run(invocation: Invocation) {
+    let status = invocation.command().status().unwrap();
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+fn main() {
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let res = match opt {
+        Opt::Thr(opt) => thr(opt),
+        Opt::Lat(opt) => lat(opt),
+        Opt::Query(opt) => query(opt),
+        Opt::Overhead(opt) => overhead(opt),
+    };
+    match res {
+        Ok((invocation, true)) => println!("{}", invocation),
+        Ok((invocation, false)) => run(invocation),
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(2);
+        }
+    }
+}
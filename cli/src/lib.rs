@@ -0,0 +1,185 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The layers and modes shared by the `zperf` subcommands, and the
+//! invocation of the benchmark binary running each of them.
+use std::fmt;
+use std::path::PathBuf;
+use std::process::Command;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Layer {
+    Transport,
+    Router,
+    Net,
+    Zenoh,
+}
+
+impl Layer {
+    pub const ALL: [Layer; 4] = [Layer::Transport, Layer::Router, Layer::Net, Layer::Zenoh];
+
+    /// The prefix of the benchmark binaries of the layer.
+    pub fn prefix(&self) -> &'static str {
+        match self {
+            Layer::Transport => "t",
+            Layer::Router => "r",
+            Layer::Net => "zn",
+            Layer::Zenoh => "z",
+        }
+    }
+
+    /// The modes the benchmark binaries of the layer accept.
+    pub fn modes(&self) -> &'static [Mode] {
+        match self {
+            Layer::Transport | Layer::Router => &[Mode::Peer, Mode::Client, Mode::Router],
+            Layer::Net | Layer::Zenoh => &[Mode::Peer, Mode::Client],
+        }
+    }
+}
+
+impl FromStr for Layer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Layer, String> {
+        match s {
+            "t" | "transport" => Ok(Layer::Transport),
+            "r" | "router" => Ok(Layer::Router),
+            "zn" | "zenoh-net" => Ok(Layer::Net),
+            "z" | "zenoh" => Ok(Layer::Zenoh),
+            _ => Err(format!("Unsupported layer: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.prefix())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Peer,
+    Client,
+    Router,
+}
+
+impl FromStr for Mode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Mode, String> {
+        match s {
+            "peer" => Ok(Mode::Peer),
+            "client" => Ok(Mode::Client),
+            "router" => Ok(Mode::Router),
+            _ => Err(format!("Unsupported mode: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mode::Peer => f.write_str("peer"),
+            Mode::Client => f.write_str("client"),
+            Mode::Router => f.write_str("router"),
+        }
+    }
+}
+
+/// Checks that the layer runs the test and accepts the mode.
+pub fn check(test: &str, layer: Layer, layers: &[Layer], mode: Mode) -> Result<(), String> {
+    if !layers.contains(&layer) {
+        let supported: Vec<&str> = layers.iter().map(|l| l.prefix()).collect();
+        return Err(format!(
+            "The {} test is not available on layer {}, use one of: {}",
+            test,
+            layer,
+            supported.join(", ")
+        ));
+    }
+    if !layer.modes().contains(&mode) {
+        let supported: Vec<String> = layer.modes().iter().map(|m| m.to_string()).collect();
+        return Err(format!(
+            "Layer {} does not support mode {}, use one of: {}",
+            layer,
+            mode,
+            supported.join(", ")
+        ));
+    }
+    Ok(())
+}
+
+/// The command line of a benchmark binary.
+#[derive(Debug, PartialEq)]
+pub struct Invocation {
+    pub bin: String,
+    pub args: Vec<String>,
+}
+
+impl Invocation {
+    pub fn new(bin: &str) -> Invocation {
+        Invocation {
+            bin: bin.to_string(),
+            args: vec![],
+        }
+    }
+
+    pub fn arg<T: ToString>(mut self, flag: &str, value: T) -> Invocation {
+        self.args.push(flag.to_string());
+        self.args.push(value.to_string());
+        self
+    }
+
+    pub fn opt<T: ToString>(self, flag: &str, value: Option<T>) -> Invocation {
+        match value {
+            Some(value) => self.arg(flag, value),
+            None => self,
+        }
+    }
+
+    pub fn flag(mut self, flag: &str, on: bool) -> Invocation {
+        if on {
+            self.args.push(flag.to_string());
+        }
+        self
+    }
+
+    pub fn extra(mut self, args: &[String]) -> Invocation {
+        self.args.extend_from_slice(args);
+        self
+    }
+
+    /// The benchmark binaries are built next to `zperf`.
+    pub fn path(&self) -> PathBuf {
+        let exe = std::env::current_exe().unwrap();
+        exe.with_file_name(&self.bin)
+    }
+
+    pub fn command(&self) -> Command {
+        let mut command = Command::new(self.path());
+        command.args(&self.args);
+        command
+    }
+}
+
+impl fmt::Display for Invocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.bin)?;
+        for arg in self.args.iter() {
+            write!(f, " {}", arg)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Checks the benchmark command lines built by `zperf --dry-run`.
+use std::process::{Command, Output};
+
+// Run `zperf <command> <subcommand> --dry-run <args>`
+fn zperf(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_zperf"))
+        .args(&args[..2])
+        .arg("--dry-run")
+        .args(&args[2..])
+        .output()
+        .unwrap()
+}
+
+fn dry_run(args: &[&str]) -> String {
+    let output = zperf(args);
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout).unwrap().trim().to_string()
+}
+
+fn rejected(args: &[&str]) -> String {
+    let output = zperf(args);
+    assert_eq!(output.status.code(), Some(2), "{:?}", output);
+    String::from_utf8(output.stderr).unwrap()
+}
+
+#[test]
+fn locator_flag_of_each_layer() {
+    let sub = [
+        "thr",
+        "sub",
+        "-l",
+        "tcp/127.0.0.1:0",
+        "-p",
+        "64",
+        "-n",
+        "n",
+        "-s",
+        "s",
+    ];
+    assert_eq!(
+        dry_run(&[&sub[..], &["--layer", "t"]].concat()),
+        "t_sub_thr -m peer -e tcp/127.0.0.1:0 -p 64 -n n -s s"
+    );
+    assert_eq!(
+        dry_run(&[&sub[..], &["--layer", "r"]].concat()),
+        "r_sub_thr -m peer -l tcp/127.0.0.1:0 -p 64 -n n -s s"
+    );
+    assert_eq!(
+        dry_run(&[
+            "thr",
+            "pub",
+            "--layer",
+            "t",
+            "-l",
+            "tcp/127.0.0.1:7447",
+            "-p",
+            "8"
+        ]),
+        "t_pub_thr -m peer -c tcp/127.0.0.1:7447 -p 8"
+    );
+    assert_eq!(
+        dry_run(&[
+            "thr",
+            "pub",
+            "--layer",
+            "z",
+            "-l",
+            "tcp/127.0.0.1:7447",
+            "-p",
+            "8"
+        ]),
+        "z_put_thr -m peer -l tcp/127.0.0.1:7447 -p 8"
+    );
+}
+
+#[test]
+fn extra_arguments_come_last() {
+    assert_eq!(
+        dry_run(&[
+            "thr",
+            "pub",
+            "-l",
+            "tcp/127.0.0.1:7447",
+            "-p",
+            "8",
+            "--",
+            "--reskey",
+            "rname",
+        ]),
+        "zn_pub_thr -m peer -l tcp/127.0.0.1:7447 -p 8 --reskey rname"
+    );
+}
+
+#[test]
+fn unsupported_combinations() {
+    let e = rejected(&[
+        "lat",
+        "pong",
+        "--layer",
+        "zn",
+        "-m",
+        "router",
+        "-l",
+        "tcp/127.0.0.1:0",
+    ]);
+    assert!(e.contains("does not support mode router"), "{}", e);
+    let e = rejected(&["overhead", "query", "--layer", "t", "-l", "tcp/127.0.0.1:0"]);
+    assert!(e.contains("not available on layer t"), "{}", e);
+    let e = rejected(&[
+        "lat",
+        "ping",
+        "-l",
+        "tcp/127.0.0.1:0",
+        "-p",
+        "4",
+        "-n",
+        "n",
+        "-s",
+        "s",
+    ]);
+    assert!(e.contains("8-byte counter"), "{}", e);
+}
@@ -0,0 +1,110 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use zenoh::net::protocol::core::Reliability;
+use zenoh::net::protocol::io::{WBuf, ZBuf};
+use zenoh::net::protocol::proto::{TransportMessage, ZenohMessage};
+use zenoh_perf_codec::*;
+
+const PAYLOADS: [usize; 5] = [8, 64, 1_024, 8_192, 65_000];
+
+// Criterion reports the time per operation, the throughput gives the bytes per operation
+fn zenoh_message(c: &mut Criterion, group: &str, id: String, mut message: ZenohMessage) {
+    let bytes = encode_zenoh(&mut message);
+    println!("{}/{}: {} bytes/op", group, id, bytes.len());
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function(BenchmarkId::new("encode", &id), |b| {
+        b.iter(|| {
+            let mut wbuf = WBuf::new(bytes.len(), false);
+            wbuf.write_zenoh_message(&mut message)
+        })
+    });
+    group.bench_function(BenchmarkId::new("decode", &id), |b| {
+        b.iter_batched(
+            || ZBuf::from(bytes.clone()),
+            |mut zbuf| zbuf.read_zenoh_message(Reliability::Reliable).unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn transport_message(c: &mut Criterion, group: &str, id: String, mut message: TransportMessage) {
+    let bytes = encode_transport(&mut message);
+    println!("{}/{}: {} bytes/op", group, id, bytes.len());
+    let mut group = c.benchmark_group(group);
+    group.throughput(Throughput::Bytes(bytes.len() as u64));
+    group.bench_function(BenchmarkId::new("encode", &id), |b| {
+        b.iter(|| {
+            let mut wbuf = WBuf::new(bytes.len(), false);
+            wbuf.write_transport_message(&mut message)
+        })
+    });
+    group.bench_function(BenchmarkId::new("decode", &id), |b| {
+        b.iter_batched(
+            || ZBuf::from(bytes.clone()),
+            |mut zbuf| zbuf.read_transport_message().unwrap(),
+            BatchSize::SmallInput,
+        )
+    });
+    group.finish();
+}
+
+fn data(c: &mut Criterion) {
+    for form in KEYS.iter() {
+        for info in [false, true].iter() {
+            for payload in PAYLOADS.iter() {
+                let info_name = if *info { "info" } else { "noinfo" };
+                let id = format!("{}/{}/{}", form, info_name, payload);
+                let message = zenoh_perf_codec::data(form, *payload, *info);
+                zenoh_message(c, "data", id, message);
+            }
+        }
+    }
+}
+
+fn frame(c: &mut Criterion) {
+    for count in [1, 10, 100].iter() {
+        for payload in [8, 64, 1_024].iter() {
+            let id = format!("{}msgs/{}", count, payload);
+            transport_message(c, "frame", id, zenoh_perf_codec::frame(*count, *payload));
+        }
+    }
+}
+
+fn fragment(c: &mut Criterion) {
+    for size in [1_024, 8_192, 65_000].iter() {
+        for is_final in [false, true].iter() {
+            let name = if *is_final { "final" } else { "more" };
+            let id = format!("{}/{}", name, size);
+            transport_message(
+                c,
+                "fragment",
+                id,
+                zenoh_perf_codec::fragment(*size, *is_final),
+            );
+        }
+    }
+}
+
+fn declare(c: &mut Criterion) {
+    for count in [1, 10, 100].iter() {
+        let id = count.to_string();
+        zenoh_message(c, "declare", id, zenoh_perf_codec::declare(*count));
+    }
+}
+
+criterion_group!(benches, data, frame, fragment, declare);
+criterion_main!(benches);
@@ -0,0 +1,124 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The messages serialized and deserialized by the codec benchmarks,
+//! run them with `cargo bench -p zenoh-perf-codec`.
+use zenoh::net::protocol::core::{
+    Channel, CongestionControl, Priority, Reliability, ResKey, SubInfo, SubMode, ZInt,
+};
+use zenoh::net::protocol::io::{WBuf, ZBuf, ZSlice};
+use zenoh::net::protocol::proto::{
+    DataInfo, Declaration, FramePayload, Publisher, Queryable, Resource, Subscriber,
+    TransportMessage, ZenohMessage,
+};
+use zenoh::net::queryable::EVAL;
+
+/// The forms of `ResKey` to compare.
+pub const KEYS: [&str; 3] = ["rid", "rname", "rid-suffix"];
+
+pub fn reskey(form: &str) -> ResKey {
+    match form {
+        "rid" => ResKey::RId(1),
+        "rname" => ResKey::RName("/test/codec/thr".to_string()),
+        "rid-suffix" => ResKey::RIdWithSuffix(1, "/thr".to_string()),
+        _ => panic!("Unsupported ResKey form: {}", form),
+    }
+}
+
+pub fn channel() -> Channel {
+    Channel {
+        priority: Priority::Data,
+        reliability: Reliability::Reliable,
+    }
+}
+
+/// A `DataInfo` as set by a publisher of the zenoh API.
+pub fn data_info() -> DataInfo {
+    DataInfo {
+        kind: Some(0),
+        encoding: Some(0),
+        source_sn: Some(42),
+        ..Default::default()
+    }
+}
+
+pub fn data(form: &str, payload: usize, info: bool) -> ZenohMessage {
+    ZenohMessage::make_data(
+        reskey(form),
+        ZBuf::from(vec![0u8; payload]),
+        channel(),
+        CongestionControl::Block,
+        if info { Some(data_info()) } else { None },
+        None,
+        None,
+        None,
+    )
+}
+
+/// A frame batching `count` data messages.
+pub fn frame(count: usize, payload: usize) -> TransportMessage {
+    let messages = (0..count).map(|_| data("rid", payload, false)).collect();
+    TransportMessage::make_frame(channel(), 0, FramePayload::Messages { messages }, None)
+}
+
+/// A frame carrying a fragment of `size` bytes of a larger message.
+pub fn fragment(size: usize, is_final: bool) -> TransportMessage {
+    let buffer = ZSlice::from(vec![0u8; size]);
+    TransportMessage::make_frame(
+        channel(),
+        0,
+        FramePayload::Fragment { buffer, is_final },
+        None,
+    )
+}
+
+/// A declare message with `count` declarations of each kind.
+pub fn declare(count: usize) -> ZenohMessage {
+    let mut declarations = vec![];
+    for i in 0..count as ZInt {
+        let key = ResKey::RIdWithSuffix(i, format!("/test/codec/{}", i));
+        declarations.push(Declaration::Resource(Resource {
+            rid: i,
+            key: ResKey::RName(format!("/test/codec/{}", i)),
+        }));
+        declarations.push(Declaration::Publisher(Publisher { key: key.clone() }));
+        declarations.push(Declaration::Subscriber(Subscriber {
+            key: key.clone(),
+            info: SubInfo {
+                reliability: Reliability::Reliable,
+                mode: SubMode::Push,
+                period: None,
+            },
+        }));
+        declarations.push(Declaration::Queryable(Queryable { key, kind: EVAL }));
+    }
+    ZenohMessage::make_declare(declarations, None, None)
+}
+
+fn to_vec(wbuf: &WBuf) -> Vec<u8> {
+    let mut bytes = vec![0u8; wbuf.len()];
+    wbuf.copy_into_slice(&mut bytes[..]);
+    bytes
+}
+
+pub fn encode_zenoh(message: &mut ZenohMessage) -> Vec<u8> {
+    let mut wbuf = WBuf::new(64, false);
+    assert!(wbuf.write_zenoh_message(message));
+    to_vec(&wbuf)
+}
+
+pub fn encode_transport(message: &mut TransportMessage) -> Vec<u8> {
+    let mut wbuf = WBuf::new(64, false);
+    assert!(wbuf.write_transport_message(message));
+    to_vec(&wbuf)
+}
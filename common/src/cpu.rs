@@ -0,0 +1,50 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The CPU usage of a process, read from `/proc/<pid>/stat` on Linux.
+use std::time::Instant;
+
+// The operating system exposes the process times in ticks of 1/100 s
+const USER_HZ: f64 = 100.0;
+
+/// The CPU time (user + system) consumed so far by a process, in seconds.
+pub fn time(pid: u32) -> Option<f64> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // Skip the command name, it may contain spaces
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 2..].split(' ').collect();
+    let utime: f64 = fields.get(11)?.parse().ok()?;
+    let stime: f64 = fields.get(12)?.parse().ok()?;
+    Some((utime + stime) / USER_HZ)
+}
+
+/// The load of a process between two samples, 1.0 being one core busy.
+pub struct Load {
+    pid: u32,
+    last: (Instant, f64),
+}
+
+impl Load {
+    pub fn new(pid: u32) -> Option<Load> {
+        Some(Load {
+            pid,
+            last: (Instant::now(), time(pid)?),
+        })
+    }
+
+    pub fn sample(&mut self) -> Option<f64> {
+        let now = (Instant::now(), time(self.pid)?);
+        let load = (now.1 - self.last.1) / now.0.duration_since(self.last.0).as_secs_f64();
+        self.last = now;
+        Some(load)
+    }
+}
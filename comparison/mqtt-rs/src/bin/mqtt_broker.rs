@@ -0,0 +1,39 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::thread;
+use structopt::StructOpt;
+use zenoh_perf_mqtt::{broker, parse_locator, DEFAULT_LOCATOR};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_broker")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let addr = broker::start(parse_locator(&opt.locator)).unwrap();
+    println!("Broker listening on {}", addr);
+
+    // Stop forever
+    loop {
+        thread::park();
+    }
+}
@@ -0,0 +1,68 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use rumqttc::v5::mqttbytes::v5::PublishProperties;
+use std::sync::mpsc;
+use structopt::StructOpt;
+use zenoh_perf_mqtt::*;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_eval")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "q", long = "qos", default_value = "1")]
+    qos: u8,
+    #[structopt(long = "broker")]
+    broker: bool,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    maybe_start_broker(opt.broker, &opt.locator);
+    let qos = parse_qos(opt.qos);
+
+    // The event loop thread must never block on the client: hand over the queries
+    // to this thread which replies to them.
+    let (tx, rx) = mpsc::channel();
+    let client = connect("mqtt_eval", &opt.locator, move |p| {
+        tx.send(p.properties).unwrap();
+    });
+    client.subscribe(QUERY_TOPIC, qos).unwrap();
+
+    let data = vec![0u8; opt.payload];
+    while let Ok(properties) = rx.recv() {
+        let properties = match properties {
+            Some(properties) => properties,
+            None => continue,
+        };
+        let topic = match properties.response_topic {
+            Some(topic) => topic,
+            None => continue,
+        };
+        let reply = PublishProperties {
+            correlation_data: properties.correlation_data,
+            ..Default::default()
+        };
+        client
+            .publish_with_properties(topic, qos, false, data.clone(), reply)
+            .unwrap();
+    }
+}
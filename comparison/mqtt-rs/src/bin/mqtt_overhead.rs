@@ -0,0 +1,80 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use structopt::StructOpt;
+use zenoh_perf_mqtt::*;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_overhead")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "v", long = "verbose")]
+    print: bool,
+    #[structopt(short = "t", long = "total", default_value = "1048576")] //1MB in bytes
+    total: u64,
+    #[structopt(short = "i", long = "interval", default_value = "0")]
+    interval: f64,
+    #[structopt(short = "q", long = "qos", default_value = "1")]
+    qos: u8,
+    #[structopt(long = "broker")]
+    broker: bool,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let bytes_in_mb: u64 = 1048576;
+
+    maybe_start_broker(opt.broker, &opt.locator);
+    let client = connect("mqtt_overhead", &opt.locator, |_| {});
+    let qos = parse_qos(opt.qos);
+
+    let data: Vec<u8> = (0usize..opt.payload).map(|i| (i % 10) as u8).collect();
+
+    let tot: u64 = (opt.total * bytes_in_mb) / (opt.payload as u64);
+
+    let count = Arc::new(AtomicUsize::new(0));
+    if opt.print {
+        let c_count = count.clone();
+        thread::spawn(move || loop {
+            thread::sleep(Duration::from_secs(1));
+            let c = c_count.swap(0, Ordering::Relaxed);
+            if c > 0 {
+                println!("{} msg/s", c);
+            }
+        });
+    }
+
+    for _ in 0..tot {
+        client
+            .publish("/test/overhead", qos, false, data.clone())
+            .unwrap();
+        count.fetch_add(1, Ordering::Relaxed);
+        thread::sleep(Duration::from_secs_f64(opt.interval));
+    }
+
+    // Let the event loop flush the queued publications before leaving
+    client.disconnect().unwrap();
+    thread::sleep(Duration::from_secs(1));
+}
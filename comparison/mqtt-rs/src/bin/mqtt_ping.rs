@@ -0,0 +1,142 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh_perf_mqtt::*;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_ping")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(short = "i", long = "interval")]
+    interval: f64,
+    #[structopt(long = "parallel")]
+    parallel: bool,
+    #[structopt(short = "q", long = "qos", default_value = "1")]
+    qos: u8,
+    #[structopt(long = "broker")]
+    broker: bool,
+}
+
+fn make_ping(count: u64, payload: usize) -> Vec<u8> {
+    let mut data = vec![0u8; payload];
+    data[0..8].copy_from_slice(&count.to_le_bytes());
+    data
+}
+
+fn read_count(payload: &[u8]) -> u64 {
+    u64::from_le_bytes(payload[0..8].try_into().unwrap())
+}
+
+fn single(opt: Opt) {
+    let qos = parse_qos(opt.qos);
+
+    let (tx, rx) = mpsc::channel();
+    let client = connect("mqtt_ping", &opt.locator, move |p| {
+        tx.send(p.payload).unwrap();
+    });
+    // The topic to wait the response back
+    client.subscribe("/test/pong", qos).unwrap();
+
+    let sleep = Duration::from_secs_f64(opt.interval);
+    let mut count: u64 = 0;
+    loop {
+        let data = make_ping(count, opt.payload);
+
+        let now = Instant::now();
+        client.publish("/test/ping", qos, false, data).unwrap();
+
+        let payload = rx.recv().unwrap();
+        println!(
+            "mqtt,{},latency.sequential,{},{},{},{},{}",
+            opt.scenario,
+            opt.name,
+            payload.len(),
+            opt.interval,
+            read_count(&payload),
+            now.elapsed().as_micros()
+        );
+
+        thread::sleep(sleep);
+        count += 1;
+    }
+}
+
+fn parallel(opt: Opt) {
+    let qos = parse_qos(opt.qos);
+
+    // The hashmap with the pings
+    let pending = Arc::new(Mutex::new(HashMap::<u64, Instant>::new()));
+
+    let c_pending = pending.clone();
+    let scenario = opt.scenario.clone();
+    let name = opt.name.clone();
+    let interval = opt.interval;
+    let client = connect("mqtt_ping", &opt.locator, move |p| {
+        let count = read_count(&p.payload);
+        let instant = c_pending.lock().unwrap().remove(&count).unwrap();
+        println!(
+            "mqtt,{},latency.parallel,{},{},{},{},{}",
+            scenario,
+            name,
+            p.payload.len(),
+            interval,
+            count,
+            instant.elapsed().as_micros()
+        );
+    });
+    // The topic to wait the response back
+    client.subscribe("/test/pong", qos).unwrap();
+
+    let sleep = Duration::from_secs_f64(opt.interval);
+    let mut count: u64 = 0;
+    loop {
+        let data = make_ping(count, opt.payload);
+
+        pending.lock().unwrap().insert(count, Instant::now());
+        client.publish("/test/ping", qos, false, data).unwrap();
+
+        thread::sleep(sleep);
+        count += 1;
+    }
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+    assert!(opt.payload >= 8, "The payload must be at least 8 bytes");
+
+    maybe_start_broker(opt.broker, &opt.locator);
+
+    if opt.parallel {
+        parallel(opt);
+    } else {
+        single(opt);
+    }
+}
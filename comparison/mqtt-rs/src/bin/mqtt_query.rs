@@ -0,0 +1,93 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use bytes::Bytes;
+use rumqttc::v5::mqttbytes::v5::PublishProperties;
+use std::sync::mpsc;
+use std::time::Instant;
+use structopt::StructOpt;
+use zenoh_perf_mqtt::*;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_query")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(short = "r", long = "responders", default_value = "1")]
+    responders: usize,
+    #[structopt(short = "q", long = "qos", default_value = "1")]
+    qos: u8,
+    #[structopt(long = "broker")]
+    broker: bool,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    maybe_start_broker(opt.broker, &opt.locator);
+    let qos = parse_qos(opt.qos);
+
+    let (tx, rx) = mpsc::channel();
+    let client = connect("mqtt_query", &opt.locator, move |p| {
+        // Skip the messages not carrying a query id
+        if let Some(qid) = query_id(&p) {
+            tx.send((qid, p.payload.len())).unwrap();
+        }
+    });
+    // The topic to wait the replies back
+    let reply_topic = reply_topic();
+    client.subscribe(reply_topic.as_str(), qos).unwrap();
+
+    let mut count: u64 = 0;
+    loop {
+        let properties = PublishProperties {
+            response_topic: Some(reply_topic.clone()),
+            correlation_data: Some(Bytes::copy_from_slice(&count.to_le_bytes())),
+            ..Default::default()
+        };
+
+        let now = Instant::now();
+        client
+            .publish_with_properties(QUERY_TOPIC, qos, false, vec![], properties)
+            .unwrap();
+
+        // MQTT has no notion of final reply: wait for one reply per responder
+        let mut payload: usize = 0;
+        let mut replies: usize = 0;
+        while replies < opt.responders {
+            let (qid, len) = rx.recv().unwrap();
+            if qid == count {
+                payload += len;
+                replies += 1;
+            }
+        }
+        println!(
+            "mqtt,{},query.latency,{},{},{},{}",
+            opt.scenario,
+            opt.name,
+            payload,
+            count,
+            now.elapsed().as_micros()
+        );
+
+        count += 1;
+    }
+}
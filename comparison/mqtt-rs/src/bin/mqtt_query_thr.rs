@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use bytes::Bytes;
+use rumqttc::v5::mqttbytes::v5::PublishProperties;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh_perf_mqtt::*;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_query_thr")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(short = "r", long = "responders", default_value = "1")]
+    responders: usize,
+    #[structopt(short = "q", long = "qos", default_value = "1")]
+    qos: u8,
+    #[structopt(long = "broker")]
+    broker: bool,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    maybe_start_broker(opt.broker, &opt.locator);
+    let qos = parse_qos(opt.qos);
+
+    let (tx, rx) = mpsc::channel();
+    let client = connect("mqtt_query_thr", &opt.locator, move |p| {
+        // Skip the messages not carrying a query id
+        if let Some(qid) = query_id(&p) {
+            tx.send(qid).unwrap();
+        }
+    });
+    // The topic to wait the replies back
+    let reply_topic = reply_topic();
+    client.subscribe(reply_topic.as_str(), qos).unwrap();
+
+    let rtt = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::new(AtomicUsize::new(0));
+
+    let c_rtt = rtt.clone();
+    let c_counter = counter.clone();
+    let scenario = opt.scenario.clone();
+    let name = opt.name.clone();
+    let payload = opt.payload;
+    thread::spawn(move || loop {
+        let now = Instant::now();
+        thread::sleep(Duration::from_secs(1));
+        let elapsed = now.elapsed().as_micros() as f64;
+
+        let r = c_rtt.swap(0, Ordering::Relaxed);
+        let c = c_counter.swap(0, Ordering::Relaxed);
+        if c > 0 {
+            let interval = 1_000_000.0 / elapsed;
+            println!(
+                "mqtt,{},query.throughput,{},{},{},{}",
+                scenario,
+                name,
+                payload,
+                (c as f64 / interval).floor() as usize,
+                (r as f64 / c as f64).floor() as usize
+            );
+        }
+    });
+
+    let mut count: u64 = 0;
+    loop {
+        let properties = PublishProperties {
+            response_topic: Some(reply_topic.clone()),
+            correlation_data: Some(Bytes::copy_from_slice(&count.to_le_bytes())),
+            ..Default::default()
+        };
+
+        let now = Instant::now();
+        client
+            .publish_with_properties(QUERY_TOPIC, qos, false, vec![], properties)
+            .unwrap();
+
+        // MQTT has no notion of final reply: wait for one reply per responder
+        let mut replies: usize = 0;
+        while replies < opt.responders {
+            if rx.recv().unwrap() == count {
+                replies += 1;
+            }
+        }
+
+        rtt.fetch_add(now.elapsed().as_micros() as usize, Ordering::Relaxed);
+        counter.fetch_add(1, Ordering::Relaxed);
+        count += 1;
+    }
+}
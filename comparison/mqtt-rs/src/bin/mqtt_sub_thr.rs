@@ -0,0 +1,71 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh_perf_mqtt::*;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "mqtt_sub_thr")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = DEFAULT_LOCATOR)]
+    locator: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(short = "q", long = "qos", default_value = "1")]
+    qos: u8,
+    #[structopt(long = "broker")]
+    broker: bool,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    maybe_start_broker(opt.broker, &opt.locator);
+
+    let messages = Arc::new(AtomicUsize::new(0));
+    let c_messages = messages.clone();
+    let client = connect("mqtt_sub_thr", &opt.locator, move |_| {
+        c_messages.fetch_add(1, Ordering::Relaxed);
+    });
+    client.subscribe("/test/thr", parse_qos(opt.qos)).unwrap();
+
+    loop {
+        let now = Instant::now();
+        thread::sleep(Duration::from_secs(1));
+        let elapsed = now.elapsed().as_micros() as f64;
+
+        let c = messages.swap(0, Ordering::Relaxed);
+        if c > 0 {
+            let interval = 1_000_000.0 / elapsed;
+            println!(
+                "mqtt,{},throughput,{},{},{}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                (c as f64 / interval).floor() as usize
+            );
+        }
+    }
+}
@@ -0,0 +1,360 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+
+//! A minimal MQTT 5 broker to run the comparison without a mosquitto install.
+//!
+//! It implements what the benchmarks need: CONNECT, SUBSCRIBE/UNSUBSCRIBE, PUBLISH
+//! with QoS 0 and 1 (QoS 2 is accepted from publishers but delivered as QoS 1),
+//! PINGREQ and DISCONNECT. Publish properties (e.g. response topic and correlation
+//! data) are forwarded untouched. There is no session persistence nor retained messages.
+use bytes::BytesMut;
+use rumqttc::v5::mqttbytes::v5::{
+    ConnAck, ConnectReturnCode, Packet, PingResp, PubAck, PubAckReason, PubComp, PubCompReason,
+    PubRec, PubRecReason, Publish, SubAck, SubscribeReasonCode, UnsubAck, UnsubAckReason,
+};
+use rumqttc::v5::mqttbytes::{matches, Error, QoS};
+#[cfg(target_os = "linux")]
+use socket2::SockRef;
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+struct Client {
+    id: usize,
+    stream: Mutex<TcpStream>,
+    pkid: Mutex<u16>,
+}
+
+impl Client {
+    fn send(&self, packet: &Packet) -> io::Result<()> {
+        let mut bytes = BytesMut::new();
+        packet
+            .write(&mut bytes)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+        self.stream.lock().unwrap().write_all(&bytes)
+    }
+
+    fn next_pkid(&self) -> u16 {
+        let mut guard = self.pkid.lock().unwrap();
+        *guard = guard.wrapping_add(1).max(1);
+        *guard
+    }
+}
+
+struct Subscription {
+    filter: String,
+    qos: QoS,
+    client: Arc<Client>,
+}
+
+type Table = Arc<RwLock<Vec<Subscription>>>;
+
+fn min_qos(a: QoS, b: QoS) -> QoS {
+    match (a, b) {
+        (QoS::AtMostOnce, _) | (_, QoS::AtMostOnce) => QoS::AtMostOnce,
+        _ => QoS::AtLeastOnce,
+    }
+}
+
+fn route(table: &Table, publish: &Publish) {
+    let topic = match std::str::from_utf8(&publish.topic) {
+        Ok(topic) => topic,
+        Err(_) => return,
+    };
+    // Do not hold the table while writing to the sockets, a slow subscriber would
+    // block the (un)subscriptions of everybody else
+    let targets: Vec<(Arc<Client>, QoS)> = table
+        .read()
+        .unwrap()
+        .iter()
+        .filter(|s| matches(topic, &s.filter))
+        .map(|s| (s.client.clone(), min_qos(publish.qos, s.qos)))
+        .collect();
+    for (client, qos) in targets.into_iter() {
+        let pkid = match qos {
+            QoS::AtMostOnce => 0,
+            _ => client.next_pkid(),
+        };
+        let message = Publish {
+            dup: false,
+            qos,
+            retain: false,
+            topic: publish.topic.clone(),
+            pkid,
+            payload: publish.payload.clone(),
+            properties: publish.properties.clone(),
+        };
+        if let Err(e) = client.send(&Packet::Publish(message)) {
+            log::debug!("Failed to forward to client {}: {}", client.id, e);
+        }
+    }
+}
+
+fn handle(client: &Arc<Client>, table: &Table, packet: Packet) -> io::Result<bool> {
+    match packet {
+        Packet::Connect(..) => {
+            let ack = ConnAck {
+                session_present: false,
+                code: ConnectReturnCode::Success,
+                properties: None,
+            };
+            client.send(&Packet::ConnAck(ack))?;
+        }
+        Packet::Subscribe(subscribe) => {
+            let mut return_codes = vec![];
+            let mut guard = table.write().unwrap();
+            for f in subscribe.filters.into_iter() {
+                return_codes.push(SubscribeReasonCode::Success(f.qos));
+                guard.push(Subscription {
+                    filter: f.path,
+                    qos: f.qos,
+                    client: client.clone(),
+                });
+            }
+            drop(guard);
+            let ack = SubAck {
+                pkid: subscribe.pkid,
+                return_codes,
+                properties: None,
+            };
+            client.send(&Packet::SubAck(ack))?;
+        }
+        Packet::Unsubscribe(unsubscribe) => {
+            table
+                .write()
+                .unwrap()
+                .retain(|s| s.client.id != client.id || !unsubscribe.filters.contains(&s.filter));
+            let ack = UnsubAck {
+                pkid: unsubscribe.pkid,
+                reasons: vec![UnsubAckReason::Success; unsubscribe.filters.len()],
+                properties: None,
+            };
+            client.send(&Packet::UnsubAck(ack))?;
+        }
+        Packet::Publish(publish) => {
+            match publish.qos {
+                QoS::AtMostOnce => {}
+                QoS::AtLeastOnce => {
+                    let ack = PubAck {
+                        pkid: publish.pkid,
+                        reason: PubAckReason::Success,
+                        properties: None,
+                    };
+                    client.send(&Packet::PubAck(ack))?;
+                }
+                QoS::ExactlyOnce => {
+                    let rec = PubRec {
+                        pkid: publish.pkid,
+                        reason: PubRecReason::Success,
+                        properties: None,
+                    };
+                    client.send(&Packet::PubRec(rec))?;
+                }
+            }
+            route(table, &publish);
+        }
+        Packet::PubRel(rel) => {
+            let comp = PubComp {
+                pkid: rel.pkid,
+                reason: PubCompReason::Success,
+                properties: None,
+            };
+            client.send(&Packet::PubComp(comp))?;
+        }
+        Packet::PingReq(_) => client.send(&Packet::PingResp(PingResp))?,
+        Packet::Disconnect(_) => return Ok(false),
+        // Acknowledgements of the messages we forwarded
+        _ => {}
+    }
+    Ok(true)
+}
+
+fn serve(mut stream: TcpStream, id: usize, table: Table) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    let client = Arc::new(Client {
+        id,
+        stream: Mutex::new(stream.try_clone()?),
+        pkid: Mutex::new(0),
+    });
+
+    let mut buffer = BytesMut::with_capacity(65_536);
+    let mut chunk = vec![0u8; 65_536];
+    'outer: loop {
+        // Decode all the complete packets we have received so far
+        loop {
+            match Packet::read(&mut buffer, None) {
+                Ok(packet) => {
+                    if !handle(&client, &table, packet)? {
+                        break 'outer;
+                    }
+                }
+                Err(Error::InsufficientBytes(_)) => break,
+                Err(e) => return Err(io::Error::new(io::ErrorKind::InvalidData, e.to_string())),
+            }
+        }
+        // The clients do not disable Nagle: acknowledge immediately so that their
+        // small packets (e.g. a PUBACK followed by a reply) are not held back by
+        // the delayed ACKs of the broker.
+        #[cfg(target_os = "linux")]
+        let _ = SockRef::from(&stream).set_tcp_quickack(true);
+        let n = stream.read(&mut chunk)?;
+        if n == 0 {
+            break;
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    }
+    Ok(())
+}
+
+/// Start the broker on `addr` in background threads and return the bound address.
+pub fn start(addr: SocketAddr) -> io::Result<SocketAddr> {
+    let listener = TcpListener::bind(addr)?;
+    let local = listener.local_addr()?;
+    let table: Table = Arc::new(RwLock::new(vec![]));
+    let ids = AtomicUsize::new(0);
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let stream = match stream {
+                Ok(stream) => stream,
+                Err(e) => {
+                    log::warn!("Failed to accept connection: {}", e);
+                    continue;
+                }
+            };
+            let id = ids.fetch_add(1, Ordering::Relaxed);
+            let c_table = table.clone();
+            thread::spawn(move || {
+                if let Err(e) = serve(stream, id, c_table.clone()) {
+                    log::debug!("Client {} closed: {}", id, e);
+                }
+                c_table.write().unwrap().retain(|s| s.client.id != id);
+            });
+        }
+    });
+    Ok(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::connect;
+    use std::sync::mpsc::{self, Receiver};
+    use std::time::{Duration, Instant};
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn broker() -> String {
+        start("127.0.0.1:0".parse().unwrap()).unwrap().to_string()
+    }
+
+    fn subscriber(
+        locator: &str,
+        filter: &str,
+        qos: QoS,
+    ) -> (rumqttc::v5::Client, Receiver<Publish>) {
+        let (tx, rx) = mpsc::channel();
+        let client = connect("test-sub", locator, move |p| {
+            let _ = tx.send(p);
+        });
+        client.subscribe(filter, qos).unwrap();
+        (client, rx)
+    }
+
+    // Publish on `topic` until something is received, the subscription being
+    // processed asynchronously by the broker
+    fn first(
+        publisher: &rumqttc::v5::Client,
+        topic: &str,
+        qos: QoS,
+        rx: &Receiver<Publish>,
+    ) -> Publish {
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            publisher
+                .publish(topic, qos, false, vec![1u8, 2, 3])
+                .unwrap();
+            if let Ok(p) = rx.recv_timeout(Duration::from_millis(50)) {
+                return p;
+            }
+            assert!(Instant::now() < deadline, "Nothing received on {}", topic);
+        }
+    }
+
+    #[test]
+    fn publish_subscribe() {
+        let locator = broker();
+        let (_sub, rx) = subscriber(&locator, "/test/+", QoS::AtLeastOnce);
+        let publisher = connect("test-pub", &locator, |_| {});
+
+        let p = first(&publisher, "/test/a", QoS::AtLeastOnce, &rx);
+        assert_eq!(&p.topic[..], b"/test/a");
+        assert_eq!(&p.payload[..], &[1u8, 2, 3]);
+        assert_eq!(p.qos, QoS::AtLeastOnce);
+        assert_ne!(p.pkid, 0);
+
+        // Delivered with the lowest of the publication and subscription QoS
+        publisher
+            .publish("/test/b", QoS::AtMostOnce, false, vec![4u8])
+            .unwrap();
+        let p = loop {
+            let p = rx.recv_timeout(TIMEOUT).unwrap();
+            if &p.topic[..] == b"/test/b" {
+                break p;
+            }
+        };
+        assert_eq!((p.qos, p.pkid), (QoS::AtMostOnce, 0));
+    }
+
+    #[test]
+    fn unsubscribe() {
+        let locator = broker();
+        let (sub, rx) = subscriber(&locator, "/test/a", QoS::AtMostOnce);
+        let publisher = connect("test-pub", &locator, |_| {});
+        first(&publisher, "/test/a", QoS::AtMostOnce, &rx);
+
+        // The broker handles the packets of a client in order: once /test/b is
+        // delivered, /test/a is unsubscribed
+        sub.unsubscribe("/test/a").unwrap();
+        sub.subscribe("/test/b", QoS::AtMostOnce).unwrap();
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            publisher
+                .publish("/test/a", QoS::AtMostOnce, false, vec![0u8])
+                .unwrap();
+            publisher
+                .publish("/test/b", QoS::AtMostOnce, false, vec![0u8])
+                .unwrap();
+            match rx.recv_timeout(Duration::from_millis(50)) {
+                Ok(p) if &p.topic[..] == b"/test/b" => break,
+                Ok(_) => {}
+                Err(_) => assert!(Instant::now() < deadline, "Nothing received on /test/b"),
+            }
+        }
+        // From now on, only /test/b is delivered
+        publisher
+            .publish("/test/a", QoS::AtMostOnce, false, vec![0u8])
+            .unwrap();
+        publisher
+            .publish("/test/b", QoS::AtMostOnce, false, vec![0u8])
+            .unwrap();
+        let mut received = 0;
+        while let Ok(p) = rx.recv_timeout(Duration::from_millis(500)) {
+            assert_eq!(&p.topic[..], b"/test/b");
+            received += 1;
+        }
+        assert!(received > 0);
+    }
+}
@@ -0,0 +1,102 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+pub mod broker;
+
+use rumqttc::v5::mqttbytes::v5::{Packet, Publish};
+use rumqttc::v5::mqttbytes::QoS;
+use rumqttc::v5::{Client, Event, MqttOptions};
+use std::convert::TryInto;
+use std::net::SocketAddr;
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_LOCATOR: &str = "127.0.0.1:1883";
+
+/// Accepts both the zenoh locator form (`tcp/127.0.0.1:1883`) and a plain socket address.
+pub fn parse_locator(locator: &str) -> SocketAddr {
+    let addr = locator.strip_prefix("tcp/").unwrap_or(locator);
+    addr.parse()
+        .unwrap_or_else(|_| panic!("Invalid locator: {}", locator))
+}
+
+pub fn parse_qos(qos: u8) -> QoS {
+    match qos {
+        0 => QoS::AtMostOnce,
+        1 => QoS::AtLeastOnce,
+        2 => QoS::ExactlyOnce,
+        _ => panic!("Unsupported QoS: {}", qos),
+    }
+}
+
+/// The largest packet allowed by the MQTT remaining length encoding.
+pub const MAX_PACKET_SIZE: u32 = 268_435_455;
+
+/// MQTT client options shared by all the comparison binaries. The client id is made
+/// unique per process so that several instances can share the same broker.
+pub fn options(name: &str, locator: &str) -> MqttOptions {
+    let addr = parse_locator(locator);
+    let id = format!("{}-{}", name, std::process::id());
+    let mut options = MqttOptions::new(id, addr.ip().to_string(), addr.port());
+    options
+        .set_keep_alive(Duration::from_secs(5))
+        .set_clean_start(true)
+        .set_max_packet_size(Some(MAX_PACKET_SIZE));
+    options
+}
+
+/// Starts the embedded broker on `locator` when requested on the command line.
+pub fn maybe_start_broker(start: bool, locator: &str) {
+    if start {
+        let addr = broker::start(parse_locator(locator)).unwrap();
+        log::info!("Embedded broker listening on {}", addr);
+    }
+}
+
+/// Connects to the broker and drives the event loop in a background thread.
+/// Incoming publications are handed to `on_publish`.
+pub fn connect<F>(name: &str, locator: &str, mut on_publish: F) -> Client
+where
+    F: FnMut(Publish) + Send + 'static,
+{
+    let (client, mut connection) = Client::new(options(name, locator), 1024);
+    thread::spawn(move || {
+        for event in connection.iter() {
+            match event {
+                Ok(Event::Incoming(Packet::Publish(p))) => on_publish(p),
+                Ok(_) => {}
+                Err(e) => {
+                    log::warn!("MQTT connection closed: {}", e);
+                    break;
+                }
+            }
+        }
+    });
+    client
+}
+
+/// The topic the queries are published on, the responders subscribe to it.
+pub const QUERY_TOPIC: &str = "/test/query";
+
+/// The per-process topic where the responders publish the replies.
+pub fn reply_topic() -> String {
+    format!("/test/reply/{}", std::process::id())
+}
+
+/// The query id a reply correlates to, `None` when its correlation data is
+/// missing or is not an 8-byte id, e.g. when sent by another client.
+pub fn query_id(reply: &Publish) -> Option<u64> {
+    let data = reply.properties.as_ref()?.correlation_data.as_ref()?;
+    let id: [u8; 8] = data.as_ref().try_into().ok()?;
+    Some(u64::from_le_bytes(id))
+}
@@ -0,0 +1,268 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Runs each mqtt_* binary against its counterpart through the mqtt_broker
+//! binary on an ephemeral port.
+use bytes::Bytes;
+use rumqttc::v5::mqttbytes::v5::PublishProperties;
+use rumqttc::v5::mqttbytes::QoS;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+use zenoh_perf_mqtt::*;
+
+const TIMEOUT: Duration = Duration::from_secs(20);
+
+/// A binary killed when dropped, with its output lines.
+struct Process {
+    child: Child,
+    lines: Receiver<String>,
+}
+
+impl Process {
+    fn spawn(bin: &str, args: &[&str]) -> Process {
+        let mut child = Command::new(bin)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if tx.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+        Process { child, lines }
+    }
+
+    // Collect the first `count` lines matching `filter`, panic on timeout
+    fn take<F: Fn(&str) -> bool>(&self, count: usize, filter: F) -> Vec<String> {
+        let deadline = Instant::now() + TIMEOUT;
+        let mut output = vec![];
+        while output.len() < count {
+            match self
+                .lines
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(line) if filter(&line) => output.push(line),
+                Ok(_) => {}
+                Err(_) => panic!("Missing output, got only: {:?}", output),
+            }
+        }
+        output
+    }
+
+    // Collect the first `count` result lines of the given test, split in fields
+    fn test(&self, count: usize, test: &str) -> Vec<Vec<String>> {
+        self.take(count, |l| l.split(',').nth(2) == Some(test))
+            .iter()
+            .map(|l| l.split(',').map(|f| f.to_string()).collect())
+            .collect()
+    }
+
+    // Wait for the process to exit by itself, panic on timeout
+    fn status(&mut self) -> ExitStatus {
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                return status;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "Still running after {:?}",
+                TIMEOUT
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// The broker and the address it bound
+fn broker() -> (Process, String) {
+    let broker = Process::spawn(env!("CARGO_BIN_EXE_mqtt_broker"), &["-l", "127.0.0.1:0"]);
+    let line = broker
+        .take(1, |l| l.starts_with("Broker listening on "))
+        .remove(0);
+    let addr = line["Broker listening on ".len()..].to_string();
+    (broker, addr)
+}
+
+// Publish on `topic` until something comes back on `back`, the subscription of
+// the responder being processed asynchronously by the broker
+fn probe(locator: &str, topic: &str, back: &str, properties: PublishProperties) {
+    let (tx, rx) = mpsc::channel();
+    let client = connect("test-probe", locator, move |p| {
+        let _ = tx.send(p);
+    });
+    client.subscribe(back, QoS::AtLeastOnce).unwrap();
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        client
+            .publish_with_properties(
+                topic,
+                QoS::AtLeastOnce,
+                false,
+                vec![0u8; 8],
+                properties.clone(),
+            )
+            .unwrap();
+        if rx.recv_timeout(Duration::from_millis(50)).is_ok() {
+            break;
+        }
+        assert!(Instant::now() < deadline, "No response on {}", back);
+    }
+    client.disconnect().unwrap();
+}
+
+// A responder answering the queries, once it is subscribed
+fn eval(locator: &str) -> Process {
+    let eval = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_eval"),
+        &["-l", locator, "-p", "64"],
+    );
+    let properties = PublishProperties {
+        response_topic: Some("/test/probe".to_string()),
+        correlation_data: Some(Bytes::copy_from_slice(&0u64.to_le_bytes())),
+        ..Default::default()
+    };
+    probe(locator, QUERY_TOPIC, "/test/probe", properties);
+    eval
+}
+
+#[test]
+fn mqtt_pub_thr_mqtt_sub_thr() {
+    let (_broker, locator) = broker();
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_sub_thr"),
+        &["-l", &locator, "-p", "64", "-n", "pair", "-s", "test"],
+    );
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_pub_thr"),
+        &["-l", &locator, "-p", "64"],
+    );
+    for row in sub.test(3, "throughput").iter() {
+        assert_eq!(row.len(), 6, "Unexpected line: {:?}", row);
+        assert_eq!(row[4], "64");
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+    }
+}
+
+#[test]
+fn mqtt_ping_mqtt_pong() {
+    let (_broker, locator) = broker();
+    let _pong = Process::spawn(env!("CARGO_BIN_EXE_mqtt_pong"), &["-l", &locator]);
+    probe(
+        &locator,
+        "/test/ping",
+        "/test/pong",
+        PublishProperties::default(),
+    );
+    let ping = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_ping"),
+        &[
+            "-l", &locator, "-p", "64", "-n", "pair", "-s", "test", "-i", "0.01",
+        ],
+    );
+    for row in ping.test(5, "latency.sequential").iter() {
+        assert_eq!(row.len(), 8, "Unexpected line: {:?}", row);
+        assert_eq!(row[4], "64");
+        let _us: u128 = row[7].parse().unwrap();
+    }
+}
+
+#[test]
+fn mqtt_query_mqtt_eval() {
+    let (_broker, locator) = broker();
+    let _eval = eval(&locator);
+    let query = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_query"),
+        &["-l", &locator, "-n", "pair", "-s", "test"],
+    );
+    for row in query.test(5, "query.latency").iter() {
+        assert_eq!(row.len(), 7, "Unexpected line: {:?}", row);
+        assert_eq!(row[4], "64");
+        let _us: u128 = row[6].parse().unwrap();
+    }
+}
+
+#[test]
+fn mqtt_query_thr_mqtt_eval() {
+    let (_broker, locator) = broker();
+    let _eval = eval(&locator);
+    let query = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_query_thr"),
+        &["-l", &locator, "-p", "64", "-n", "pair", "-s", "test"],
+    );
+    for row in query.test(3, "query.throughput").iter() {
+        assert_eq!(row.len(), 7, "Unexpected line: {:?}", row);
+        assert_eq!(row[4], "64");
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+    }
+}
+
+// A reply whose correlation data is not a query id is skipped
+#[test]
+fn mqtt_query_short_correlation_data() {
+    let (_broker, locator) = broker();
+    let _eval = eval(&locator);
+    let mut query = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_query"),
+        &["-l", &locator, "-n", "pair", "-s", "test"],
+    );
+    query.test(1, "query.latency");
+
+    let client = connect("test-reply", &locator, |_| {});
+    let properties = PublishProperties {
+        correlation_data: Some(Bytes::from_static(&[1, 2])),
+        ..Default::default()
+    };
+    client
+        .publish_with_properties(
+            format!("/test/reply/{}", query.child.id()),
+            QoS::AtLeastOnce,
+            false,
+            vec![],
+            properties,
+        )
+        .unwrap();
+    client.disconnect().unwrap();
+
+    // The query keeps running, still matching its replies
+    thread::sleep(Duration::from_millis(500));
+    assert!(query.child.try_wait().unwrap().is_none(), "Query exited");
+    query.test(5, "query.latency");
+}
+
+#[test]
+fn mqtt_overhead() {
+    let (_broker, locator) = broker();
+    let mut publ = Process::spawn(
+        env!("CARGO_BIN_EXE_mqtt_overhead"),
+        &["-l", &locator, "-p", "1024", "-t", "1"],
+    );
+    assert!(publ.status().success());
+}
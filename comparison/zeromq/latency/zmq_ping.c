@@ -0,0 +1,160 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#include <zmq.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+#include <time.h>
+
+#define PING_TOPIC "/test/ping"
+#define PONG_TOPIC "/test/pong"
+
+int main(int argc, char *argv[])
+{
+    const char *payload_value = NULL;
+    const char *locator = NULL;
+    char *name = NULL;
+    char *scenario = NULL;
+    double interval = 1.0;
+    void *ctx = NULL;
+    void *s = NULL;
+    int rc = 0;
+    int c = 0;
+    size_t payload = 0;
+    u_int64_t count = 0;
+    zmq_msg_t msg;
+    struct timespec start, end;
+
+    // Parsing arguments
+    while ((c = getopt(argc, argv, ":l:p:n:s:i:")) != -1)
+    {
+        switch (c)
+        {
+        case 'n':
+            name = optarg;
+            break;
+        case 's':
+            scenario = optarg;
+            break;
+        case 'p':
+            payload_value = optarg;
+            break;
+        case 'l':
+            locator = optarg;
+            break;
+        case 'i':
+            interval = atof(optarg);
+            break;
+        default:
+            break;
+        }
+    }
+
+    if (locator == NULL || name == NULL || scenario == NULL || payload_value == NULL)
+    {
+        printf("Usage:\n\t./zmq_ping -l tcp://127.0.0.1:4505 -p 8 -n name -s scenario -i 1\n");
+        exit(EXIT_FAILURE);
+    }
+    payload = (size_t)atoi(payload_value);
+    if (payload < sizeof(u_int64_t))
+    {
+        printf("The payload must be at least %lu bytes\n", sizeof(u_int64_t));
+        exit(EXIT_FAILURE);
+    }
+
+    ctx = zmq_init(1);
+    if (!ctx)
+    {
+        printf("error in zmq_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    s = zmq_socket(ctx, ZMQ_PAIR);
+    if (!s)
+    {
+        printf("error in zmq_socket: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_connect(s, locator);
+    if (rc != 0)
+    {
+        printf("error in zmq_connect: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    char *data = (char *)malloc(payload);
+    memset(data, 0, payload);
+
+    rc = zmq_msg_init(&msg);
+    if (rc != 0)
+    {
+        printf("error in zmq_msg_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    while (1)
+    {
+        memcpy(data, &count, sizeof(u_int64_t));
+
+        clock_gettime(CLOCK_MONOTONIC_RAW, &start);
+        // TOPIC
+        rc = zmq_send(s, PING_TOPIC, sizeof(PING_TOPIC), ZMQ_SNDMORE);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        // DATA
+        rc = zmq_send(s, data, payload, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+
+        // TOPIC
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        if (strncmp((char *)zmq_msg_data(&msg), PONG_TOPIC, sizeof(PONG_TOPIC)) != 0)
+        {
+            printf("error in topic\n");
+            return -1;
+        }
+        // DATA
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        clock_gettime(CLOCK_MONOTONIC_RAW, &end);
+
+        u_int64_t s_count;
+        memcpy(&s_count, zmq_msg_data(&msg), sizeof(u_int64_t));
+        u_int64_t elapsed = (end.tv_sec - start.tv_sec) * 1000000 + (end.tv_nsec - start.tv_nsec) / 1000;
+        printf("zeromq,%s,latency.sequential,%s,%lu,%f,%lu,%lu\n", scenario, name, zmq_msg_size(&msg), interval, s_count, elapsed);
+        fflush(stdout);
+
+        usleep((useconds_t)(interval * 1000000));
+        count += 1;
+    }
+
+    return 0;
+}
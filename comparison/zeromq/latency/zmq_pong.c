@@ -0,0 +1,116 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#include <zmq.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#define PING_TOPIC "/test/ping"
+#define PONG_TOPIC "/test/pong"
+
+int main(int argc, char *argv[])
+{
+    const char *locator = NULL;
+    void *ctx = NULL;
+    void *s = NULL;
+    int rc = 0;
+    int c = 0;
+    zmq_msg_t msg;
+
+    // Parsing arguments
+    while ((c = getopt(argc, argv, ":l:")) != -1)
+    {
+        switch (c)
+        {
+        case 'l':
+            locator = optarg;
+            break;
+        default:
+            break;
+        }
+    }
+
+    if (locator == NULL)
+    {
+        printf("Usage:\n\t./zmq_pong -l tcp://127.0.0.1:4505\n");
+        exit(EXIT_FAILURE);
+    }
+
+    ctx = zmq_init(1);
+    if (!ctx)
+    {
+        printf("error in zmq_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    s = zmq_socket(ctx, ZMQ_PAIR);
+    if (!s)
+    {
+        printf("error in zmq_socket: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_bind(s, locator);
+    if (rc != 0)
+    {
+        printf("error in zmq_bind: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_msg_init(&msg);
+    if (rc != 0)
+    {
+        printf("error in zmq_msg_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    while (1)
+    {
+        // TOPIC
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        if (strncmp((char *)zmq_msg_data(&msg), PING_TOPIC, sizeof(PING_TOPIC)) != 0)
+        {
+            printf("error in topic\n");
+            return -1;
+        }
+
+        // DATA, echoed back as is
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        rc = zmq_send(s, PONG_TOPIC, sizeof(PONG_TOPIC), ZMQ_SNDMORE);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        rc = zmq_sendmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_sendmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+    }
+
+    return 0;
+}
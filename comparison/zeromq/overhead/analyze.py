@@ -0,0 +1,116 @@
+import json
+import argparse
+
+ZMTP_SIGNATURE_LEN = 10
+ZMTP_GREETING_LEN = 64
+
+FLAG_MORE = 0x01
+FLAG_LONG = 0x02
+FLAG_COMMAND = 0x04
+
+
+def read_json(filename):
+    f = open(filename, 'r')
+    data = json.load(f)
+    f.close()
+    return data
+
+
+def field(layers, name):
+    if name in layers:
+        return layers[name][0]
+    return None
+
+
+def parse_zmtp(stream):
+    # Parse a reassembled ZMTP 3.x byte stream (one direction of a connection).
+    # Returns the greeting/command bytes and the list of messages, each message
+    # being the list of (frame_len, body_len) of its frames.
+    handshake = 0
+    messages = []
+    frames = []
+    pos = 0
+
+    if len(stream) < ZMTP_GREETING_LEN or stream[0] != 0xff or stream[ZMTP_SIGNATURE_LEN - 1] != 0x7f:
+        return (0, [])
+    handshake += ZMTP_GREETING_LEN
+    pos += ZMTP_GREETING_LEN
+
+    while pos < len(stream):
+        flags = stream[pos]
+        if flags & FLAG_LONG:
+            if pos + 9 > len(stream):
+                break
+            size = int.from_bytes(stream[pos + 1:pos + 9], 'big')
+            header = 9
+        else:
+            if pos + 2 > len(stream):
+                break
+            size = stream[pos + 1]
+            header = 2
+        if pos + header + size > len(stream):
+            # Truncated capture
+            break
+        pos += header + size
+
+        if flags & FLAG_COMMAND:
+            handshake += header + size
+            continue
+        frames.append((header + size, size))
+        if not flags & FLAG_MORE:
+            messages.append(frames)
+            frames = []
+
+    return (handshake, messages)
+
+
+def main(datafile):
+    data = read_json(datafile)
+
+    total_frame_size = 0
+    total_ip_size = 0
+    total_tcp_size = 0
+    streams = {}
+
+    for p in data:
+        layers = p['_source']['layers']
+        total_frame_size += int(field(layers, 'frame.len'))
+        total_ip_size += int(field(layers, 'ip.len'))
+        payload = field(layers, 'tcp.payload')
+        if payload is None:
+            continue
+        payload = bytes.fromhex(payload.replace(':', ''))
+        total_tcp_size += len(payload)
+        key = (field(layers, 'tcp.srcport'), field(layers, 'tcp.dstport'))
+        streams.setdefault(key, bytearray()).extend(payload)
+
+    total_zmtp_size = 0
+    total_zmtp_data_size = 0
+    total_handshake_size = 0
+    total_payload = 0
+    data_msg = 0
+
+    for stream in streams.values():
+        (handshake, messages) = parse_zmtp(stream)
+        total_handshake_size += handshake
+        total_zmtp_size += handshake
+        for m in messages:
+            size = sum(f[0] for f in m)
+            total_zmtp_size += size
+            total_zmtp_data_size += size
+            # The data is carried by the last frame, the previous ones
+            # being the topic or the envelope
+            total_payload += m[-1][1]
+            data_msg += 1
+
+    payload = int(total_payload / data_msg) if data_msg > 0 else 0
+
+    print('protocol,total_wire,total_ip,total_tcp,total_zmtp,data_zmtp,total_payload,payload,handshake_zmtp,messages')
+    print(f"zeromq,{total_frame_size},{total_ip_size},{total_tcp_size},{total_zmtp_size},{total_zmtp_data_size},{total_payload},{payload},{total_handshake_size},{data_msg}")
+
+
+if __name__=='__main__':
+    parser = argparse.ArgumentParser(description='ZeroMQ Overhead Analyzer')
+    parser.add_argument('data', help='JSON data file to be analyzed')
+    args = parser.parse_args()
+    main(args.data)
@@ -0,0 +1,3 @@
+#!/usr/bin/env bash
+
+sudo tcpdump -i lo "port ${1:-4505}" -w zmq_overhead.pcap
\ No newline at end of file
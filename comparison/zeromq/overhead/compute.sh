@@ -0,0 +1,6 @@
+#!/usr/bin/env bash
+
+# ZMTP is not dissected by tshark unless explicitly decoded, hence the raw
+# TCP payloads are exported and the framing is parsed by analyze.py
+tshark -r $1 -l -n -T json -e frame.len -e ip.len -e tcp.srcport -e tcp.dstport -e tcp.payload "tcp" > data.json
+python3 analyze.py data.json
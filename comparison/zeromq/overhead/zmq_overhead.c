@@ -0,0 +1,165 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#include <zmq.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <pthread.h>
+#include <unistd.h>
+
+#define TOPIC "/test/overhead"
+
+u_int64_t counter = 0;
+
+void *counter_thread(void *arg)
+{
+    u_int64_t n;
+    u_int64_t zero = 0;
+    while (1)
+    {
+        sleep(1);
+        __atomic_exchange(&counter, &zero, &n, __ATOMIC_RELAXED);
+        if (n > 0)
+        {
+            printf("%lu msg/s\n", n);
+            fflush(stdout);
+        }
+    }
+}
+
+int main(int argc, char *argv[])
+{
+    const char *payload_value = NULL;
+    const char *locator = NULL;
+    void *ctx = NULL;
+    void *s = NULL;
+    int rc = 0;
+    int c = 0;
+    int verbose = 0;
+    size_t payload = 0;
+    u_int64_t total = 1;
+    double interval = 0.0;
+    u_int64_t bytes_in_mb = 1048576;
+
+    // Parsing arguments
+    while ((c = getopt(argc, argv, ":l:p:t:i:v")) != -1)
+    {
+        switch (c)
+        {
+        case 'p':
+            payload_value = optarg;
+            break;
+        case 'l':
+            locator = optarg;
+            break;
+        case 't':
+            total = (u_int64_t)atol(optarg);
+            break;
+        case 'i':
+            interval = atof(optarg);
+            break;
+        case 'v':
+            verbose = 1;
+            break;
+        default:
+            break;
+        }
+    }
+
+    if (locator == NULL || payload_value == NULL)
+    {
+        printf("Usage:\n\t./zmq_overhead -l tcp://127.0.0.1:4505 -p 8 [-t 1] [-i 0] [-v]\n");
+        exit(EXIT_FAILURE);
+    }
+    payload = (size_t)atoi(payload_value);
+    // The number of messages is the total divided by the payload
+    if (payload == 0)
+    {
+        printf("The payload must be at least 1 byte\n");
+        exit(EXIT_FAILURE);
+    }
+
+    ctx = zmq_init(1);
+    if (!ctx)
+    {
+        printf("error in zmq_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    s = zmq_socket(ctx, ZMQ_PUSH);
+    if (!s)
+    {
+        printf("error in zmq_socket: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    // Make sure all the queued messages are delivered before closing
+    int linger = -1;
+    rc = zmq_setsockopt(s, ZMQ_LINGER, &linger, sizeof(linger));
+    if (rc != 0)
+    {
+        printf("error in zmq_setsockopt: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_connect(s, locator);
+    if (rc != 0)
+    {
+        printf("error in zmq_connect: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    if (verbose)
+    {
+        pthread_t *thread = (pthread_t *)malloc(sizeof(pthread_t));
+        memset(thread, 0, sizeof(pthread_t));
+        rc = pthread_create(thread, NULL, counter_thread, NULL);
+        if (rc != 0)
+        {
+            printf("error in pthread_create: %d\n", rc);
+            return -1;
+        }
+    }
+
+    char *data = (char *)malloc(payload);
+    for (size_t i = 0; i < payload; i++)
+    {
+        data[i] = (char)(i % 10);
+    }
+
+    u_int64_t tot = (total * bytes_in_mb) / payload;
+    for (u_int64_t i = 0; i < tot; i++)
+    {
+        // TOPIC
+        rc = zmq_send(s, TOPIC, sizeof(TOPIC), ZMQ_SNDMORE);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        // DATA
+        rc = zmq_send(s, data, payload, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        __atomic_fetch_add(&counter, 1, __ATOMIC_RELAXED);
+        usleep((useconds_t)(interval * 1000000));
+    }
+
+    zmq_close(s);
+    zmq_ctx_term(ctx);
+    return 0;
+}
@@ -0,0 +1,125 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#include <zmq.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+
+#define QUERY_SELECTOR "/test/query"
+
+int main(int argc, char *argv[])
+{
+    const char *payload_value = NULL;
+    const char *locator = NULL;
+    void *ctx = NULL;
+    void *s = NULL;
+    int rc = 0;
+    int c = 0;
+    size_t payload = 0;
+    zmq_msg_t msg;
+
+    // Parsing arguments
+    while ((c = getopt(argc, argv, ":l:p:")) != -1)
+    {
+        switch (c)
+        {
+        case 'p':
+            payload_value = optarg;
+            break;
+        case 'l':
+            locator = optarg;
+            break;
+        default:
+            break;
+        }
+    }
+
+    if (locator == NULL || payload_value == NULL)
+    {
+        printf("Usage:\n\t./zmq_eval -l tcp://127.0.0.1:4505 -p 8\n");
+        exit(EXIT_FAILURE);
+    }
+    payload = (size_t)atoi(payload_value);
+
+    ctx = zmq_init(1);
+    if (!ctx)
+    {
+        printf("error in zmq_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    s = zmq_socket(ctx, ZMQ_REP);
+    if (!s)
+    {
+        printf("error in zmq_socket: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_bind(s, locator);
+    if (rc != 0)
+    {
+        printf("error in zmq_bind: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_msg_init(&msg);
+    if (rc != 0)
+    {
+        printf("error in zmq_msg_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    char *data = (char *)malloc(payload);
+    memset(data, 0, payload);
+
+    while (1)
+    {
+        // SELECTOR
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        if (strncmp((char *)zmq_msg_data(&msg), QUERY_SELECTOR, sizeof(QUERY_SELECTOR)) != 0)
+        {
+            printf("error in selector\n");
+            return -1;
+        }
+
+        // PREDICATE, drain all the remaining frames of the query
+        int more = zmq_msg_more(&msg);
+        while (more)
+        {
+            rc = zmq_recvmsg(s, &msg, 0);
+            if (rc < 0)
+            {
+                printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+                return -1;
+            }
+            more = zmq_msg_more(&msg);
+        }
+
+        // REPLY
+        rc = zmq_send(s, data, payload, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+    }
+
+    return 0;
+}
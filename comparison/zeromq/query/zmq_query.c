@@ -0,0 +1,124 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#include <zmq.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <unistd.h>
+#include <time.h>
+
+#define QUERY_SELECTOR "/test/query"
+
+int main(int argc, char *argv[])
+{
+    const char *locator = NULL;
+    char *name = NULL;
+    char *scenario = NULL;
+    void *ctx = NULL;
+    void *s = NULL;
+    int rc = 0;
+    int c = 0;
+    u_int64_t count = 0;
+    zmq_msg_t msg;
+    struct timespec start, end;
+
+    // Parsing arguments
+    while ((c = getopt(argc, argv, ":l:n:s:")) != -1)
+    {
+        switch (c)
+        {
+        case 'n':
+            name = optarg;
+            break;
+        case 's':
+            scenario = optarg;
+            break;
+        case 'l':
+            locator = optarg;
+            break;
+        default:
+            break;
+        }
+    }
+
+    if (locator == NULL || name == NULL || scenario == NULL)
+    {
+        printf("Usage:\n\t./zmq_query -l tcp://127.0.0.1:4505 -n name -s scenario\n");
+        exit(EXIT_FAILURE);
+    }
+
+    ctx = zmq_init(1);
+    if (!ctx)
+    {
+        printf("error in zmq_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    s = zmq_socket(ctx, ZMQ_REQ);
+    if (!s)
+    {
+        printf("error in zmq_socket: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_connect(s, locator);
+    if (rc != 0)
+    {
+        printf("error in zmq_connect: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_msg_init(&msg);
+    if (rc != 0)
+    {
+        printf("error in zmq_msg_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    while (1)
+    {
+        clock_gettime(CLOCK_MONOTONIC_RAW, &start);
+        // SELECTOR
+        rc = zmq_send(s, QUERY_SELECTOR, sizeof(QUERY_SELECTOR), ZMQ_SNDMORE);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        // PREDICATE
+        rc = zmq_send(s, NULL, 0, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+
+        // REPLY
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        clock_gettime(CLOCK_MONOTONIC_RAW, &end);
+
+        u_int64_t elapsed = (end.tv_sec - start.tv_sec) * 1000000 + (end.tv_nsec - start.tv_nsec) / 1000;
+        printf("zeromq,%s,query.latency,%s,%lu,%lu,%lu\n", scenario, name, zmq_msg_size(&msg), count, elapsed);
+        fflush(stdout);
+
+        count += 1;
+    }
+
+    return 0;
+}
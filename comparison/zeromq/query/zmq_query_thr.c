@@ -0,0 +1,163 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+#include <zmq.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+#include <pthread.h>
+#include <unistd.h>
+#include <time.h>
+
+#define QUERY_SELECTOR "/test/query"
+
+int payload;
+char *name = NULL;
+char *scenario = NULL;
+u_int64_t counter = 0;
+u_int64_t rtt = 0;
+
+void *counter_thread(void *arg)
+{
+    u_int64_t n, r;
+    u_int64_t zero = 0;
+    struct timespec start, end;
+    while (1)
+    {
+        clock_gettime(CLOCK_MONOTONIC_RAW, &start);
+        sleep(1);
+        clock_gettime(CLOCK_MONOTONIC_RAW, &end);
+        u_int64_t elapsed = (end.tv_sec - start.tv_sec) * 1000000 + (end.tv_nsec - start.tv_nsec) / 1000;
+
+        __atomic_exchange(&rtt, &zero, &r, __ATOMIC_RELAXED);
+        __atomic_exchange(&counter, &zero, &n, __ATOMIC_RELAXED);
+        if (n > 0)
+        {
+            printf("zeromq,%s,query.throughput,%s,%d,%lu,%lu\n", scenario, name, payload, (n * 1000000) / elapsed, r / n);
+            fflush(stdout);
+        }
+    }
+}
+
+int main(int argc, char *argv[])
+{
+    const char *payload_value = NULL;
+    const char *locator = NULL;
+    void *ctx = NULL;
+    void *s = NULL;
+    int rc = 0;
+    int c = 0;
+    zmq_msg_t msg;
+    struct timespec start, end;
+
+    // Parsing arguments
+    while ((c = getopt(argc, argv, ":l:p:n:s:")) != -1)
+    {
+        switch (c)
+        {
+        case 'n':
+            name = optarg;
+            break;
+        case 's':
+            scenario = optarg;
+            break;
+        case 'p':
+            payload_value = optarg;
+            break;
+        case 'l':
+            locator = optarg;
+            break;
+        default:
+            break;
+        }
+    }
+
+    if (locator == NULL || name == NULL || scenario == NULL || payload_value == NULL)
+    {
+        printf("Usage:\n\t./zmq_query_thr -l tcp://127.0.0.1:4505 -p 8 -n name -s scenario\n");
+        exit(EXIT_FAILURE);
+    }
+    payload = atoi(payload_value);
+
+    ctx = zmq_init(1);
+    if (!ctx)
+    {
+        printf("error in zmq_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    s = zmq_socket(ctx, ZMQ_REQ);
+    if (!s)
+    {
+        printf("error in zmq_socket: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_connect(s, locator);
+    if (rc != 0)
+    {
+        printf("error in zmq_connect: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    rc = zmq_msg_init(&msg);
+    if (rc != 0)
+    {
+        printf("error in zmq_msg_init: %s\n", zmq_strerror(errno));
+        return -1;
+    }
+
+    // Spawn the counter task
+    pthread_t *thread = (pthread_t *)malloc(sizeof(pthread_t));
+    memset(thread, 0, sizeof(pthread_t));
+    rc = pthread_create(thread, NULL, counter_thread, NULL);
+    if (rc != 0)
+    {
+        printf("error in pthread_create: %d\n", rc);
+        return -1;
+    }
+
+    while (1)
+    {
+        clock_gettime(CLOCK_MONOTONIC_RAW, &start);
+        // SELECTOR
+        rc = zmq_send(s, QUERY_SELECTOR, sizeof(QUERY_SELECTOR), ZMQ_SNDMORE);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        // PREDICATE
+        rc = zmq_send(s, NULL, 0, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_send: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+
+        // REPLY
+        rc = zmq_recvmsg(s, &msg, 0);
+        if (rc < 0)
+        {
+            printf("error in zmq_recvmsg: %s\n", zmq_strerror(errno));
+            return -1;
+        }
+        clock_gettime(CLOCK_MONOTONIC_RAW, &end);
+
+        u_int64_t elapsed = (end.tv_sec - start.tv_sec) * 1000000 + (end.tv_nsec - start.tv_nsec) / 1000;
+        __atomic_fetch_add(&rtt, elapsed, __ATOMIC_RELAXED);
+        __atomic_fetch_add(&counter, 1, __ATOMIC_RELAXED);
+    }
+
+    return 0;
+}
@@ -25,8 +25,10 @@ use zenoh::net::protocol::io::{WBuf, ZBuf};
 use zenoh::net::protocol::proto::{DataInfo, RoutingContext};
 use zenoh::net::runtime::Runtime;
 use zenoh::net::transport::Primitives;
+use zenoh_perf_latency::pong;
+use zenoh_perf_throughput::ready;
 use zenoh_util::properties::config::{
-    ConfigProperties, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
+    ConfigProperties, ZN_LISTENER_KEY, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
 
 // Primitives for the non-blocking locator
@@ -229,6 +231,10 @@ struct Opt {
     interval: f64,
     #[structopt(long = "parallel")]
     parallel: bool,
+    #[structopt(long = "loopback")]
+    loopback: bool,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
 }
 
 async fn parallel(opt: Opt, config: ConfigProperties) {
@@ -344,6 +350,21 @@ async fn main() {
     config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
     config.insert(ZN_PEER_KEY, opt.locator.clone());
 
+    // Run the pong in this process, listening on the locator
+    let _pong = if opt.loopback {
+        let mut config = ConfigProperties::default();
+        config.insert(ZN_MODE_KEY, "peer".to_string());
+        config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+        config.insert(ZN_LISTENER_KEY, opt.locator.clone());
+        Some(pong::router(config).await)
+    } else {
+        None
+    };
+
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     if opt.parallel {
         parallel(opt, config).await;
     } else {
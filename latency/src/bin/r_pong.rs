@@ -12,112 +12,14 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use async_std::future;
-use std::sync::{Arc, Mutex};
+use std::path::PathBuf;
 use structopt::StructOpt;
-use zenoh::net::protocol::core::{
-    Channel, CongestionControl, PeerId, QueryConsolidation, QueryTarget, Reliability, ResKey,
-    SubInfo, SubMode, ZInt,
-};
-use zenoh::net::protocol::io::ZBuf;
-use zenoh::net::protocol::proto::{DataInfo, RoutingContext};
-use zenoh::net::routing::face::Face;
-use zenoh::net::runtime::Runtime;
-use zenoh::net::transport::Primitives;
+use zenoh_perf_latency::pong;
+use zenoh_perf_throughput::ready;
 use zenoh_util::properties::config::{
     ConfigProperties, ZN_LISTENER_KEY, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
 
-struct LatencyPrimitives {
-    tx: Mutex<Option<Arc<Face>>>,
-}
-
-impl LatencyPrimitives {
-    fn new() -> LatencyPrimitives {
-        LatencyPrimitives {
-            tx: Mutex::new(None),
-        }
-    }
-
-    fn set_tx(&self, tx: Arc<Face>) {
-        let mut guard = self.tx.lock().unwrap();
-        *guard = Some(tx);
-    }
-}
-
-impl Primitives for LatencyPrimitives {
-    fn decl_resource(&self, _rid: ZInt, _reskey: &ResKey) {}
-    fn forget_resource(&self, _rid: ZInt) {}
-    fn decl_publisher(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
-    fn forget_publisher(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
-    fn decl_subscriber(
-        &self,
-        _reskey: &ResKey,
-        _sub_info: &SubInfo,
-        _routing_context: Option<RoutingContext>,
-    ) {
-    }
-    fn forget_subscriber(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
-    fn decl_queryable(
-        &self,
-        _reskey: &ResKey,
-        _kind: ZInt,
-        _routing_context: Option<RoutingContext>,
-    ) {
-    }
-    fn forget_queryable(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
-
-    fn send_data(
-        &self,
-        _reskey: &ResKey,
-        payload: ZBuf,
-        channel: Channel,
-        congestion_control: CongestionControl,
-        data_info: Option<DataInfo>,
-        routing_context: Option<RoutingContext>,
-    ) {
-        let reskey = ResKey::RName("/test/pong".to_string());
-        self.tx.lock().unwrap().as_ref().unwrap().send_data(
-            &reskey,
-            payload,
-            channel,
-            congestion_control,
-            data_info,
-            routing_context,
-        );
-    }
-
-    fn send_query(
-        &self,
-        _reskey: &ResKey,
-        _predicate: &str,
-        _qid: ZInt,
-        _target: QueryTarget,
-        _consolidation: QueryConsolidation,
-        _routing_context: Option<RoutingContext>,
-    ) {
-    }
-    fn send_reply_data(
-        &self,
-        _qid: ZInt,
-        _source_kind: ZInt,
-        _replier_id: PeerId,
-        _reskey: ResKey,
-        _info: Option<DataInfo>,
-        _payload: ZBuf,
-    ) {
-    }
-    fn send_reply_final(&self, _qid: ZInt) {}
-    fn send_pull(
-        &self,
-        _is_final: bool,
-        _reskey: &ResKey,
-        _pull_id: ZInt,
-        _max_samples: &Option<ZInt>,
-    ) {
-    }
-    fn send_close(&self) {}
-}
-
 #[derive(Debug, StructOpt)]
 #[structopt(name = "r_sub_thr")]
 struct Opt {
@@ -125,6 +27,8 @@ struct Opt {
     locator: String,
     #[structopt(short = "m", long = "mode")]
     mode: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -140,23 +44,20 @@ async fn main() {
 
     config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
     match opt.mode.as_str() {
-        "peer" | "router" => config.insert(ZN_LISTENER_KEY, opt.locator),
-        "client" => config.insert(ZN_PEER_KEY, opt.locator),
+        "peer" | "router" => {
+            ready::remove_stale(&opt.locator);
+            config.insert(ZN_LISTENER_KEY, opt.locator.clone())
+        }
+        "client" => config.insert(ZN_PEER_KEY, opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let runtime = Runtime::new(0u8, config, None).await.unwrap();
-    let rx_primitives = Arc::new(LatencyPrimitives::new());
-    let tx_primitives = runtime.router.new_primitives(rx_primitives.clone());
-    rx_primitives.set_tx(tx_primitives.clone());
+    let runtime = pong::router(config).await;
 
-    let rid = ResKey::RName("/test/ping".to_string());
-    let sub_info = SubInfo {
-        reliability: Reliability::Reliable,
-        mode: SubMode::Push,
-        period: None,
-    };
-    tx_primitives.decl_subscriber(&rid, &sub_info, None);
+    if opt.mode != "client" {
+        let locator = ready::listening(&runtime, &opt.locator);
+        ready::announce(&locator, opt.ready_file.as_deref());
+    }
 
     // Stop forever
     future::pending::<()>().await;
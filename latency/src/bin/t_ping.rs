@@ -14,6 +14,7 @@
 use async_std::task;
 use std::any::Any;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Barrier, Mutex};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
@@ -24,7 +25,10 @@ use zenoh::net::protocol::core::{
 use zenoh::net::protocol::io::{WBuf, ZBuf};
 use zenoh::net::protocol::proto::{Data, ZenohBody, ZenohMessage};
 use zenoh::net::transport::*;
+use zenoh_perf_latency::pong;
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
+use zenoh_util::properties::{IntKeyProperties, Properties};
 
 // Transport Handler for the non-blocking locator
 struct MySHParallel {
@@ -208,17 +212,41 @@ struct Opt {
     interval: f64,
     #[structopt(long = "parallel")]
     parallel: bool,
+    #[structopt(long = "loopback")]
+    loopback: bool,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
-async fn single(opt: Opt, whatami: WhatAmI) {
+async fn builder(
+    properties: Option<&IntKeyProperties>,
+    whatami: WhatAmI,
+) -> TransportManagerConfigBuilder {
+    match properties {
+        Some(int_props) => TransportManagerConfig::builder()
+            .from_config(int_props)
+            .await
+            .unwrap(),
+        None => TransportManagerConfig::builder().whatami(whatami),
+    }
+}
+
+async fn single(opt: Opt, whatami: WhatAmI, properties: Option<IntKeyProperties>) {
     let pending: Arc<Mutex<HashMap<u64, Arc<Barrier>>>> = Arc::new(Mutex::new(HashMap::new()));
-    let config = TransportManagerConfig::builder()
-        .whatami(whatami)
+    let config = builder(properties.as_ref(), whatami)
+        .await
         .build(Arc::new(MySHSequential::new(pending.clone())));
     let manager = TransportManager::new(config);
 
     // Connect to publisher
-    let session = manager.open_transport(opt.locator).await.unwrap();
+    let session = ready::retry(
+        Duration::from_secs_f64(opt.wait_ready.unwrap_or(0.0)),
+        || manager.open_transport(opt.locator.clone()),
+    )
+    .await
+    .unwrap();
 
     let sleep = Duration::from_secs_f64(opt.interval);
     let payload = vec![0u8; opt.payload - 8];
@@ -275,10 +303,10 @@ async fn single(opt: Opt, whatami: WhatAmI) {
     }
 }
 
-async fn parallel(opt: Opt, whatami: WhatAmI) {
+async fn parallel(opt: Opt, whatami: WhatAmI, properties: Option<IntKeyProperties>) {
     let pending: Arc<Mutex<HashMap<u64, Instant>>> = Arc::new(Mutex::new(HashMap::new()));
-    let config = TransportManagerConfig::builder()
-        .whatami(whatami)
+    let config = builder(properties.as_ref(), whatami)
+        .await
         .build(Arc::new(MySHParallel::new(
             opt.scenario,
             opt.name,
@@ -288,7 +316,12 @@ async fn parallel(opt: Opt, whatami: WhatAmI) {
     let manager = TransportManager::new(config);
 
     // Connect to publisher
-    let session = manager.open_transport(opt.locator).await.unwrap();
+    let session = ready::retry(
+        Duration::from_secs_f64(opt.wait_ready.unwrap_or(0.0)),
+        || manager.open_transport(opt.locator.clone()),
+    )
+    .await
+    .unwrap();
 
     let sleep = Duration::from_secs_f64(opt.interval);
     let payload = vec![0u8; opt.payload - 8];
@@ -343,9 +376,25 @@ async fn main() {
 
     let whatami = whatami::parse(opt.mode.as_str()).unwrap();
 
+    let properties = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            let properties = Properties::from(config);
+            Some(IntKeyProperties::from(properties))
+        }
+        None => None,
+    };
+
+    // Run the pong in this process, listening on the locator
+    let _pong = if opt.loopback {
+        Some(pong::session(opt.locator.clone(), whatami::PEER, properties.as_ref()).await)
+    } else {
+        None
+    };
+
     if opt.parallel {
-        parallel(opt, whatami).await;
+        parallel(opt, whatami, properties).await;
     } else {
-        single(opt, whatami).await;
+        single(opt, whatami, properties).await;
     }
 }
@@ -12,68 +12,13 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use async_std::future;
-use async_std::sync::Arc;
-use std::any::Any;
+use std::path::PathBuf;
 use structopt::StructOpt;
-use zenoh::net::link::{EndPoint, Link};
+use zenoh::net::link::EndPoint;
 use zenoh::net::protocol::core::whatami;
-use zenoh::net::protocol::proto::ZenohMessage;
-use zenoh::net::transport::{
-    TransportEventHandler, TransportManager, TransportManagerConfig, TransportMulticast,
-    TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler, TransportUnicast,
-};
-use zenoh_util::core::ZResult;
-
-// Transport Handler for the peer
-struct MySH;
-
-impl MySH {
-    fn new() -> Self {
-        Self
-    }
-}
-
-impl TransportEventHandler for MySH {
-    fn new_unicast(
-        &self,
-        _peer: TransportPeer,
-        transport: TransportUnicast,
-    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
-        Ok(Arc::new(MyMH::new(transport)))
-    }
-
-    fn new_multicast(
-        &self,
-        _transport: TransportMulticast,
-    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
-        panic!();
-    }
-}
-
-// Message Handler for the peer
-struct MyMH {
-    session: TransportUnicast,
-}
-
-impl MyMH {
-    fn new(session: TransportUnicast) -> Self {
-        Self { session }
-    }
-}
-
-impl TransportPeerEventHandler for MyMH {
-    fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
-        self.session.handle_message(message)
-    }
-
-    fn new_link(&self, _link: Link) {}
-    fn del_link(&self, _link: Link) {}
-    fn closing(&self) {}
-    fn closed(&self) {}
-    fn as_any(&self) -> &dyn Any {
-        self
-    }
-}
+use zenoh_perf_latency::pong;
+use zenoh_perf_throughput::ready;
+use zenoh_util::properties::{IntKeyProperties, Properties};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "s_sub_thr")]
@@ -82,6 +27,10 @@ struct Opt {
     locator: EndPoint,
     #[structopt(short = "m", long = "mode")]
     mode: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -94,16 +43,23 @@ async fn main() {
 
     let whatami = whatami::parse(opt.mode.as_str()).unwrap();
 
-    let config = TransportManagerConfig::builder()
-        .whatami(whatami)
-        .build(Arc::new(MySH::new()));
-    let manager = TransportManager::new(config);
+    let properties = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            let properties = Properties::from(config);
+            Some(IntKeyProperties::from(properties))
+        }
+        None => None,
+    };
 
-    // Connect to the peer or listen
+    let requested = opt.locator.to_string();
+    if whatami == whatami::PEER {
+        ready::remove_stale(&requested);
+    }
+    let manager = pong::session(opt.locator.clone(), whatami, properties.as_ref()).await;
     if whatami == whatami::PEER {
-        manager.add_listener(opt.locator).await.unwrap();
-    } else {
-        let _session = manager.open_transport(opt.locator).await.unwrap();
+        let locator = ready::bound(&requested, &manager.get_locators());
+        ready::announce(&locator, opt.ready_file.as_deref());
     }
 
     // Stop forever
@@ -25,6 +25,7 @@ use zenoh::net::transport::{
     TransportManagerConfig, TransportMulticast, TransportMulticastEventHandler, TransportPeer,
     TransportPeerEventHandler, TransportUnicast,
 };
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 
 struct MySH {}
@@ -63,6 +64,8 @@ struct Opt {
     payload: usize,
     #[structopt(short = "i", long = "interval")]
     interval: f64,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
 }
 
 #[async_std::main]
@@ -81,7 +84,12 @@ async fn main() {
     let manager = TransportManager::new(config);
 
     // Connect to publisher
-    let session = manager.open_transport(opt.locator).await.unwrap();
+    let session = ready::retry(
+        Duration::from_secs_f64(opt.wait_ready.unwrap_or(0.0)),
+        || manager.open_transport(opt.locator.clone()),
+    )
+    .await
+    .unwrap();
 
     let mut count: u64 = 0;
     loop {
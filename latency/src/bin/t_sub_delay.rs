@@ -14,12 +14,14 @@
 use async_std::future;
 use async_std::sync::Arc;
 use std::any::Any;
+use std::path::PathBuf;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use structopt::StructOpt;
 use zenoh::net::link::{EndPoint, Link};
 use zenoh::net::protocol::core::whatami;
 use zenoh::net::protocol::proto::{Data, ZenohBody, ZenohMessage};
 use zenoh::net::transport::*;
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 
 // Transport Handler for the peer
@@ -99,6 +101,8 @@ struct Opt {
     locator: EndPoint,
     #[structopt(short = "m", long = "mode")]
     mode: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -118,9 +122,15 @@ async fn main() {
 
     // Connect to the peer or listen
     if whatami == whatami::PEER {
-        manager.add_listener(opt.locator).await.unwrap();
+        let requested = opt.locator.to_string();
+        ready::remove_stale(&requested);
+        let bound = manager.add_listener(opt.locator.clone()).await.unwrap();
+        ready::announce(
+            &ready::bound(&requested, &[bound]),
+            opt.ready_file.as_deref(),
+        );
     } else {
-        let _session = manager.open_transport(opt.locator).await.unwrap();
+        let _session = manager.open_transport(opt.locator.clone()).await.unwrap();
     }
 
     // Stop forever
@@ -16,9 +16,12 @@ use async_std::sync::{Arc, Barrier, Mutex};
 use async_std::task;
 use std::collections::HashMap;
 use std::convert::TryInto;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::*;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::value::{self, Kind};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "z_ping")]
@@ -37,6 +40,12 @@ struct Opt {
     interval: f64,
     #[structopt(long = "parallel")]
     parallel: bool,
+    #[structopt(long = "value", default_value = "raw")]
+    value: Kind,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 async fn parallel(opt: Opt, config: Properties) {
@@ -64,25 +73,19 @@ async fn parallel(opt: Opt, config: Properties) {
         c_barrier.wait().await;
 
         while let Some(change) = sub.next().await {
-            match change.value.unwrap() {
-                Value::Raw(_, mut payload) => {
-                    let mut count_bytes = [0u8; 8];
-                    payload.read_bytes(&mut count_bytes);
-                    let count = u64::from_le_bytes(count_bytes);
-
-                    let instant = c_pending.lock().await.remove(&count).unwrap();
-                    println!(
-                        "zenoh,{},latency.parallel,{},{},{},{},{}",
-                        scenario,
-                        name,
-                        payload.len(),
-                        interval,
-                        count,
-                        instant.elapsed().as_micros()
-                    );
-                }
-                _ => panic!("Invalid value"),
-            }
+            let value = change.value.unwrap();
+            let count = value::seq(&value).expect("Invalid value");
+
+            let instant = c_pending.lock().await.remove(&count).unwrap();
+            println!(
+                "zenoh,{},latency.parallel,{},{},{},{},{}",
+                scenario,
+                name,
+                value::len(&value),
+                interval,
+                count,
+                instant.elapsed().as_micros()
+            );
         }
     });
 
@@ -92,14 +95,12 @@ async fn parallel(opt: Opt, config: Properties) {
     let workspace = zenoh.workspace(None).await.unwrap();
     let mut count: u64 = 0;
     loop {
-        let count_bytes: [u8; 8] = count.to_le_bytes();
-        let mut payload = vec![0u8; opt.payload];
-        payload[0..8].copy_from_slice(&count_bytes);
+        let value = value::generate(opt.value, opt.payload, count);
 
         pending.lock().await.insert(count, Instant::now());
 
         workspace
-            .put(&"/test/ping".try_into().unwrap(), payload.into())
+            .put(&"/test/ping".try_into().unwrap(), value)
             .await
             .unwrap();
 
@@ -123,34 +124,25 @@ async fn single(opt: Opt, config: Properties) {
 
     let mut count: u64 = 0;
     loop {
-        let count_bytes: [u8; 8] = count.to_le_bytes();
-        let mut payload = vec![0u8; opt.payload];
-        payload[0..8].copy_from_slice(&count_bytes);
+        let value = value::generate(opt.value, opt.payload, count);
 
         let now = Instant::now();
         workspace
-            .put(&"/test/ping".try_into().unwrap(), payload.into())
+            .put(&"/test/ping".try_into().unwrap(), value)
             .await
             .unwrap();
 
-        match sub.next().await.unwrap().value.unwrap() {
-            Value::Raw(_, mut payload) => {
-                let mut count_bytes = [0u8; 8];
-                payload.read_bytes(&mut count_bytes);
-                let s_count = u64::from_le_bytes(count_bytes);
-
-                println!(
-                    "zenoh,{},latency.sequential,{},{},{},{},{}",
-                    scenario,
-                    name,
-                    payload.len(),
-                    interval,
-                    s_count,
-                    now.elapsed().as_micros()
-                );
-            }
-            _ => panic!("Invalid value"),
-        }
+        let value = sub.next().await.unwrap().value.unwrap();
+        let s_count = value::seq(&value).expect("Invalid value");
+        println!(
+            "zenoh,{},latency.sequential,{},{},{},{},{}",
+            scenario,
+            name,
+            value::len(&value),
+            interval,
+            s_count,
+            now.elapsed().as_micros()
+        );
 
         task::sleep(Duration::from_secs_f64(opt.interval)).await;
         count += 1;
@@ -165,7 +157,13 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     if opt.locator.is_none() {
@@ -175,6 +173,10 @@ async fn main() {
         config.insert("peer".to_string(), opt.locator.clone().unwrap());
     }
 
+    if let (Some(timeout), Some(locator)) = (opt.wait_ready, opt.locator.as_ref()) {
+        ready::wait(locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     if opt.parallel {
         parallel(opt, config).await;
     } else {
@@ -14,8 +14,11 @@
 use async_std::future;
 use async_std::stream::StreamExt;
 use std::convert::TryInto;
+use std::path::PathBuf;
 use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
 use zenoh::*;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "z_pong")]
@@ -24,6 +27,10 @@ struct Opt {
     locator: String,
     #[structopt(short = "m", long = "mode")]
     mode: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -34,32 +41,45 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
     match opt.mode.as_str() {
-        "peer" => config.insert("listener".to_string(), opt.locator),
-        "client" => config.insert("peer".to_string(), opt.locator),
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let zenoh = Zenoh::new(config.into()).await.unwrap();
+    // Open the zenoh instance over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let zenoh = Zenoh::init(runtime).await;
     let workspace = zenoh.workspace(None).await.unwrap();
     let mut sub = workspace
         .subscribe(&"/test/ping/".to_string().try_into().unwrap())
         .await
         .unwrap();
 
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+    // Echo the values whatever their type
     while let Some(change) = sub.next().await {
-        match change.value.unwrap() {
-            Value::Raw(_, payload) => {
-                workspace
-                    .put(&"/test/pong".try_into().unwrap(), payload.into())
-                    .wait()
-                    .unwrap();
-            }
-            _ => panic!("Invalid value"),
+        if let Some(value) = change.value {
+            workspace
+                .put(&"/test/pong".try_into().unwrap(), value)
+                .wait()
+                .unwrap();
         }
     }
 
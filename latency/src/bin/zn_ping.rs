@@ -13,12 +13,15 @@
 //
 use async_std::task;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::{Arc, Barrier, Mutex};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::ResKey::*;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_latency::pong;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_ping")]
@@ -39,6 +42,12 @@ struct Opt {
     parallel: bool,
     #[structopt(short = "d", long = "samples")]
     samples: Option<usize>,
+    #[structopt(long = "loopback")]
+    loopback: bool,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 async fn single(opt: Opt, config: Properties) {
@@ -266,12 +275,31 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
     config.insert("peer".to_string(), opt.locator.clone());
 
+    // Run the pong in this process, listening on the locator
+    if opt.loopback {
+        let mut config = config.clone();
+        config.insert("mode".to_string(), "peer".to_string());
+        config.remove("peer");
+        config.insert("listener".to_string(), opt.locator.clone());
+        pong::net(config).await;
+    }
+
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     if opt.parallel {
         parallel(opt, config).await;
         return;
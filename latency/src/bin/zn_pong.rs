@@ -12,10 +12,11 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use async_std::future;
+use std::path::PathBuf;
 use structopt::StructOpt;
-use zenoh::net::ResKey::*;
-use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_latency::pong;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_pong")]
@@ -24,6 +25,10 @@ struct Opt {
     locator: String,
     #[structopt(short = "m", long = "mode")]
     mode: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -34,51 +39,30 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
     match opt.mode.as_str() {
-        "peer" => config.insert("listener".to_string(), opt.locator),
-        "client" => config.insert("peer".to_string(), opt.locator),
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let session = open(config.into()).await.unwrap();
-
-    // The resource to echo the data back
-    let reskey_pong = RId(session
-        .declare_resource(&RName("/test/pong".to_string()))
-        .await
-        .unwrap());
-    let _publ = session.declare_publisher(&reskey_pong).await.unwrap();
-
-    // The resource to read the data from
-    let reskey_ping = RId(session
-        .declare_resource(&RName("/test/ping".to_string()))
-        .await
-        .unwrap());
-    let sub_info = SubInfo {
-        reliability: Reliability::Reliable,
-        mode: SubMode::Push,
-        period: None,
-    };
+    let runtime = pong::net(config).await;
 
-    let mut sub = session
-        .declare_subscriber(&reskey_ping, &sub_info)
-        .await
-        .unwrap();
-    while let Ok(sample) = sub.receiver().recv() {
-        session
-            .write_ext(
-                &reskey_pong,
-                sample.payload,
-                encoding::DEFAULT,
-                data_kind::DEFAULT,
-                CongestionControl::Block, // Make sure to not drop messages because of congestion control
-            )
-            .wait()
-            .unwrap();
+    if opt.mode == "peer" {
+        let locator = ready::listening(&runtime, &opt.locator);
+        ready::announce(&locator, opt.ready_file.as_deref());
     }
 
     // Stop forever
@@ -0,0 +1,281 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The echo side of the ping/pong latency tests, shared between the pong
+//! binaries and the `--loopback` mode of the ping binaries.
+use async_std::sync::Arc;
+use async_std::task;
+use std::any::Any;
+use std::sync::{Barrier, Mutex};
+use zenoh::net::link::{EndPoint, Link};
+use zenoh::net::protocol::core::{
+    whatami, Channel, CongestionControl, PeerId, QueryConsolidation, QueryTarget, Reliability,
+    ResKey, SubInfo, SubMode, WhatAmI, ZInt,
+};
+use zenoh::net::protocol::io::ZBuf;
+use zenoh::net::protocol::proto::{DataInfo, RoutingContext, ZenohMessage};
+use zenoh::net::routing::face::Face;
+use zenoh::net::runtime::Runtime;
+use zenoh::net::transport::{
+    Primitives, TransportEventHandler, TransportManager, TransportManagerConfig,
+    TransportMulticast, TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler,
+    TransportUnicast,
+};
+use zenoh::net::{data_kind, encoding, Session};
+use zenoh::Properties;
+use zenoh_util::core::ZResult;
+use zenoh_util::properties::config::ConfigProperties;
+use zenoh_util::properties::IntKeyProperties;
+
+// Transport Handler for the peer
+struct MySH;
+
+impl MySH {
+    fn new() -> Self {
+        Self
+    }
+}
+
+impl TransportEventHandler for MySH {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(MyMH::new(transport)))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+// Message Handler for the peer
+struct MyMH {
+    session: TransportUnicast,
+}
+
+impl MyMH {
+    fn new(session: TransportUnicast) -> Self {
+        Self { session }
+    }
+}
+
+impl TransportPeerEventHandler for MyMH {
+    fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
+        self.session.handle_message(message)
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Echoes back every message received by a transport manager, either
+/// listening on or connecting to `locator` depending on `whatami`. The
+/// manager is configured from `properties` when given.
+pub async fn session(
+    locator: EndPoint,
+    whatami: WhatAmI,
+    properties: Option<&IntKeyProperties>,
+) -> TransportManager {
+    let bc = match properties {
+        Some(int_props) => TransportManagerConfig::builder()
+            .from_config(int_props)
+            .await
+            .unwrap(),
+        None => TransportManagerConfig::builder().whatami(whatami),
+    };
+    let config = bc.build(Arc::new(MySH::new()));
+    let manager = TransportManager::new(config);
+
+    // Connect to the peer or listen
+    if whatami == whatami::PEER {
+        manager.add_listener(locator).await.unwrap();
+    } else {
+        let _session = manager.open_transport(locator).await.unwrap();
+    }
+    manager
+}
+
+struct LatencyPrimitives {
+    tx: Mutex<Option<Arc<Face>>>,
+}
+
+impl LatencyPrimitives {
+    fn new() -> LatencyPrimitives {
+        LatencyPrimitives {
+            tx: Mutex::new(None),
+        }
+    }
+
+    fn set_tx(&self, tx: Arc<Face>) {
+        let mut guard = self.tx.lock().unwrap();
+        *guard = Some(tx);
+    }
+}
+
+impl Primitives for LatencyPrimitives {
+    fn decl_resource(&self, _rid: ZInt, _reskey: &ResKey) {}
+    fn forget_resource(&self, _rid: ZInt) {}
+    fn decl_publisher(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
+    fn forget_publisher(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
+    fn decl_subscriber(
+        &self,
+        _reskey: &ResKey,
+        _sub_info: &SubInfo,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn forget_subscriber(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
+    fn decl_queryable(
+        &self,
+        _reskey: &ResKey,
+        _kind: ZInt,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn forget_queryable(&self, _reskey: &ResKey, _routing_context: Option<RoutingContext>) {}
+
+    fn send_data(
+        &self,
+        _reskey: &ResKey,
+        payload: ZBuf,
+        channel: Channel,
+        congestion_control: CongestionControl,
+        data_info: Option<DataInfo>,
+        routing_context: Option<RoutingContext>,
+    ) {
+        let reskey = ResKey::RName("/test/pong".to_string());
+        self.tx.lock().unwrap().as_ref().unwrap().send_data(
+            &reskey,
+            payload,
+            channel,
+            congestion_control,
+            data_info,
+            routing_context,
+        );
+    }
+
+    fn send_query(
+        &self,
+        _reskey: &ResKey,
+        _predicate: &str,
+        _qid: ZInt,
+        _target: QueryTarget,
+        _consolidation: QueryConsolidation,
+        _routing_context: Option<RoutingContext>,
+    ) {
+    }
+    fn send_reply_data(
+        &self,
+        _qid: ZInt,
+        _source_kind: ZInt,
+        _replier_id: PeerId,
+        _reskey: ResKey,
+        _info: Option<DataInfo>,
+        _payload: ZBuf,
+    ) {
+    }
+    fn send_reply_final(&self, _qid: ZInt) {}
+    fn send_pull(
+        &self,
+        _is_final: bool,
+        _reskey: &ResKey,
+        _pull_id: ZInt,
+        _max_samples: &Option<ZInt>,
+    ) {
+    }
+    fn send_close(&self) {}
+}
+
+/// Echoes back on `/test/pong` every data received on `/test/ping`, through
+/// a face of a router runtime.
+pub async fn router(config: ConfigProperties) -> Runtime {
+    let runtime = Runtime::new(0u8, config, None).await.unwrap();
+    let rx_primitives = Arc::new(LatencyPrimitives::new());
+    let tx_primitives = runtime.router.new_primitives(rx_primitives.clone());
+    rx_primitives.set_tx(tx_primitives.clone());
+
+    let rid = ResKey::RName("/test/ping".to_string());
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    tx_primitives.decl_subscriber(&rid, &sub_info, None);
+    runtime
+}
+
+/// Echoes back on `/test/pong` every sample received on `/test/ping`, through
+/// a zenoh-net session running on its own thread. Returns the runtime of the
+/// session, to read back its locators, once the subscriber is declared.
+pub async fn net(config: Properties) -> Runtime {
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let ready = Arc::new(Barrier::new(2));
+    let (c_ready, c_runtime) = (ready.clone(), runtime.clone());
+    std::thread::spawn(move || {
+        task::block_on(async move {
+            let session = Session::init(c_runtime, true, vec![], vec![]).await;
+
+            // The resource to echo the data back
+            let reskey_pong = ResKey::RId(
+                session
+                    .declare_resource(&ResKey::RName("/test/pong".to_string()))
+                    .await
+                    .unwrap(),
+            );
+            let _publ = session.declare_publisher(&reskey_pong).await.unwrap();
+
+            // The resource to read the data from
+            let reskey_ping = ResKey::RId(
+                session
+                    .declare_resource(&ResKey::RName("/test/ping".to_string()))
+                    .await
+                    .unwrap(),
+            );
+            let sub_info = SubInfo {
+                reliability: Reliability::Reliable,
+                mode: SubMode::Push,
+                period: None,
+            };
+
+            let mut sub = session
+                .declare_subscriber(&reskey_ping, &sub_info)
+                .await
+                .unwrap();
+            c_ready.wait();
+            while let Ok(sample) = sub.receiver().recv() {
+                session
+                    .write_ext(
+                        &reskey_pong,
+                        sample.payload,
+                        encoding::DEFAULT,
+                        data_kind::DEFAULT,
+                        CongestionControl::Block, // Make sure to not drop messages because of congestion control
+                    )
+                    .await
+                    .unwrap();
+            }
+        })
+    });
+    task::spawn_blocking(move || ready.wait()).await;
+    runtime
+}
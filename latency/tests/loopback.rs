@@ -0,0 +1,93 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Smoke tests running the pings with an in-process pong.
+use zenoh_perf_throughput::testing::*;
+
+fn check(layer: &str, rows: &[Vec<String>]) {
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(row.len(), 8, "Unexpected line: {:?}", row);
+        assert_eq!(row[0], layer);
+        assert_eq!(row[6].parse::<usize>().unwrap(), i);
+        let _rtt: u128 = row[7].parse().unwrap();
+    }
+}
+
+#[test]
+fn t_ping_loopback() {
+    let ping = Process::spawn(
+        env!("CARGO_BIN_EXE_t_ping"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "smoke",
+            "-s",
+            "loopback",
+            "-i",
+            "0.01",
+            "--loopback",
+        ],
+    );
+    check("session", &ping.test(10, "latency.sequential"));
+}
+
+#[test]
+fn r_ping_loopback() {
+    let ping = Process::spawn(
+        env!("CARGO_BIN_EXE_r_ping"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "smoke",
+            "-s",
+            "loopback",
+            "-i",
+            "0.01",
+            "--loopback",
+        ],
+    );
+    check("router", &ping.test(10, "latency.sequential"));
+}
+
+#[test]
+fn zn_ping_loopback() {
+    let ping = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_ping"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "smoke",
+            "-s",
+            "loopback",
+            "-i",
+            "0.01",
+            "--loopback",
+        ],
+    );
+    check("zenoh-net", &ping.test(10, "latency.sequential"));
+}
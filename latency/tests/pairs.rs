@@ -0,0 +1,75 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each ping against its pong on an ephemeral port.
+use zenoh_perf_throughput::testing::*;
+
+fn check(layer: &str, rows: &[Vec<String>]) {
+    for (i, row) in rows.iter().enumerate() {
+        assert_eq!(row.len(), 8, "Unexpected line: {:?}", row);
+        assert_eq!(row[0], layer);
+        assert_eq!(row[6].parse::<usize>().unwrap(), i);
+        let _rtt: u128 = row[7].parse().unwrap();
+    }
+}
+
+fn ping(ping: &str, pong: &str) -> Vec<Vec<String>> {
+    let pong = Process::spawn(pong, &["-l", "tcp/127.0.0.1:0", "-m", "peer"]);
+    let locator = pong.ready();
+    let ping = Process::spawn(
+        ping,
+        &[
+            "-l", &locator, "-m", "peer", "-p", "64", "-n", "pair", "-s", "test", "-i", "0.01",
+        ],
+    );
+    ping.test(10, "latency.sequential")
+}
+
+#[test]
+fn t_ping_t_pong() {
+    let rows = ping(env!("CARGO_BIN_EXE_t_ping"), env!("CARGO_BIN_EXE_t_pong"));
+    check("session", &rows);
+}
+
+#[test]
+fn r_ping_r_pong() {
+    let rows = ping(env!("CARGO_BIN_EXE_r_ping"), env!("CARGO_BIN_EXE_r_pong"));
+    check("router", &rows);
+}
+
+#[test]
+fn zn_ping_zn_pong() {
+    let rows = ping(env!("CARGO_BIN_EXE_zn_ping"), env!("CARGO_BIN_EXE_zn_pong"));
+    check("zenoh-net", &rows);
+}
+
+#[test]
+fn z_ping_z_pong() {
+    let rows = ping(env!("CARGO_BIN_EXE_z_ping"), env!("CARGO_BIN_EXE_z_pong"));
+    check("zenoh", &rows);
+}
+
+#[test]
+fn t_pub_delay_t_sub_delay() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_t_sub_delay"),
+        &["-l", "tcp/127.0.0.1:0", "-m", "peer"],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pub_delay"),
+        &["-l", &locator, "-m", "peer", "-p", "64", "-i", "0.01"],
+    );
+    let lines = sub.take(10, |l| l.contains(" bytes: seq="));
+    assert_eq!(lines.len(), 10);
+}
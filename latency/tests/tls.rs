@@ -0,0 +1,144 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each ping against its pong over TLS or QUIC with throwaway
+//! certificates, next to plain TCP for every payload size.
+#![cfg(any(feature = "tls", feature = "quic"))]
+use std::path::Path;
+use zenoh_perf_throughput::certs;
+use zenoh_perf_throughput::testing::*;
+
+const PAYLOADS: [&str; 3] = ["64", "8192", "65536"];
+
+// The latency lines of a pair, the pong listening on `locator`
+fn run(ping: &str, pong: &str, locator: &str, conf: &Path, payload: &str) -> Vec<String> {
+    let conf = conf.to_str().unwrap();
+    let pong = Process::spawn(pong, &["-l", locator, "-m", "peer", "--conf", conf]);
+    let locator = pong.ready();
+    let ping = Process::spawn(
+        ping,
+        &[
+            "-l", &locator, "-m", "peer", "-p", payload, "-n", "pair", "-s", "test", "-i", "0.01",
+            "--conf", conf,
+        ],
+    );
+    ping.lines(10, "latency.sequential")
+}
+
+// Round trip times in microseconds, the last value of the latency lines
+fn rtts(lines: &[String]) -> Vec<u128> {
+    lines
+        .iter()
+        .map(|l| l.rsplit(',').next().unwrap().parse().unwrap())
+        .collect()
+}
+
+fn median(lines: &[String]) -> u128 {
+    let mut rtts = rtts(lines);
+    rtts.sort_unstable();
+    rtts[rtts.len() / 2]
+}
+
+// Prints the round trip times side by side and saves both series for `zperf-compare`
+fn compare(proto: &str, ping: &str, pong: &str) {
+    let conf = certs::generate(&certs::temp_dir());
+    let (mut secure, mut tcp) = (vec![], vec![]);
+    println!("payload,tcp rtt us,{} rtt us", proto);
+    for payload in PAYLOADS.iter() {
+        let s = run(
+            ping,
+            pong,
+            &format!("{}/localhost:0", proto),
+            &conf,
+            payload,
+        );
+        let t = run(ping, pong, "tcp/127.0.0.1:0", &conf, payload);
+        assert!(
+            rtts(&s).iter().chain(rtts(&t).iter()).all(|rtt| *rtt > 0),
+            "Null round trip for {} B: {:?} over {}, {:?} over TCP",
+            payload,
+            s,
+            proto,
+            t
+        );
+        println!("{},{},{}", payload, median(&t), median(&s));
+        secure.extend(s);
+        tcp.extend(t);
+    }
+    let name = Path::new(ping).file_stem().unwrap().to_str().unwrap();
+    println!(
+        "zperf-compare -b {} -c {}",
+        save(&format!("{}-tcp", name), &tcp).display(),
+        save(&format!("{}-{}", name, proto), &secure).display()
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn t_ping_t_pong_tls() {
+    compare(
+        "tls",
+        env!("CARGO_BIN_EXE_t_ping"),
+        env!("CARGO_BIN_EXE_t_pong"),
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn zn_ping_zn_pong_tls() {
+    compare(
+        "tls",
+        env!("CARGO_BIN_EXE_zn_ping"),
+        env!("CARGO_BIN_EXE_zn_pong"),
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn z_ping_z_pong_tls() {
+    compare(
+        "tls",
+        env!("CARGO_BIN_EXE_z_ping"),
+        env!("CARGO_BIN_EXE_z_pong"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn t_ping_t_pong_quic() {
+    compare(
+        "quic",
+        env!("CARGO_BIN_EXE_t_ping"),
+        env!("CARGO_BIN_EXE_t_pong"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn zn_ping_zn_pong_quic() {
+    compare(
+        "quic",
+        env!("CARGO_BIN_EXE_zn_ping"),
+        env!("CARGO_BIN_EXE_zn_pong"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn z_ping_z_pong_quic() {
+    compare(
+        "quic",
+        env!("CARGO_BIN_EXE_z_ping"),
+        env!("CARGO_BIN_EXE_z_pong"),
+    );
+}
@@ -0,0 +1,84 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each ping against its pong over a Unix socket, then over TCP
+//! loopback, and compares their round trip times.
+#![cfg(all(unix, feature = "unixsock-stream"))]
+use zenoh_perf_throughput::testing::*;
+
+// Median round trip time in microseconds, the pong listening on `locator`
+fn rtt(ping: &str, pong: &str, locator: &str) -> u128 {
+    let pong = Process::spawn(pong, &["-l", locator, "-m", "peer"]);
+    let locator = pong.ready();
+    let ping = Process::spawn(
+        ping,
+        &[
+            "-l", &locator, "-m", "peer", "-p", "64", "-n", "pair", "-s", "test", "-i", "0.01",
+        ],
+    );
+    let mut rtts: Vec<u128> = ping
+        .test(10, "latency.sequential")
+        .iter()
+        .map(|r| r[7].parse().unwrap())
+        .collect();
+    rtts.sort_unstable();
+    rtts[rtts.len() / 2]
+}
+
+fn compare(name: &str, ping: &str, pong: &str) {
+    let unixsock = rtt(ping, pong, &unixsock(name));
+    let tcp = rtt(ping, pong, "tcp/127.0.0.1:0");
+    // Loose bound, the two runs share a possibly busy host
+    assert!(
+        unixsock <= 10 * tcp.max(1),
+        "{} us over the Unix socket against {} us over TCP",
+        unixsock,
+        tcp
+    );
+}
+
+#[test]
+fn t_ping_t_pong() {
+    compare(
+        "t_lat",
+        env!("CARGO_BIN_EXE_t_ping"),
+        env!("CARGO_BIN_EXE_t_pong"),
+    );
+}
+
+#[test]
+fn r_ping_r_pong() {
+    compare(
+        "r_lat",
+        env!("CARGO_BIN_EXE_r_ping"),
+        env!("CARGO_BIN_EXE_r_pong"),
+    );
+}
+
+#[test]
+fn zn_ping_zn_pong() {
+    compare(
+        "zn_lat",
+        env!("CARGO_BIN_EXE_zn_ping"),
+        env!("CARGO_BIN_EXE_zn_pong"),
+    );
+}
+
+#[test]
+fn z_ping_z_pong() {
+    compare(
+        "z_lat",
+        env!("CARGO_BIN_EXE_z_ping"),
+        env!("CARGO_BIN_EXE_z_pong"),
+    );
+}
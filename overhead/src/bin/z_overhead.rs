@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use structopt::StructOpt;
+use zenoh::Properties;
+use zenoh::*;
+use zenoh_perf_throughput::value::{self, Kind};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "z_overhead")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(long = "value", default_value = "raw")]
+    value: Kind,
+    #[structopt(short = "v", long = "verbose")]
+    print: bool,
+    #[structopt(short = "t", long = "total", default_value = "1048576")] //1MB in bytes
+    total: u64,
+    #[structopt(short = "i", long = "interval", default_value = "0")]
+    interval: f64,
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+    // The number of messages is the total divided by the payload
+    assert!(opt.payload > 0, "The payload must be at least 1 byte");
+
+    let bytes_in_mb: u64 = 1048576;
+
+    let mut config = Properties::default();
+    config.insert("mode".to_string(), opt.mode.clone());
+    config.insert("add_timestamp".to_string(), "false".to_string());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), opt.locator);
+
+    let zenoh = Zenoh::new(config.into()).await.unwrap();
+    let workspace = zenoh.workspace(None).await.unwrap();
+
+    let path: Path = Path::try_from("/test/overhead").unwrap();
+    let value = value::generate(opt.value, opt.payload, 0);
+
+    let mut i: u64 = 0;
+    let tot: u64 = (opt.total * bytes_in_mb) / (opt.payload as u64);
+
+    if opt.print {
+        let count = Arc::new(AtomicUsize::new(0));
+        let c_count = count.clone();
+        task::spawn(async move {
+            loop {
+                task::sleep(Duration::from_secs(1)).await;
+                let c = count.swap(0, Ordering::Relaxed);
+                if c > 0 {
+                    println!("{} msg/s", c);
+                }
+            }
+        });
+
+        while i < tot {
+            workspace.put(&path, value.clone()).await.unwrap();
+            c_count.fetch_add(1, Ordering::Relaxed);
+            i += 1;
+            task::sleep(Duration::from_secs_f64(opt.interval)).await;
+        }
+    } else {
+        while i < tot {
+            workspace.put(&path, value.clone()).await.unwrap();
+            i += 1;
+            task::sleep(Duration::from_secs_f64(opt.interval)).await;
+        }
+    }
+
+    zenoh.close().await.unwrap();
+}
@@ -17,8 +17,9 @@ use structopt::StructOpt;
 //use zenoh::net::ResKey::*;
 use async_std::fs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::io::Read;
-use zenoh::net::protocol::io::ZBuf;
+use zenoh::net::protocol::io::{WBuf, ZBuf};
 use zenoh::net::protocol::proto::{
     FramePayload, TransportBody, TransportMessage, ZenohBody, ZenohMessage,
 };
@@ -28,6 +29,10 @@ use zenoh::net::protocol::proto::{
 struct Opt {
     #[structopt(short = "j", long = "json")]
     file: String,
+    #[structopt(short = "o", long = "op", default_value = "data")]
+    op: String,
+    #[structopt(long = "protocol", default_value = "zenoh-net")]
+    protocol: String,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -62,6 +67,20 @@ pub struct PcapLayers {
     pub tcp_payload: Option<Vec<String>>,
 }
 
+#[derive(Default)]
+struct Counter {
+    messages: usize,
+    bytes: usize,
+}
+
+fn first_usize(field: &Option<Vec<String>>) -> usize {
+    field
+        .as_ref()
+        .and_then(|v| v.first())
+        .and_then(|s| s.parse::<usize>().ok())
+        .unwrap_or(0)
+}
+
 fn read_transport_messages(mut data: &[u8]) -> Vec<TransportMessage> {
     let mut messages: Vec<TransportMessage> = Vec::with_capacity(1);
     let mut length_bytes = [0u8; 2];
@@ -95,6 +114,24 @@ fn read_zenoh_messages(data: Vec<TransportMessage>) -> Vec<ZenohMessage> {
     messages
 }
 
+fn zenoh_message_len(message: &ZenohMessage) -> usize {
+    let mut wbuf = WBuf::new(64, false);
+    let mut message = message.clone();
+    assert!(wbuf.write_zenoh_message(&mut message));
+    wbuf.len()
+}
+
+fn zenoh_message_kind(message: &ZenohMessage) -> &'static str {
+    match &message.body {
+        ZenohBody::Data(_) => "data",
+        ZenohBody::Query(_) => "query",
+        ZenohBody::Declare(_) => "declare",
+        ZenohBody::Unit(_) => "unit",
+        ZenohBody::Pull(_) => "pull",
+        _ => "other",
+    }
+}
+
 #[async_std::main]
 async fn main() {
     // initiate logging
@@ -105,23 +142,32 @@ async fn main() {
 
     let contents = fs::read_to_string(opt.file).await.unwrap();
     let pkts: Vec<PcapData> = serde_json::from_str(&contents).unwrap();
-    let mut zenoh_data: Vec<u8> = vec![];
 
-    let mut payload_size = 0;
-    //let mut total_bytes = 0;
-    let mut data_count = 0;
+    // Each direction of each TCP connection is a separate byte stream
+    let mut streams: BTreeMap<(String, String), Vec<u8>> = BTreeMap::new();
+    let mut total_wire = 0;
+    let mut total_ip = 0;
 
     for pkt in pkts.iter() {
-        if let Some(payload) = &pkt.source.layers.tcp_payload {
+        let layers = &pkt.source.layers;
+        total_wire += first_usize(&layers.frame_len);
+        total_ip += first_usize(&layers.ip_len);
+        if let Some(payload) = &layers.tcp_payload {
             let p = payload.first().unwrap();
             let mut d = hex::decode(p).unwrap();
-            zenoh_data.append(&mut d);
+            let src = layers.tcp_src.as_ref().unwrap().first().unwrap().clone();
+            let dst = layers.tcp_dest.as_ref().unwrap().first().unwrap().clone();
+            streams.entry((src, dst)).or_default().append(&mut d);
         }
     }
 
-    println!("Total Size of Zenoh messages: {} bytes", zenoh_data.len());
+    let zenoh_size: usize = streams.values().map(|s| s.len()).sum();
+    println!("Total Size of Zenoh messages: {} bytes", zenoh_size);
 
-    let transport_messages = read_transport_messages(zenoh_data.as_slice());
+    let mut transport_messages = vec![];
+    for s in streams.values() {
+        transport_messages.append(&mut read_transport_messages(s.as_slice()));
+    }
 
     println!("Total TransportMessages: {}", transport_messages.len());
 
@@ -129,16 +175,72 @@ async fn main() {
 
     println!("Total Zenoh Messages: {}", zenoh_messages.len());
 
+    let mut payload_size = 0;
+    let mut data_count = 0;
+    let mut kinds: BTreeMap<&'static str, Counter> = BTreeMap::new();
+
     for m in zenoh_messages.iter() {
+        let counter = kinds.entry(zenoh_message_kind(m)).or_default();
+        counter.messages += 1;
+        counter.bytes += zenoh_message_len(m);
+
         if let ZenohBody::Data(d) = &m.body {
             data_count += 1;
             payload_size += d.payload.len();
         }
     }
 
-    let payload = (payload_size / data_count) as u64;
+    let payload = if data_count > 0 {
+        (payload_size / data_count) as u64
+    } else {
+        0
+    };
 
     println!("Total Data messages: {}", data_count);
     println!("Total Payload: {} bytes", payload_size);
     println!("Per message Payload: {} bytes", payload);
+    for (kind, counter) in kinds.iter() {
+        println!(
+            "Total {} messages: {} ({} bytes)",
+            kind, counter.messages, counter.bytes
+        );
+    }
+
+    let ops = match opt.op.as_str() {
+        "data" => data_count,
+        "query" => kinds.get("query").map(|c| c.messages).unwrap_or(0),
+        _ => panic!("Unsupported operation: {}", opt.op),
+    };
+    if ops == 0 {
+        println!("No {} operation found in the capture", opt.op);
+        return;
+    }
+    let declare = kinds.get("declare").map(|c| c.bytes).unwrap_or(0);
+
+    println!(
+        "Per {} operation: {} bytes (zenoh)",
+        opt.op,
+        zenoh_size / ops
+    );
+    println!(
+        "Per {} operation: {} bytes (wire)",
+        opt.op,
+        total_wire / ops
+    );
+
+    println!("protocol,op,total_wire,total_ip,total_zenoh,declare_zenoh,ops,total_payload,payload,zenoh_per_op,wire_per_op");
+    println!(
+        "{},{},{},{},{},{},{},{},{},{},{}",
+        opt.protocol,
+        opt.op,
+        total_wire,
+        total_ip,
+        zenoh_size,
+        declare,
+        ops,
+        payload_size,
+        payload,
+        zenoh_size / ops,
+        total_wire / ops
+    );
 }
@@ -44,6 +44,8 @@ async fn main() {
 
     // Parse the args
     let opt = Opt::from_args();
+    // The number of messages is the total divided by the payload
+    assert!(opt.payload > 0, "The payload must be at least 1 byte");
 
     let bytes_in_mb: u64 = 1048576;
 
@@ -0,0 +1,90 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::stream::StreamExt;
+use async_std::sync::Arc;
+use async_std::task;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use structopt::StructOpt;
+use zenoh::net::ResKey::*;
+use zenoh::net::*;
+use zenoh::Properties;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_query_overhead")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "v", long = "verbose")]
+    print: bool,
+    #[structopt(short = "t", long = "total", default_value = "10000")] // number of queries
+    total: u64,
+    #[structopt(short = "i", long = "interval", default_value = "0")]
+    interval: f64,
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let mut config = Properties::default();
+    config.insert("mode".to_string(), opt.mode.clone());
+    config.insert("add_timestamp".to_string(), "false".to_string());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), opt.locator);
+
+    let session = open(config.into()).await.unwrap();
+
+    let reskey = RName("/test/query".to_string());
+    let predicate = "";
+
+    let count = Arc::new(AtomicUsize::new(0));
+    if opt.print {
+        let c_count = count.clone();
+        task::spawn(async move {
+            loop {
+                task::sleep(Duration::from_secs(1)).await;
+                let c = c_count.swap(0, Ordering::Relaxed);
+                if c > 0 {
+                    println!("{} query/s", c);
+                }
+            }
+        });
+    }
+
+    // Each query is considered complete once the final reply has been received
+    for _ in 0..opt.total {
+        let mut replies = session
+            .query(
+                &reskey,
+                predicate,
+                QueryTarget::default(),
+                QueryConsolidation::default(),
+            )
+            .await
+            .unwrap();
+        while replies.next().await.is_some() {}
+        count.fetch_add(1, Ordering::Relaxed);
+        task::sleep(Duration::from_secs_f64(opt.interval)).await;
+    }
+
+    session.close().await.unwrap();
+}
@@ -0,0 +1,88 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Runs each overhead publisher against a peer of this test listening on an
+//! ephemeral port, and checks that its messages arrive.
+use async_std::task;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use zenoh::net::runtime::Runtime;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::testing::*;
+
+// Runs a publisher to completion, returns the samples received by the peer
+fn run(bin: &'static str, args: &[&str]) -> usize {
+    task::block_on(async {
+        let listener = "tcp/127.0.0.1:0";
+        let mut config = Properties::default();
+        config.insert("mode".to_string(), "peer".to_string());
+        config.insert("multicast_scouting".to_string(), "false".to_string());
+        config.insert("listener".to_string(), listener.to_string());
+        let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+        let locator = ready::listening(&runtime, listener);
+        let session = Session::init(runtime, true, vec![], vec![]).await;
+
+        let samples = Arc::new(AtomicUsize::new(0));
+        let c_samples = samples.clone();
+        let sub_info = SubInfo {
+            reliability: Reliability::Reliable,
+            mode: SubMode::Push,
+            period: None,
+        };
+        let _sub = session
+            .declare_callback_subscriber(
+                &ResKey::RName("/test/**".to_string()),
+                &sub_info,
+                move |_sample| {
+                    c_samples.fetch_add(1, Ordering::Relaxed);
+                },
+            )
+            .await
+            .unwrap();
+
+        let mut all = vec!["-l", &locator, "-m", "peer"];
+        all.extend_from_slice(args);
+        let mut publ = Process::spawn(bin, &all);
+        let status = task::spawn_blocking(move || publ.status()).await;
+        assert!(status.success(), "{} failed: {}", bin, status);
+        samples.load(Ordering::Relaxed)
+    })
+}
+
+#[test]
+fn zn_overhead() {
+    // 1 MB of 1 KB messages
+    let samples = run(
+        env!("CARGO_BIN_EXE_zn_overhead"),
+        &["-p", "1024", "-t", "1"],
+    );
+    assert!(samples > 0, "No sample received");
+}
+
+#[test]
+fn z_overhead() {
+    let samples = run(
+        env!("CARGO_BIN_EXE_z_overhead"),
+        &["-p", "1024", "-t", "1", "--value", "json"],
+    );
+    assert!(samples > 0, "No sample received");
+}
+
+#[test]
+fn zn_query_overhead() {
+    // Without a queryable, each query completes with the final reply only
+    run(env!("CARGO_BIN_EXE_zn_query_overhead"), &["-t", "10"]);
+}
@@ -0,0 +1,112 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh_perf_proxy::control::Control;
+use zenoh_perf_proxy::impairment::{self, Impairment, Loss};
+use zenoh_perf_proxy::injection::Log;
+use zenoh_perf_proxy::link::LinkConfig;
+use zenoh_perf_proxy::profile::Profile;
+use zenoh_perf_proxy::{parse_locator, tcp, udp, Protocol};
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zperf-proxy")]
+struct Opt {
+    #[structopt(short = "l", long = "listen")]
+    listen: String,
+    #[structopt(short = "c", long = "connect")]
+    connect: String,
+    #[structopt(long = "delay", parse(try_from_str = impairment::parse_duration), default_value = "0ms")]
+    delay: Duration,
+    #[structopt(long = "jitter", parse(try_from_str = impairment::parse_duration), default_value = "0ms")]
+    jitter: Duration,
+    #[structopt(long = "bandwidth", parse(try_from_str = impairment::parse_bandwidth))]
+    bandwidth: Option<u64>,
+    #[structopt(long = "loss", parse(try_from_str = impairment::parse_loss), default_value = "0")]
+    loss: Loss,
+    #[structopt(long = "duplicate", default_value = "0")]
+    duplicate: f64,
+    #[structopt(long = "reorder", default_value = "0")]
+    reorder: f64,
+    #[structopt(long = "reorder-gap", parse(try_from_str = impairment::parse_duration), default_value = "1ms")]
+    reorder_gap: Duration,
+    #[structopt(long = "rto", parse(try_from_str = impairment::parse_duration), default_value = "200ms")]
+    rto: Duration,
+    #[structopt(long = "profile", parse(from_os_str))]
+    profile: Option<PathBuf>,
+    #[structopt(long = "period", parse(try_from_str = impairment::parse_duration))]
+    period: Option<Duration>,
+    #[structopt(long = "log", parse(from_os_str))]
+    log: Option<PathBuf>,
+    #[structopt(long = "seed", default_value = "0")]
+    seed: u64,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let (protocol, listen) = parse_locator(&opt.listen).unwrap();
+    let (t_protocol, target) = parse_locator(&opt.connect).unwrap();
+    assert_eq!(
+        protocol, t_protocol,
+        "The listen and connect locators must use the same protocol"
+    );
+
+    let base = Impairment {
+        delay: opt.delay,
+        jitter: opt.jitter,
+        bandwidth: opt.bandwidth,
+        loss: opt.loss,
+        duplicate: opt.duplicate,
+        reorder: opt.reorder,
+        reorder_gap: opt.reorder_gap,
+    };
+    let profile = match opt.profile.as_ref() {
+        Some(f) => {
+            let content = std::fs::read_to_string(f).unwrap();
+            Profile::parse(&content, &base, opt.period).unwrap()
+        }
+        None => Profile::constant(base),
+    };
+    for (at, imp) in profile.steps().iter() {
+        println!("{:?}: {}", at, imp);
+    }
+    if protocol == Protocol::Tcp && (opt.duplicate > 0.0 || opt.reorder > 0.0) {
+        log::warn!("Duplication and reordering are not applied to TCP streams");
+    }
+
+    let log = match opt.log.as_ref() {
+        Some(f) => Log::create(f).unwrap(),
+        None => Log::disabled(),
+    };
+    let config = Arc::new(LinkConfig {
+        profile: Arc::new(profile),
+        rto: opt.rto,
+        seed: opt.seed,
+        log,
+        start: Instant::now(),
+        control: Control::new(),
+    });
+
+    match protocol {
+        Protocol::Tcp => tcp::run(listen, target, config).unwrap(),
+        Protocol::Udp => udp::run(listen, target, config).unwrap(),
+    }
+}
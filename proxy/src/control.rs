@@ -0,0 +1,99 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runtime control of a proxy embedded in a benchmark, e.g. to emulate link failures.
+use std::net::{Shutdown, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    /// A new connection (or UDP session) was accepted.
+    Opened(u64),
+    /// A connection was closed by one of its endpoints.
+    Closed(u64),
+}
+
+#[derive(Default)]
+pub struct Control {
+    blackhole: AtomicBool,
+    bytes: AtomicU64,
+    streams: Mutex<Vec<(u64, TcpStream)>>,
+    events: Mutex<Vec<(Instant, Event)>>,
+}
+
+impl Control {
+    pub fn new() -> Arc<Control> {
+        Arc::new(Control::default())
+    }
+
+    /// While blackholed, the datagrams are silently discarded in both directions and the
+    /// streams are held back until the link heals, as TCP would retransmit them. The
+    /// connections are kept open.
+    pub fn set_blackhole(&self, blackhole: bool) {
+        self.blackhole.store(blackhole, Ordering::Release);
+    }
+
+    pub fn is_blackholed(&self) -> bool {
+        self.blackhole.load(Ordering::Acquire)
+    }
+
+    /// Closes all the active TCP connections by sending a FIN to both endpoints. The
+    /// connections are reported closed once an endpoint closes its own side in turn.
+    pub fn kill(&self) {
+        for (_, s) in self.streams.lock().unwrap().drain(..) {
+            let _ = s.shutdown(Shutdown::Write);
+        }
+    }
+
+    /// Total amount of bytes received from the endpoints, in both directions. The stream
+    /// bytes held back while blackholed are accounted once released.
+    pub fn bytes(&self) -> u64 {
+        self.bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn events_since(&self, since: Instant) -> Vec<(Instant, Event)> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(t, _)| *t >= since)
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn received(&self, len: usize) {
+        self.bytes.fetch_add(len as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn register(&self, id: u64, stream: TcpStream) {
+        self.streams.lock().unwrap().push((id, stream));
+    }
+
+    pub(crate) fn opened(&self, id: u64) {
+        self.events
+            .lock()
+            .unwrap()
+            .push((Instant::now(), Event::Opened(id)));
+    }
+
+    /// Records the first close of a connection.
+    pub(crate) fn closed(&self, id: u64) {
+        self.streams.lock().unwrap().retain(|(i, _)| *i != id);
+        let mut events = self.events.lock().unwrap();
+        if !events.iter().any(|(_, e)| *e == Event::Closed(id)) {
+            events.push((Instant::now(), Event::Closed(id)));
+        }
+    }
+}
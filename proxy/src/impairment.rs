@@ -0,0 +1,267 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Loss {
+    #[default]
+    None,
+    /// Independent losses with the given probability.
+    Bernoulli(f64),
+    /// Two-state Markov chain: `p` is the probability to go from the good to the bad
+    /// state, `r` from the bad to the good state; `loss_good` and `loss_bad` are the
+    /// loss probabilities in each state.
+    GilbertElliott {
+        p: f64,
+        r: f64,
+        loss_good: f64,
+        loss_bad: f64,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Impairment {
+    pub delay: Duration,
+    /// Uniform jitter in [-jitter, +jitter] added to the delay.
+    pub jitter: Duration,
+    /// Bandwidth limit in bit/s.
+    pub bandwidth: Option<u64>,
+    pub loss: Loss,
+    /// Probability to duplicate a datagram.
+    pub duplicate: f64,
+    /// Probability to hold back a datagram by `reorder_gap` so that later ones overtake it.
+    pub reorder: f64,
+    pub reorder_gap: Duration,
+}
+
+pub fn parse_duration(s: &str) -> Result<Duration, String> {
+    let s = s.trim();
+    let (value, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s, "ms"),
+    };
+    let value: f64 = value
+        .parse()
+        .map_err(|_| format!("Invalid duration: {}", s))?;
+    let secs = match unit {
+        "s" => value,
+        "ms" => value / 1_000.0,
+        "us" => value / 1_000_000.0,
+        "ns" => value / 1_000_000_000.0,
+        _ => return Err(format!("Invalid duration unit: {}", s)),
+    };
+    if secs < 0.0 {
+        return Err(format!("Negative duration: {}", s));
+    }
+    Ok(Duration::from_secs_f64(secs))
+}
+
+/// Parses a bandwidth in bit/s, e.g. `100mbit`, `1gbit`, `500kbit` or `64000`.
+pub fn parse_bandwidth(s: &str) -> Result<u64, String> {
+    let s = s.trim().to_lowercase();
+    let (value, unit) = match s.find(|c: char| c.is_ascii_alphabetic()) {
+        Some(i) => s.split_at(i),
+        None => (s.as_str(), "bit"),
+    };
+    let value: f64 = value
+        .parse()
+        .map_err(|_| format!("Invalid bandwidth: {}", s))?;
+    let factor = match unit {
+        "bit" | "bps" => 1.0,
+        "kbit" | "kbps" => 1e3,
+        "mbit" | "mbps" => 1e6,
+        "gbit" | "gbps" => 1e9,
+        _ => return Err(format!("Invalid bandwidth unit: {}", s)),
+    };
+    Ok((value * factor) as u64)
+}
+
+fn parse_probability(s: &str) -> Result<f64, String> {
+    let p: f64 = s
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid probability: {}", s))?;
+    if !(0.0..=1.0).contains(&p) {
+        return Err(format!("Probability out of [0, 1]: {}", s));
+    }
+    Ok(p)
+}
+
+/// Parses `<p>` for Bernoulli losses or `ge:<p>,<r>,<loss_good>,<loss_bad>` for
+/// Gilbert-Elliott losses.
+pub fn parse_loss(s: &str) -> Result<Loss, String> {
+    match s.strip_prefix("ge:") {
+        Some(params) => {
+            let params = params
+                .split(',')
+                .map(parse_probability)
+                .collect::<Result<Vec<f64>, String>>()?;
+            if params.len() != 4 {
+                return Err(format!(
+                    "Gilbert-Elliott loss needs 4 parameters (p,r,loss_good,loss_bad): {}",
+                    s
+                ));
+            }
+            Ok(Loss::GilbertElliott {
+                p: params[0],
+                r: params[1],
+                loss_good: params[2],
+                loss_bad: params[3],
+            })
+        }
+        None => {
+            let p = parse_probability(s)?;
+            if p > 0.0 {
+                Ok(Loss::Bernoulli(p))
+            } else {
+                Ok(Loss::None)
+            }
+        }
+    }
+}
+
+impl Impairment {
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "delay" => self.delay = parse_duration(value)?,
+            "jitter" => self.jitter = parse_duration(value)?,
+            "bandwidth" => {
+                self.bandwidth = match value {
+                    "none" | "0" => None,
+                    v => Some(parse_bandwidth(v)?),
+                }
+            }
+            "loss" => self.loss = parse_loss(value)?,
+            "duplicate" => self.duplicate = parse_probability(value)?,
+            "reorder" => self.reorder = parse_probability(value)?,
+            "reorder-gap" => self.reorder_gap = parse_duration(value)?,
+            _ => return Err(format!("Unknown impairment: {}", key)),
+        }
+        Ok(())
+    }
+
+    /// Applies a whitespace separated list of `key=value` settings on top of `self`,
+    /// e.g. `delay=50ms jitter=5ms loss=ge:0.01,0.3,0,0.5`.
+    pub fn apply(&mut self, spec: &str) -> Result<(), String> {
+        for kv in spec.split_whitespace() {
+            let mut it = kv.splitn(2, '=');
+            let key = it.next().unwrap();
+            let value = it
+                .next()
+                .ok_or_else(|| format!("Missing value for: {}", key))?;
+            self.set(key, value)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Impairment {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "delay={:?} jitter={:?}", self.delay, self.jitter)?;
+        match self.bandwidth {
+            Some(b) => write!(f, " bandwidth={}bit", b)?,
+            None => write!(f, " bandwidth=none")?,
+        }
+        match &self.loss {
+            Loss::None => write!(f, " loss=0")?,
+            Loss::Bernoulli(p) => write!(f, " loss={}", p)?,
+            Loss::GilbertElliott {
+                p,
+                r,
+                loss_good,
+                loss_bad,
+            } => write!(f, " loss=ge:{},{},{},{}", p, r, loss_good, loss_bad)?,
+        }
+        write!(
+            f,
+            " duplicate={} reorder={} reorder-gap={:?}",
+            self.duplicate, self.reorder, self.reorder_gap
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loss() {
+        assert_eq!(parse_loss("0"), Ok(Loss::None));
+        assert_eq!(parse_loss("0.25"), Ok(Loss::Bernoulli(0.25)));
+        assert_eq!(parse_loss(" 1 "), Ok(Loss::Bernoulli(1.0)));
+        assert_eq!(
+            parse_loss("ge:0.01,0.3,0,0.5"),
+            Ok(Loss::GilbertElliott {
+                p: 0.01,
+                r: 0.3,
+                loss_good: 0.0,
+                loss_bad: 0.5,
+            })
+        );
+        for invalid in [
+            "",
+            "1.5",
+            "-0.1",
+            "x",
+            "ge:0.1,0.2,0",
+            "ge:0.1,0.2,0,0,0",
+            "ge:0.1,2,0,0",
+        ]
+        .iter()
+        {
+            assert!(parse_loss(invalid).is_err(), "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn duration() {
+        assert_eq!(parse_duration("10"), Ok(Duration::from_millis(10)));
+        assert_eq!(parse_duration("1.5s"), Ok(Duration::from_millis(1_500)));
+        assert_eq!(parse_duration("250us"), Ok(Duration::from_micros(250)));
+        assert_eq!(parse_duration("100ns"), Ok(Duration::from_nanos(100)));
+        assert!(parse_duration("-1ms").is_err());
+        assert!(parse_duration("1min").is_err());
+    }
+
+    #[test]
+    fn bandwidth() {
+        assert_eq!(parse_bandwidth("64000"), Ok(64_000));
+        assert_eq!(parse_bandwidth("500kbit"), Ok(500_000));
+        assert_eq!(parse_bandwidth("100Mbps"), Ok(100_000_000));
+        assert_eq!(parse_bandwidth("1.5gbit"), Ok(1_500_000_000));
+        assert!(parse_bandwidth("10mb").is_err());
+    }
+
+    #[test]
+    fn apply() {
+        let mut imp = Impairment::default();
+        imp.apply("delay=50ms jitter=5ms bandwidth=1mbit loss=ge:0.01,0.3,0,0.5")
+            .unwrap();
+        imp.apply("reorder=0.1 reorder-gap=2ms duplicate=0.01")
+            .unwrap();
+        assert_eq!(imp.delay, Duration::from_millis(50));
+        assert_eq!(imp.bandwidth, Some(1_000_000));
+        assert_eq!(imp.reorder_gap, Duration::from_millis(2));
+        assert_eq!(
+            imp.to_string(),
+            "delay=50ms jitter=5ms bandwidth=1000000bit loss=ge:0.01,0.3,0,0.5 duplicate=0.01 reorder=0.1 reorder-gap=2ms"
+        );
+
+        imp.apply("bandwidth=none").unwrap();
+        assert_eq!(imp.bandwidth, None);
+        assert!(imp.apply("delay").is_err());
+        assert!(imp.apply("latency=1ms").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// CSV log of the injected impairments: `time_ms,link,event,bytes,value`.
+#[derive(Clone)]
+pub struct Log {
+    start: Instant,
+    out: Option<Arc<Mutex<LineWriter<File>>>>,
+}
+
+impl Log {
+    pub fn disabled() -> Log {
+        Log {
+            start: Instant::now(),
+            out: None,
+        }
+    }
+
+    pub fn create<P: AsRef<Path>>(path: P) -> io::Result<Log> {
+        let mut out = LineWriter::new(File::create(path)?);
+        writeln!(out, "time_ms,link,event,bytes,value")?;
+        Ok(Log {
+            start: Instant::now(),
+            out: Some(Arc::new(Mutex::new(out))),
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.out.is_some()
+    }
+
+    pub fn log(&self, link: &str, event: &str, bytes: usize, value: &str) {
+        if let Some(out) = self.out.as_ref() {
+            let ms = self.start.elapsed().as_secs_f64() * 1_000.0;
+            let mut guard = out.lock().unwrap();
+            if let Err(e) = writeln!(guard, "{:.3},{},{},{},{}", ms, link, event, bytes, value) {
+                log::warn!("Failed to write the injection log: {}", e);
+            }
+        }
+    }
+}
@@ -0,0 +1,45 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! A user-space proxy emulating impaired links (delay, jitter, bandwidth, losses,
+//! duplication and reordering) between zenoh endpoints, for environments where
+//! `tc netem` is not available.
+pub mod control;
+pub mod impairment;
+pub mod injection;
+pub mod link;
+pub mod profile;
+pub mod scheduler;
+pub mod tcp;
+pub mod udp;
+
+use std::net::SocketAddr;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Protocol {
+    Tcp,
+    Udp,
+}
+
+/// Parses a zenoh locator (`tcp/127.0.0.1:7447` or `udp/127.0.0.1:7447`).
+pub fn parse_locator(locator: &str) -> Result<(Protocol, SocketAddr), String> {
+    let (protocol, addr) = match locator.split_once('/') {
+        Some(("tcp", addr)) => (Protocol::Tcp, addr),
+        Some(("udp", addr)) => (Protocol::Udp, addr),
+        _ => return Err(format!("Unsupported locator: {}", locator)),
+    };
+    let addr = addr
+        .parse()
+        .map_err(|_| format!("Invalid address in locator: {}", locator))?;
+    Ok((protocol, addr))
+}
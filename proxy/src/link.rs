@@ -0,0 +1,307 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::control::Control;
+use crate::impairment::Loss;
+use crate::injection::Log;
+use crate::profile::Profile;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Typical TCP segment payload, used to evaluate the losses of a stream chunk.
+const MSS: usize = 1448;
+
+/// A small deterministic PRNG (SplitMix64), so that a given seed reproduces the
+/// same sequence of impairments.
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform in [0, 1).
+    pub fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    pub fn chance(&mut self, p: f64) -> bool {
+        p > 0.0 && self.next_f64() < p
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    /// A reliable byte stream (TCP): losses are emulated as retransmission stalls
+    /// and the order is always preserved.
+    Stream,
+    /// Datagrams (UDP): losses drop, duplications and reorderings are applied.
+    Datagram,
+}
+
+pub struct LinkConfig {
+    pub profile: Arc<Profile>,
+    /// Stall applied to a stream chunk when one of its segments is lost.
+    pub rto: Duration,
+    pub seed: u64,
+    pub log: Log,
+    pub start: Instant,
+    pub control: Arc<Control>,
+}
+
+impl LinkConfig {
+    /// A configuration without injection log, with the default RTO and seed.
+    pub fn new(profile: Profile, control: Arc<Control>) -> LinkConfig {
+        LinkConfig {
+            profile: Arc::new(profile),
+            rto: Duration::from_millis(200),
+            seed: 0,
+            log: Log::disabled(),
+            start: Instant::now(),
+            control,
+        }
+    }
+}
+
+/// The impairment state of one direction of a connection.
+pub struct Link {
+    name: String,
+    config: Arc<LinkConfig>,
+    rng: Rng,
+    step: usize,
+    ge_bad: bool,
+    link_free: Instant,
+    last_release: Instant,
+}
+
+impl Link {
+    pub fn new(name: String, config: Arc<LinkConfig>, id: u64) -> Link {
+        let now = Instant::now();
+        let rng = Rng::new(config.seed ^ id.wrapping_mul(0x2545_f491_4f6c_dd1d));
+        Link {
+            name,
+            config,
+            rng,
+            step: 0,
+            ge_bad: false,
+            link_free: now,
+            last_release: now,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn lost(&mut self, loss: &Loss) -> bool {
+        match loss {
+            Loss::None => false,
+            Loss::Bernoulli(p) => self.rng.chance(*p),
+            Loss::GilbertElliott {
+                p,
+                r,
+                loss_good,
+                loss_bad,
+            } => {
+                if self.ge_bad {
+                    if self.rng.chance(*r) {
+                        self.ge_bad = false;
+                    }
+                } else if self.rng.chance(*p) {
+                    self.ge_bad = true;
+                }
+                let loss = if self.ge_bad { loss_bad } else { loss_good };
+                self.rng.chance(*loss)
+            }
+        }
+    }
+
+    /// Returns the release times of a buffer of `len` bytes received now: empty if the
+    /// buffer is dropped, two entries if it is duplicated. Datagrams are dropped while
+    /// blackholed, a stream must instead be held back by the caller until the link
+    /// heals, not to cut a hole in it.
+    pub fn schedule(&mut self, len: usize, kind: Kind) -> Vec<Instant> {
+        let config = self.config.clone();
+        let log = &config.log;
+        let now = Instant::now();
+        config.control.received(len);
+        if kind == Kind::Datagram && config.control.is_blackholed() {
+            log.log(&self.name, "blackhole", len, "");
+            return vec![];
+        }
+        let (step, imp) = config
+            .profile
+            .at(now.saturating_duration_since(config.start));
+        if step != self.step {
+            self.step = step;
+            log.log(&self.name, "profile", 0, &format!("step {}: {}", step, imp));
+        }
+
+        // Losses
+        let mut stall = Duration::from_secs(0);
+        match kind {
+            Kind::Stream => {
+                let segments = len.div_ceil(MSS);
+                let lost = (0..segments).filter(|_| self.lost(&imp.loss)).count();
+                if lost > 0 {
+                    stall = config.rto;
+                    log.log(&self.name, "loss-stall", len, &format!("{}", lost));
+                }
+            }
+            Kind::Datagram => {
+                if self.lost(&imp.loss) {
+                    log.log(&self.name, "drop", len, "");
+                    return vec![];
+                }
+            }
+        }
+
+        // Serialization on the bandwidth limited link
+        let start = self.link_free.max(now);
+        let tx = match imp.bandwidth {
+            Some(bw) if bw > 0 => Duration::from_secs_f64(len as f64 * 8.0 / bw as f64),
+            _ => Duration::from_secs(0),
+        };
+        self.link_free = start + tx + stall;
+        if start > now && log.is_enabled() {
+            log.log(
+                &self.name,
+                "queue",
+                len,
+                &format!("{}", (start - now).as_micros()),
+            );
+        }
+
+        // Propagation delay and jitter
+        let mut delay = imp.delay.as_secs_f64();
+        if imp.jitter > Duration::from_secs(0) {
+            delay += (self.rng.next_f64() * 2.0 - 1.0) * imp.jitter.as_secs_f64();
+        }
+        let mut release = self.link_free + Duration::from_secs_f64(delay.max(0.0));
+
+        match kind {
+            Kind::Stream => {
+                // A stream never reorders
+                release = release.max(self.last_release);
+                self.last_release = release;
+                vec![release]
+            }
+            Kind::Datagram => {
+                if self.rng.chance(imp.reorder) {
+                    release += imp.reorder_gap;
+                    log.log(
+                        &self.name,
+                        "reorder",
+                        len,
+                        &format!("{}", imp.reorder_gap.as_micros()),
+                    );
+                }
+                if self.rng.chance(imp.duplicate) {
+                    log.log(&self.name, "duplicate", len, "");
+                    vec![release, release]
+                } else {
+                    vec![release]
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::impairment::Impairment;
+
+    fn link(control: Arc<Control>) -> Link {
+        let config = LinkConfig::new(Profile::constant(Impairment::default()), control);
+        Link::new("test".to_string(), Arc::new(config), 0)
+    }
+
+    fn loss(p: f64, r: f64) -> Loss {
+        Loss::GilbertElliott {
+            p,
+            r,
+            loss_good: 0.0,
+            loss_bad: 1.0,
+        }
+    }
+
+    #[test]
+    fn gilbert_elliott_transitions() {
+        let mut link = link(Control::new());
+        // Always in the good state
+        assert!((0..100).all(|_| !link.lost(&loss(0.0, 1.0))));
+        // Into the bad state at once, then stuck there
+        assert!((0..100).all(|_| link.lost(&loss(1.0, 0.0))));
+        assert!(link.ge_bad);
+        // Back to the good state, then alternating
+        assert!(!link.lost(&loss(1.0, 1.0)));
+        assert!(link.lost(&loss(1.0, 1.0)));
+        assert!(!link.lost(&loss(1.0, 1.0)));
+    }
+
+    #[test]
+    fn gilbert_elliott_bursts() {
+        // Stationary loss p / (p + r) = 0.2, with bursts of 1 / r = 2.5 losses
+        let mut link = link(Control::new());
+        let (p, r) = (0.1, 0.4);
+        let losses: Vec<bool> = (0..200_000).map(|_| link.lost(&loss(p, r))).collect();
+        let lost = losses.iter().filter(|l| **l).count() as f64;
+        let bursts = losses.windows(2).filter(|w| !w[0] && w[1]).count() as f64;
+        assert!((lost / losses.len() as f64 - 0.2).abs() < 0.01);
+        assert!((lost / bursts - 2.5).abs() < 0.1);
+    }
+
+    #[test]
+    fn datagram_losses() {
+        let config = LinkConfig::new(
+            Profile::constant(Impairment {
+                loss: Loss::Bernoulli(1.0),
+                ..Default::default()
+            }),
+            Control::new(),
+        );
+        let mut link = Link::new("test".to_string(), Arc::new(config), 0);
+        assert!(link.schedule(100, Kind::Datagram).is_empty());
+        // A stream is stalled instead
+        let now = Instant::now();
+        let release = link.schedule(100, Kind::Stream);
+        assert_eq!(release.len(), 1);
+        assert!(release[0] >= now + Duration::from_millis(200));
+    }
+
+    #[test]
+    fn blackhole_drops_datagrams() {
+        let control = Control::new();
+        let mut link = link(control.clone());
+        assert_eq!(link.schedule(100, Kind::Datagram).len(), 1);
+
+        control.set_blackhole(true);
+        assert!(link.schedule(100, Kind::Datagram).is_empty());
+        // The blackholed bytes are still accounted as received
+        assert_eq!(control.bytes(), 200);
+        // A stream is held back by the caller, never cut
+        assert_eq!(link.schedule(100, Kind::Stream).len(), 1);
+
+        control.set_blackhole(false);
+        assert_eq!(link.schedule(100, Kind::Datagram).len(), 1);
+    }
+}
@@ -0,0 +1,163 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Time-varying impairments.
+//!
+//! A profile file has one step per line: the time offset at which the step starts
+//! followed by the `key=value` settings changed at that time, e.g.
+//!
+//! ```text
+//! # t   settings
+//! 0s    delay=10ms loss=0
+//! 10s   delay=100ms jitter=20ms
+//! 20s   loss=ge:0.05,0.3,0,0.8
+//! ```
+//!
+//! Settings accumulate from one step to the next, starting from the impairment given
+//! on the command line. With a period, the profile restarts from the first step once
+//! the period has elapsed.
+use crate::impairment::{parse_duration, Impairment};
+use std::time::Duration;
+
+#[derive(Debug, Clone)]
+pub struct Profile {
+    steps: Vec<(Duration, Impairment)>,
+    period: Option<Duration>,
+}
+
+impl Profile {
+    pub fn constant(impairment: Impairment) -> Profile {
+        Profile {
+            steps: vec![(Duration::from_secs(0), impairment)],
+            period: None,
+        }
+    }
+
+    pub fn parse(
+        content: &str,
+        base: &Impairment,
+        period: Option<Duration>,
+    ) -> Result<Profile, String> {
+        let mut steps = vec![(Duration::from_secs(0), base.clone())];
+        let mut current = base.clone();
+        for (n, line) in content.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut it = line.splitn(2, char::is_whitespace);
+            let at =
+                parse_duration(it.next().unwrap()).map_err(|e| format!("Line {}: {}", n + 1, e))?;
+            current
+                .apply(it.next().unwrap_or(""))
+                .map_err(|e| format!("Line {}: {}", n + 1, e))?;
+            if at < steps.last().unwrap().0 {
+                return Err(format!("Line {}: steps must be in time order", n + 1));
+            }
+            if at == steps.last().unwrap().0 {
+                steps.pop();
+            }
+            steps.push((at, current.clone()));
+        }
+        Ok(Profile { steps, period })
+    }
+
+    /// The index and the impairment of the step active `elapsed` after the start.
+    pub fn at(&self, elapsed: Duration) -> (usize, &Impairment) {
+        let elapsed = match self.period {
+            Some(p) if p.as_nanos() > 0 => {
+                Duration::from_nanos((elapsed.as_nanos() % p.as_nanos()) as u64)
+            }
+            _ => elapsed,
+        };
+        let i = self
+            .steps
+            .iter()
+            .rposition(|(at, _)| *at <= elapsed)
+            .unwrap_or(0);
+        (i, &self.steps[i].1)
+    }
+
+    pub fn steps(&self) -> &[(Duration, Impairment)] {
+        &self.steps
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROFILE: &str = "
+# t   settings
+0s    delay=10ms loss=0
+10s   delay=100ms jitter=20ms  # congested
+20s   loss=0.5
+20s   jitter=0
+";
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    #[test]
+    fn steps_accumulate() {
+        let base = Impairment {
+            bandwidth: Some(1_000_000),
+            ..Default::default()
+        };
+        let profile = Profile::parse(PROFILE, &base, None).unwrap();
+        // The steps at the same offset are merged
+        assert_eq!(profile.steps().len(), 3);
+
+        let (i, imp) = profile.at(secs(5));
+        assert_eq!(
+            (i, imp.delay, imp.bandwidth),
+            (0, Duration::from_millis(10), Some(1_000_000))
+        );
+        let (i, imp) = profile.at(secs(15));
+        assert_eq!(
+            (i, imp.delay, imp.jitter),
+            (1, Duration::from_millis(100), Duration::from_millis(20))
+        );
+        let (i, imp) = profile.at(secs(1_000));
+        assert_eq!(i, 2);
+        assert_eq!(imp.delay, Duration::from_millis(100));
+        assert_eq!(imp.jitter, Duration::from_secs(0));
+        assert_eq!(imp.loss, crate::impairment::Loss::Bernoulli(0.5));
+    }
+
+    #[test]
+    fn period() {
+        let profile = Profile::parse(PROFILE, &Impairment::default(), Some(secs(30))).unwrap();
+        assert_eq!(profile.at(secs(25)).0, 2);
+        assert_eq!(profile.at(secs(35)).0, 0);
+        assert_eq!(profile.at(secs(45)).0, 1);
+    }
+
+    #[test]
+    fn errors() {
+        let base = Impairment::default();
+        let err = Profile::parse("10s delay=1ms\n5s delay=2ms", &base, None).unwrap_err();
+        assert!(err.starts_with("Line 2:"), "{}", err);
+        let err = Profile::parse("\n1s jitter", &base, None).unwrap_err();
+        assert!(err.starts_with("Line 2:"), "{}", err);
+        assert!(Profile::parse("soon delay=1ms", &base, None).is_err());
+    }
+
+    #[test]
+    fn constant() {
+        let profile = Profile::constant(Impairment::default());
+        assert_eq!(profile.at(secs(3_600)).0, 0);
+        assert_eq!(profile.steps().len(), 1);
+    }
+}
@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io;
+use std::sync::{Arc, Condvar, Mutex};
+use std::thread;
+use std::time::Instant;
+
+struct State {
+    // Ordered by release time, then by arrival
+    queue: BinaryHeap<Reverse<(Instant, u64, Vec<u8>)>>,
+    seq: u64,
+    closed: bool,
+}
+
+/// Releases the pushed buffers to a sink at their scheduled time, from a dedicated thread.
+#[derive(Clone)]
+pub struct Scheduler {
+    inner: Arc<(Mutex<State>, Condvar)>,
+}
+
+impl Scheduler {
+    /// `sink` is called for each released buffer; `on_close` once the scheduler has been
+    /// closed and all the pending buffers have been released, or when the sink fails.
+    pub fn spawn<S, C>(mut sink: S, on_close: C) -> Scheduler
+    where
+        S: FnMut(&[u8]) -> io::Result<()> + Send + 'static,
+        C: FnOnce() + Send + 'static,
+    {
+        let inner = Arc::new((
+            Mutex::new(State {
+                queue: BinaryHeap::new(),
+                seq: 0,
+                closed: false,
+            }),
+            Condvar::new(),
+        ));
+        let c_inner = inner.clone();
+        thread::spawn(move || {
+            let (lock, cvar) = &*c_inner;
+            let mut guard = lock.lock().unwrap();
+            loop {
+                let next = guard.queue.peek().map(|Reverse((at, _, _))| *at);
+                match next {
+                    Some(at) => {
+                        let now = Instant::now();
+                        if at > now {
+                            guard = cvar.wait_timeout(guard, at - now).unwrap().0;
+                            continue;
+                        }
+                        let Reverse((_, _, data)) = guard.queue.pop().unwrap();
+                        drop(guard);
+                        if let Err(e) = sink(&data) {
+                            log::debug!("Scheduler sink failed: {}", e);
+                            lock.lock().unwrap().closed = true;
+                            break;
+                        }
+                        guard = lock.lock().unwrap();
+                    }
+                    None if guard.closed => break,
+                    None => guard = cvar.wait(guard).unwrap(),
+                }
+            }
+            on_close();
+        });
+        Scheduler { inner }
+    }
+
+    pub fn push(&self, at: Instant, data: Vec<u8>) {
+        let (lock, cvar) = &*self.inner;
+        let mut guard = lock.lock().unwrap();
+        if guard.closed {
+            return;
+        }
+        let seq = guard.seq;
+        guard.seq += 1;
+        guard.queue.push(Reverse((at, seq, data)));
+        cvar.notify_one();
+    }
+
+    pub fn close(&self) {
+        let (lock, cvar) = &*self.inner;
+        lock.lock().unwrap().closed = true;
+        cvar.notify_one();
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.inner.0.lock().unwrap().closed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[test]
+    fn released_in_time_order() {
+        let (tx, rx) = mpsc::channel();
+        let (c_tx, closed) = mpsc::channel();
+        let scheduler = Scheduler::spawn(
+            move |data| {
+                tx.send(data[0]).unwrap();
+                Ok(())
+            },
+            move || c_tx.send(()).unwrap(),
+        );
+        let now = Instant::now();
+        let ms = Duration::from_millis;
+        scheduler.push(now + ms(60), vec![4]);
+        scheduler.push(now + ms(20), vec![1]);
+        scheduler.push(now + ms(40), vec![2]);
+        // Released in arrival order at the same time
+        scheduler.push(now + ms(40), vec![3]);
+        scheduler.close();
+
+        closed.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(now.elapsed() >= ms(60));
+        assert_eq!(rx.try_iter().collect::<Vec<u8>>(), vec![1, 2, 3, 4]);
+        // Nothing is accepted once closed
+        scheduler.push(now, vec![5]);
+        assert!(rx.recv_timeout(ms(100)).is_err());
+    }
+
+    #[test]
+    fn sink_failure_closes() {
+        let (c_tx, closed) = mpsc::channel();
+        let scheduler = Scheduler::spawn(
+            |_| Err(io::Error::new(io::ErrorKind::BrokenPipe, "gone")),
+            move || c_tx.send(()).unwrap(),
+        );
+        assert!(!scheduler.is_closed());
+        scheduler.push(Instant::now(), vec![0]);
+        closed.recv_timeout(Duration::from_secs(5)).unwrap();
+        assert!(scheduler.is_closed());
+    }
+}
@@ -0,0 +1,239 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::link::{Kind, Link, LinkConfig};
+use crate::scheduler::Scheduler;
+use std::io::{self, ErrorKind, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const BUFFER_SIZE: usize = 65_536;
+/// Bytes held back while blackholed, beyond which the endpoint is not read anymore
+/// as with a full TCP window.
+const HELD_MAX: usize = 4 * 1_048_576;
+/// Period of the checks of the end of a blackhole.
+const POLL: Duration = Duration::from_millis(10);
+
+// Schedules the bytes held back during a blackhole, once healed
+fn release(held: &mut Vec<u8>, link: &mut Link, scheduler: &Scheduler) {
+    if !held.is_empty() {
+        for at in link.schedule(held.len(), Kind::Stream) {
+            scheduler.push(at, held.clone());
+        }
+        held.clear();
+    }
+}
+
+fn pipe(mut from: TcpStream, mut to: TcpStream, mut link: Link, config: Arc<LinkConfig>, id: u64) {
+    let c_to = to.try_clone().unwrap();
+    let scheduler = Scheduler::spawn(
+        move |data| to.write_all(data),
+        move || {
+            let _ = c_to.shutdown(Shutdown::Write);
+        },
+    );
+    // Wake up regularly to release the held bytes once healed
+    from.set_read_timeout(Some(POLL)).unwrap();
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut held: Vec<u8> = vec![];
+    loop {
+        let blackholed = config.control.is_blackholed();
+        if !blackholed {
+            release(&mut held, &mut link, &scheduler);
+        } else if held.len() >= HELD_MAX {
+            thread::sleep(POLL);
+            continue;
+        }
+        let n = match from.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => continue,
+            Err(e) => {
+                log::debug!("{}: {}", link.name(), e);
+                break;
+            }
+        };
+        // Once the other endpoint is gone keep draining this one, the connection is
+        // reported closed only when this endpoint closes its side
+        if scheduler.is_closed() {
+            continue;
+        }
+        // Hold the stream back rather than cutting a hole in it
+        if config.control.is_blackholed() {
+            held.extend_from_slice(&buffer[..n]);
+            continue;
+        }
+        release(&mut held, &mut link, &scheduler);
+        for at in link.schedule(n, Kind::Stream) {
+            scheduler.push(at, buffer[..n].to_vec());
+        }
+    }
+    config.control.closed(id);
+    // The end of the stream follows the held bytes
+    while config.control.is_blackholed() && !held.is_empty() && !scheduler.is_closed() {
+        thread::sleep(POLL);
+    }
+    release(&mut held, &mut link, &scheduler);
+    scheduler.close();
+}
+
+fn serve(
+    client: TcpStream,
+    target: SocketAddr,
+    config: Arc<LinkConfig>,
+    id: u64,
+) -> io::Result<()> {
+    let server = TcpStream::connect(target)?;
+    client.set_nodelay(true)?;
+    server.set_nodelay(true)?;
+    log::info!(
+        "Connection {}: {} <-> {}",
+        id,
+        client.peer_addr()?,
+        server.peer_addr()?
+    );
+
+    config.control.register(id, client.try_clone()?);
+    config.control.register(id, server.try_clone()?);
+    config.control.opened(id);
+
+    let up = Link::new(format!("tcp#{}-up", id), config.clone(), 2 * id);
+    let down = Link::new(format!("tcp#{}-down", id), config.clone(), 2 * id + 1);
+    let (c_client, c_server, c_config) = (client.try_clone()?, server.try_clone()?, config.clone());
+    thread::spawn(move || pipe(c_client, c_server, up, c_config, id));
+    thread::spawn(move || pipe(server, client, down, config, id));
+    Ok(())
+}
+
+/// Accepts TCP connections on `listen` and forwards each of them to `target`.
+///
+/// Each connection takes four threads, a pipe and a scheduler per direction, so
+/// thousands of connections are bounded by the threads a user may run (`ulimit -u`)
+/// and by `kernel.threads-max`, not only by the open files.
+pub fn run(listen: SocketAddr, target: SocketAddr, config: Arc<LinkConfig>) -> io::Result<()> {
+    forward(TcpListener::bind(listen)?, target, config)
+}
+
+/// Forwards the connections accepted by an already bound `listener` to `target`,
+/// e.g. one bound on port 0 whose address is read back before accepting.
+pub fn forward(
+    listener: TcpListener,
+    target: SocketAddr,
+    config: Arc<LinkConfig>,
+) -> io::Result<()> {
+    log::info!("Proxying tcp/{} -> tcp/{}", listener.local_addr()?, target);
+    for (id, client) in listener.incoming().enumerate() {
+        match client {
+            Ok(client) => {
+                if let Err(e) = serve(client, target, config.clone(), id as u64) {
+                    log::warn!("Failed to connect to {}: {}", target, e);
+                }
+            }
+            Err(e) => log::warn!("Failed to accept a connection: {}", e),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::control::{Control, Event};
+    use crate::impairment::Impairment;
+    use crate::profile::Profile;
+    use std::time::{Duration, Instant};
+
+    // Starts a proxy towards a fresh target, returns the connected client and the
+    // accepted target side.
+    fn proxy(control: Arc<Control>) -> (TcpStream, TcpStream) {
+        let target = TcpListener::bind("127.0.0.1:0").unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let listen = listener.local_addr().unwrap();
+        let config = Arc::new(LinkConfig::new(
+            Profile::constant(Impairment::default()),
+            control.clone(),
+        ));
+        let t_addr = target.local_addr().unwrap();
+        thread::spawn(move || forward(listener, t_addr, config));
+
+        let start = Instant::now();
+        let client = loop {
+            match TcpStream::connect(listen) {
+                Ok(s) => break s,
+                Err(e) if start.elapsed() > Duration::from_secs(5) => panic!("{}", e),
+                Err(_) => thread::sleep(Duration::from_millis(10)),
+            }
+        };
+        let (server, _) = target.accept().unwrap();
+        // Wait for the connection to be registered
+        while control.events_since(start).is_empty() {
+            thread::sleep(Duration::from_millis(1));
+        }
+        server
+            .set_read_timeout(Some(Duration::from_millis(500)))
+            .unwrap();
+        (client, server)
+    }
+
+    #[test]
+    fn blackhole_holds_the_stream() {
+        let control = Control::new();
+        let (mut client, mut server) = proxy(control.clone());
+        let mut buffer = [0u8; 16];
+        let t0 = Instant::now();
+
+        control.set_blackhole(true);
+        client.write_all(b"held").unwrap();
+        let err = server.read(&mut buffer).unwrap_err();
+        assert!(matches!(
+            err.kind(),
+            io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut
+        ));
+        // The connection is kept open
+        assert!(control.events_since(t0).is_empty());
+
+        // Once healed, the held bytes come first and nothing is missing
+        control.set_blackhole(false);
+        client.write_all(b"next").unwrap();
+        let mut received = vec![];
+        while received.len() < 8 {
+            let n = server.read(&mut buffer).unwrap();
+            received.extend_from_slice(&buffer[..n]);
+        }
+        assert_eq!(received, b"heldnext");
+    }
+
+    #[test]
+    fn kill_reports_the_close_of_an_endpoint() {
+        let control = Control::new();
+        let (client, mut server) = proxy(control.clone());
+        let t0 = Instant::now();
+        control.kill();
+
+        // The endpoints get a FIN, the close is reported once one of them reacts
+        let mut buffer = [0u8; 16];
+        assert_eq!(server.read(&mut buffer).unwrap(), 0);
+        thread::sleep(Duration::from_millis(100));
+        assert!(control.events_since(t0).is_empty());
+
+        drop(client);
+        let start = Instant::now();
+        while control.events_since(t0).is_empty() {
+            assert!(start.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(matches!(control.events_since(t0)[0].1, Event::Closed(_)));
+    }
+}
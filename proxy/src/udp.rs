@@ -0,0 +1,107 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use crate::link::{Kind, Link, LinkConfig};
+use crate::scheduler::Scheduler;
+use std::collections::HashMap;
+use std::io;
+use std::net::{SocketAddr, UdpSocket};
+use std::sync::Arc;
+use std::thread;
+
+const BUFFER_SIZE: usize = 65_536;
+
+struct Session {
+    link: Link,
+    scheduler: Scheduler,
+}
+
+fn bind_any(target: &SocketAddr) -> io::Result<UdpSocket> {
+    match target {
+        SocketAddr::V4(_) => UdpSocket::bind("0.0.0.0:0"),
+        SocketAddr::V6(_) => UdpSocket::bind("[::]:0"),
+    }
+}
+
+fn open(
+    listener: &Arc<UdpSocket>,
+    client: SocketAddr,
+    target: SocketAddr,
+    config: &Arc<LinkConfig>,
+    id: u64,
+) -> io::Result<Session> {
+    // One upstream socket per client so that the replies can be routed back
+    let upstream = Arc::new(bind_any(&target)?);
+    upstream.connect(target)?;
+    log::info!("Session {}: {} <-> {}", id, client, target);
+    config.control.opened(id);
+
+    let c_upstream = upstream.clone();
+    let up = Scheduler::spawn(move |data| c_upstream.send(data).map(|_| ()), || {});
+
+    let c_listener = listener.clone();
+    let down = Scheduler::spawn(
+        move |data| c_listener.send_to(data, client).map(|_| ()),
+        || {},
+    );
+    let mut down_link = Link::new(format!("udp#{}-down", id), config.clone(), 2 * id + 1);
+    thread::spawn(move || {
+        let mut buffer = vec![0u8; BUFFER_SIZE];
+        loop {
+            let n = match upstream.recv(&mut buffer) {
+                Ok(n) => n,
+                Err(e) => {
+                    log::debug!("{}: {}", down_link.name(), e);
+                    break;
+                }
+            };
+            for at in down_link.schedule(n, Kind::Datagram) {
+                down.push(at, buffer[..n].to_vec());
+            }
+        }
+        down.close();
+    });
+
+    Ok(Session {
+        link: Link::new(format!("udp#{}-up", id), config.clone(), 2 * id),
+        scheduler: up,
+    })
+}
+
+/// Forwards the datagrams received on `listen` to `target`, and the replies back.
+pub fn run(listen: SocketAddr, target: SocketAddr, config: Arc<LinkConfig>) -> io::Result<()> {
+    let listener = Arc::new(UdpSocket::bind(listen)?);
+    log::info!("Proxying udp/{} -> udp/{}", listener.local_addr()?, target);
+
+    let mut sessions: HashMap<SocketAddr, Session> = HashMap::new();
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    loop {
+        let (n, client) = listener.recv_from(&mut buffer)?;
+        if !sessions.contains_key(&client) {
+            let id = sessions.len() as u64;
+            match open(&listener, client, target, &config, id) {
+                Ok(session) => {
+                    sessions.insert(client, session);
+                }
+                Err(e) => {
+                    log::warn!("Failed to open a session towards {}: {}", target, e);
+                    continue;
+                }
+            }
+        }
+        let session = sessions.get_mut(&client).unwrap();
+        for at in session.link.schedule(n, Kind::Datagram) {
+            session.scheduler.push(at, buffer[..n].to_vec());
+        }
+    }
+}
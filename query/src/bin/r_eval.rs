@@ -13,6 +13,7 @@
 //
 use async_std::future;
 use async_std::task;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use structopt::StructOpt;
 use zenoh::net::protocol::core::{
@@ -20,24 +21,27 @@ use zenoh::net::protocol::core::{
 };
 use zenoh::net::protocol::io::ZBuf;
 use zenoh::net::protocol::proto::{DataInfo, RoutingContext};
-use zenoh::net::queryable::ALL_KINDS;
 use zenoh::net::routing::face::Face;
 use zenoh::net::runtime::Runtime;
 use zenoh::net::transport::Primitives;
+use zenoh_perf_query::parse_kind;
+use zenoh_perf_throughput::ready;
 use zenoh_util::properties::config::{
     ConfigProperties, ZN_LISTENER_KEY, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
 
 struct EvalPrimitives {
     pid: PeerId,
+    kind: ZInt,
     payload: usize,
     tx: Mutex<Option<Arc<Face>>>,
 }
 
 impl EvalPrimitives {
-    fn new(payload: usize) -> EvalPrimitives {
+    fn new(kind: ZInt, payload: usize) -> EvalPrimitives {
         EvalPrimitives {
             pid: PeerId::rand(),
+            kind,
             payload,
             tx: Mutex::new(None),
         }
@@ -91,7 +95,7 @@ impl Primitives for EvalPrimitives {
         _routing_context: Option<RoutingContext>,
     ) {
         let reskey = reskey.clone();
-        let source_kind = 0;
+        let source_kind = self.kind;
         let pid = self.pid;
         let info = None;
         let payload = ZBuf::from(vec![0u8; self.payload]);
@@ -134,6 +138,17 @@ struct Opt {
     mode: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(short = "q", long = "queryables", default_value = "1")]
+    queryables: usize,
+    #[structopt(
+        long = "kind",
+        default_value = "all",
+        use_delimiter = true,
+        parse(try_from_str = parse_kind)
+    )]
+    kinds: Vec<ZInt>,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -149,21 +164,32 @@ async fn main() {
 
     config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
     match opt.mode.as_str() {
-        "peer" | "router" => config.insert(ZN_LISTENER_KEY, opt.locator),
-        "client" => config.insert(ZN_PEER_KEY, opt.locator),
+        "peer" | "router" => {
+            ready::remove_stale(&opt.locator);
+            config.insert(ZN_LISTENER_KEY, opt.locator.clone())
+        }
+        "client" => config.insert(ZN_PEER_KEY, opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
     let runtime = Runtime::new(0u8, config, None).await.unwrap();
+    let locator = (opt.mode != "client").then(|| ready::listening(&runtime, &opt.locator));
+
+    // Each queryable has its own face, the kinds are assigned in a round-robin fashion
+    for i in 0..opt.queryables {
+        let kind = opt.kinds[i % opt.kinds.len()];
+        let rx_primitives = Arc::new(EvalPrimitives::new(kind, opt.payload));
+        let tx_primitives = runtime.router.new_primitives(rx_primitives.clone());
+        rx_primitives.set_tx(tx_primitives.clone());
 
-    let rx_primitives = Arc::new(EvalPrimitives::new(opt.payload));
-    let tx_primitives = runtime.router.new_primitives(rx_primitives.clone());
-    rx_primitives.set_tx(tx_primitives.clone());
+        let rid = ResKey::RName("/test/query".to_string());
+        let routing_context = None;
+        tx_primitives.decl_queryable(&rid, kind, routing_context);
+    }
 
-    let rid = ResKey::RName("/test/query".to_string());
-    let kind = ALL_KINDS;
-    let routing_context = None;
-    tx_primitives.decl_queryable(&rid, kind, routing_context);
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
 
     // Stop forever
     future::pending::<()>().await;
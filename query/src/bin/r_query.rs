@@ -13,20 +13,44 @@
 //
 use std::collections::HashMap;
 use std::sync::{Arc, Barrier, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::protocol::core::{
-    Channel, CongestionControl, PeerId, QueryConsolidation, QueryTarget, ResKey, SubInfo, ZInt,
+    Channel, CongestionControl, PeerId, QueryConsolidation, QueryTarget, ResKey, SubInfo, Target,
+    ZInt,
 };
 use zenoh::net::protocol::io::ZBuf;
 use zenoh::net::protocol::proto::{DataInfo, RoutingContext};
 use zenoh::net::runtime::Runtime;
 use zenoh::net::transport::Primitives;
+use zenoh_perf_query::{parse_consolidation, parse_kind, parse_target, query_target};
+use zenoh_perf_throughput::ready;
 use zenoh_util::properties::config::{
     ConfigProperties, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
 
-type Pending = Arc<Mutex<HashMap<u64, (Instant, Arc<Barrier>)>>>;
+// The replies received so far for a query
+struct Replies {
+    start: Instant,
+    first: Option<Instant>,
+    count: usize,
+    payload: usize,
+    barrier: Arc<Barrier>,
+}
+
+impl Replies {
+    fn new(barrier: Arc<Barrier>) -> Replies {
+        Replies {
+            start: Instant::now(),
+            first: None,
+            count: 0,
+            payload: 0,
+            barrier,
+        }
+    }
+}
+
+type Pending = Arc<Mutex<HashMap<u64, Replies>>>;
 
 struct QueryPrimitives {
     scenario: String,
@@ -95,19 +119,34 @@ impl Primitives for QueryPrimitives {
         _info: Option<DataInfo>,
         payload: ZBuf,
     ) {
-        let tuple = self.pending.lock().unwrap().remove(&qid).unwrap();
-        let (instant, barrier) = (tuple.0, tuple.1);
-        barrier.wait();
+        let mut guard = self.pending.lock().unwrap();
+        let replies = guard.get_mut(&qid).unwrap();
+        replies.first.get_or_insert_with(Instant::now);
+        replies.count += 1;
+        replies.payload += payload.len();
+    }
+    fn send_reply_final(&self, qid: ZInt) {
+        let replies = self.pending.lock().unwrap().remove(&qid).unwrap();
+        let elapsed = replies.start.elapsed().as_micros();
+        replies.barrier.wait();
         println!(
             "router,{},query.latency,{},{},{},{}",
+            self.scenario, self.name, replies.payload, qid, elapsed
+        );
+        println!(
+            "router,{},query.replies,{},{},{},{},{},{}",
             self.scenario,
             self.name,
-            payload.len(),
+            replies.payload,
             qid,
-            instant.elapsed().as_micros()
+            replies.count,
+            replies.first.map_or_else(
+                || "-".to_string(),
+                |f| f.duration_since(replies.start).as_micros().to_string()
+            ),
+            elapsed
         );
     }
-    fn send_reply_final(&self, _qid: ZInt) {}
     fn send_pull(
         &self,
         _is_final: bool,
@@ -130,6 +169,29 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(
+        short = "k",
+        long = "kind",
+        default_value = "all",
+        parse(try_from_str = parse_kind)
+    )]
+    kind: ZInt,
+    #[structopt(
+        short = "t",
+        long = "target",
+        default_value = "best-matching",
+        parse(try_from_str = parse_target)
+    )]
+    target: Target,
+    #[structopt(
+        short = "c",
+        long = "consolidation",
+        default_value = "lazy,lazy,full",
+        parse(try_from_str = parse_consolidation)
+    )]
+    consolidation: QueryConsolidation,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
 }
 
 #[async_std::main]
@@ -140,6 +202,10 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     let mut config = ConfigProperties::default();
     config.insert(ZN_MODE_KEY, opt.mode.clone());
 
@@ -150,8 +216,8 @@ async fn main() {
 
     let runtime = Runtime::new(0u8, config, None).await.unwrap();
     let rx_primitives = Arc::new(QueryPrimitives::new(
-        opt.scenario,
-        opt.name,
+        opt.scenario.clone(),
+        opt.name.clone(),
         pending.clone(),
     ));
     let tx_primitives = runtime.router.new_primitives(rx_primitives);
@@ -162,15 +228,15 @@ async fn main() {
         let reskey = ResKey::RName("/test/query".to_string());
         let predicate = "";
         let qid = count;
-        let target = QueryTarget::default();
-        let consolidation = QueryConsolidation::default();
+        let target = query_target(opt.kind, opt.target.clone());
+        let consolidation = opt.consolidation.clone();
         let routing_context = None;
 
         // Insert the pending query
         pending
             .lock()
             .unwrap()
-            .insert(count, (Instant::now(), barrier.clone()));
+            .insert(count, Replies::new(barrier.clone()));
         tx_primitives.send_query(
             &reskey,
             predicate,
@@ -179,7 +245,7 @@ async fn main() {
             consolidation.clone(),
             routing_context,
         );
-        // Wait for the reply to arrive
+        // Wait for the final reply to arrive
         barrier.wait();
 
         count += 1;
@@ -14,7 +14,7 @@
 use async_std::task;
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Barrier, Mutex};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::protocol::core::{
@@ -24,20 +24,19 @@ use zenoh::net::protocol::io::ZBuf;
 use zenoh::net::protocol::proto::{DataInfo, RoutingContext};
 use zenoh::net::runtime::Runtime;
 use zenoh::net::transport::Primitives;
+use zenoh_perf_query::Window;
+use zenoh_perf_throughput::ready;
 use zenoh_util::properties::config::{
     ConfigProperties, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
 
-type Pending = Arc<Mutex<HashMap<u64, Arc<Barrier>>>>;
+type Pending = Arc<Mutex<HashMap<u64, Instant>>>;
 
 struct QueryPrimitives {
     pending: Pending,
-}
-
-impl QueryPrimitives {
-    pub fn new(pending: Pending) -> QueryPrimitives {
-        QueryPrimitives { pending }
-    }
+    window: Arc<Window>,
+    rtt: Arc<AtomicUsize>,
+    counter: Arc<AtomicUsize>,
 }
 
 impl Primitives for QueryPrimitives {
@@ -84,17 +83,22 @@ impl Primitives for QueryPrimitives {
     }
     fn send_reply_data(
         &self,
-        qid: ZInt,
+        _qid: ZInt,
         _source_kind: ZInt,
         _replier_id: PeerId,
         _reskey: ResKey,
         _info: Option<DataInfo>,
         _payload: ZBuf,
     ) {
-        let barrier = self.pending.lock().unwrap().remove(&qid).unwrap();
-        barrier.wait();
     }
-    fn send_reply_final(&self, _qid: ZInt) {}
+    // The query is complete once the final reply is received
+    fn send_reply_final(&self, qid: ZInt) {
+        let start = self.pending.lock().unwrap().remove(&qid).unwrap();
+        self.rtt
+            .fetch_add(start.elapsed().as_micros() as usize, Ordering::Relaxed);
+        self.counter.fetch_add(1, Ordering::Relaxed);
+        self.window.release();
+    }
     fn send_pull(
         &self,
         _is_final: bool,
@@ -119,6 +123,10 @@ struct Opt {
     scenario: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(short = "w", long = "window", default_value = "1")]
+    window: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
 }
 
 #[async_std::main]
@@ -128,6 +136,11 @@ async fn main() {
 
     // Parse the args
     let opt = Opt::from_args();
+    let window = Arc::new(Window::new(opt.window));
+
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
 
     let mut config = ConfigProperties::default();
     config.insert(ZN_MODE_KEY, opt.mode.clone());
@@ -140,7 +153,12 @@ async fn main() {
     let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
 
     let runtime = Runtime::new(0u8, config, None).await.unwrap();
-    let rx_primitives = Arc::new(QueryPrimitives::new(pending.clone()));
+    let rx_primitives = Arc::new(QueryPrimitives {
+        pending: pending.clone(),
+        window: window.clone(),
+        rtt: rtt.clone(),
+        counter: counter.clone(),
+    });
     let tx_primitives = runtime.router.new_primitives(rx_primitives);
 
     let c_rtt = rtt.clone();
@@ -156,12 +174,13 @@ async fn main() {
             if c > 0 {
                 let interval = 1_000_000.0 / elapsed;
                 println!(
-                    "router,{},query.throughput,{},{},{},{}",
+                    "router,{},query.throughput,{},{},{},{},{}",
                     opt.scenario,
                     opt.name,
                     opt.payload,
                     (c as f64 / interval).floor() as usize,
                     (r as f64 / c as f64).floor() as usize,
+                    opt.window
                 );
             }
         }
@@ -176,10 +195,9 @@ async fn main() {
         let consolidation = QueryConsolidation::default();
         let routing_context = None;
 
-        // Insert the pending query
-        let barrier = Arc::new(Barrier::new(2));
-        pending.lock().unwrap().insert(count, barrier.clone());
-        let now = Instant::now();
+        // Wait for a slot in the window and insert the pending query
+        window.acquire();
+        pending.lock().unwrap().insert(count, Instant::now());
         tx_primitives.send_query(
             &reskey,
             predicate,
@@ -188,10 +206,6 @@ async fn main() {
             consolidation.clone(),
             routing_context,
         );
-        // Wait for the reply to arrive
-        barrier.wait();
-        rtt.fetch_add(now.elapsed().as_micros() as usize, Ordering::Relaxed);
-        counter.fetch_add(1, Ordering::Relaxed);
 
         count += 1;
     }
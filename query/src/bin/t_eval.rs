@@ -18,22 +18,28 @@ use std::path::PathBuf;
 use structopt::StructOpt;
 use zenoh::net::link::{EndPoint, Link};
 use zenoh::net::protocol::core::{
-    whatami, Channel, CongestionControl, Priority, Reliability, ResKey,
+    whatami, Channel, CongestionControl, PeerId, Priority, Reliability, ResKey, ZInt,
 };
 use zenoh::net::protocol::io::ZBuf;
-use zenoh::net::protocol::proto::{Query, ReplyContext, ZenohBody, ZenohMessage};
+use zenoh::net::protocol::proto::{Query, ReplierInfo, ReplyContext, ZenohBody, ZenohMessage};
 use zenoh::net::transport::*;
+use zenoh_perf_query::parse_kind;
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
 // Transport Handler for the peer
 struct MySH {
     payload: usize,
+    repliers: Arc<Vec<ReplierInfo>>,
 }
 
 impl MySH {
-    fn new(payload: usize) -> Self {
-        Self { payload }
+    fn new(payload: usize, repliers: Vec<ReplierInfo>) -> Self {
+        Self {
+            payload,
+            repliers: Arc::new(repliers),
+        }
     }
 }
 
@@ -43,7 +49,11 @@ impl TransportEventHandler for MySH {
         _peer: TransportPeer,
         transport: TransportUnicast,
     ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
-        Ok(Arc::new(MyMH::new(transport, self.payload)))
+        Ok(Arc::new(MyMH::new(
+            transport,
+            self.payload,
+            self.repliers.clone(),
+        )))
     }
 
     fn new_multicast(
@@ -58,11 +68,16 @@ impl TransportEventHandler for MySH {
 struct MyMH {
     session: TransportUnicast,
     payload: usize,
+    repliers: Arc<Vec<ReplierInfo>>,
 }
 
 impl MyMH {
-    fn new(session: TransportUnicast, payload: usize) -> Self {
-        Self { session, payload }
+    fn new(session: TransportUnicast, payload: usize, repliers: Arc<Vec<ReplierInfo>>) -> Self {
+        Self {
+            session,
+            payload,
+            repliers,
+        }
     }
 }
 
@@ -76,24 +91,37 @@ impl TransportPeerEventHandler for MyMH {
                     reliability: Reliability::Reliable,
                 };
                 let congestion_control = CongestionControl::Block;
-                let key = ResKey::RName("/test/query".to_string());
-                let info = None;
-                let payload = ZBuf::from(vec![0u8; self.payload]);
-                let routing_context = None;
+
+                // One reply per emulated queryable
+                for replier in self.repliers.iter() {
+                    let key = ResKey::RName("/test/query".to_string());
+                    let info = None;
+                    let payload = ZBuf::from(vec![0u8; self.payload]);
+                    let routing_context = None;
+                    let reply_context = Some(ReplyContext {
+                        qid,
+                        replier: Some(replier.clone()),
+                    });
+                    let attachment = None;
+
+                    let message = ZenohMessage::make_data(
+                        key,
+                        payload,
+                        channel,
+                        congestion_control,
+                        info,
+                        routing_context,
+                        reply_context,
+                        attachment,
+                    );
+                    self.session.handle_message(message)?;
+                }
+
+                // The final reply has no replier
                 let reply_context = Some(ReplyContext { qid, replier: None });
                 let attachment = None;
-
-                let message = ZenohMessage::make_data(
-                    key,
-                    payload,
-                    channel,
-                    congestion_control,
-                    info,
-                    routing_context,
-                    reply_context,
-                    attachment,
-                );
-
+                let message =
+                    ZenohMessage::make_unit(channel, congestion_control, reply_context, attachment);
                 self.session.handle_message(message)
             }
             _ => panic!("Invalid message"),
@@ -118,6 +146,17 @@ struct Opt {
     mode: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(short = "q", long = "queryables", default_value = "1")]
+    queryables: usize,
+    #[structopt(
+        long = "kind",
+        default_value = "eval",
+        use_delimiter = true,
+        parse(try_from_str = parse_kind)
+    )]
+    kinds: Vec<ZInt>,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -144,14 +183,27 @@ async fn main() {
         }
         None => TransportManagerConfig::builder().whatami(whatami),
     };
-    let config = bc.build(Arc::new(MySH::new(opt.payload)));
+    // The kinds are assigned to the queryables in a round-robin fashion
+    let repliers = (0..opt.queryables)
+        .map(|i| ReplierInfo {
+            kind: opt.kinds[i % opt.kinds.len()],
+            id: PeerId::rand(),
+        })
+        .collect();
+    let config = bc.build(Arc::new(MySH::new(opt.payload, repliers)));
     let manager = TransportManager::new(config);
 
     // Connect to the peer or listen
     if whatami == whatami::PEER {
-        manager.add_listener(opt.locator).await.unwrap();
+        let requested = opt.locator.to_string();
+        ready::remove_stale(&requested);
+        let bound = manager.add_listener(opt.locator.clone()).await.unwrap();
+        ready::announce(
+            &ready::bound(&requested, &[bound]),
+            opt.ready_file.as_deref(),
+        );
     } else {
-        let _session = manager.open_transport(opt.locator).await.unwrap();
+        let _session = manager.open_transport(opt.locator.clone()).await.unwrap();
     }
 
     // Stop forever
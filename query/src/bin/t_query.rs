@@ -15,16 +15,39 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::{Arc, Barrier, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::link::{EndPoint, Link};
-use zenoh::net::protocol::core::{whatami, QueryConsolidation, QueryTarget, ResKey};
-use zenoh::net::protocol::proto::{Data, ZenohBody, ZenohMessage};
+use zenoh::net::protocol::core::{whatami, QueryConsolidation, ResKey, Target, ZInt};
+use zenoh::net::protocol::proto::{Data, Unit, ZenohBody, ZenohMessage};
 use zenoh::net::transport::*;
+use zenoh_perf_query::{parse_consolidation, parse_kind, parse_target, query_target};
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
-type Pending = Arc<Mutex<HashMap<u64, (Instant, Arc<Barrier>)>>>;
+// The replies received so far for a query
+struct Replies {
+    start: Instant,
+    first: Option<Instant>,
+    count: usize,
+    payload: usize,
+    barrier: Arc<Barrier>,
+}
+
+impl Replies {
+    fn new(barrier: Arc<Barrier>) -> Replies {
+        Replies {
+            start: Instant::now(),
+            first: None,
+            count: 0,
+            payload: 0,
+            barrier,
+        }
+    }
+}
+
+type Pending = Arc<Mutex<HashMap<u64, Replies>>>;
 
 // Transport Handler for the blocking locator
 struct MySH {
@@ -90,21 +113,33 @@ impl TransportPeerEventHandler for MyMH {
                 ..
             }) => {
                 let reply_context = reply_context.unwrap();
-                let tuple = self
-                    .pending
-                    .lock()
-                    .unwrap()
-                    .remove(&reply_context.qid)
-                    .unwrap();
-                let (instant, barrier) = (tuple.0, tuple.1);
-                barrier.wait();
+                let mut guard = self.pending.lock().unwrap();
+                let replies = guard.get_mut(&reply_context.qid).unwrap();
+                replies.first.get_or_insert_with(Instant::now);
+                replies.count += 1;
+                replies.payload += payload.len();
+            }
+            ZenohBody::Unit(Unit { reply_context, .. }) => {
+                let qid = reply_context.unwrap().qid;
+                let replies = self.pending.lock().unwrap().remove(&qid).unwrap();
+                let elapsed = replies.start.elapsed().as_micros();
+                replies.barrier.wait();
                 println!(
                     "session,{},query.latency,{},{},{},{}",
+                    self.scenario, self.name, replies.payload, qid, elapsed
+                );
+                println!(
+                    "session,{},query.replies,{},{},{},{},{},{}",
                     self.scenario,
                     self.name,
-                    payload.len(),
-                    reply_context.qid,
-                    instant.elapsed().as_micros()
+                    replies.payload,
+                    qid,
+                    replies.count,
+                    replies.first.map_or_else(
+                        || "-".to_string(),
+                        |f| f.duration_since(replies.start).as_micros().to_string()
+                    ),
+                    elapsed
                 );
             }
             _ => panic!("Invalid message"),
@@ -132,6 +167,29 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(
+        short = "k",
+        long = "kind",
+        default_value = "all",
+        parse(try_from_str = parse_kind)
+    )]
+    kind: ZInt,
+    #[structopt(
+        short = "t",
+        long = "target",
+        default_value = "best-matching",
+        parse(try_from_str = parse_target)
+    )]
+    target: Target,
+    #[structopt(
+        short = "c",
+        long = "consolidation",
+        default_value = "lazy,lazy,full",
+        parse(try_from_str = parse_consolidation)
+    )]
+    consolidation: QueryConsolidation,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -168,7 +226,12 @@ async fn main() {
     let manager = TransportManager::new(config);
 
     // Connect to publisher
-    let session = manager.open_transport(opt.locator).await.unwrap();
+    let session = ready::retry(
+        Duration::from_secs_f64(opt.wait_ready.unwrap_or(0.0)),
+        || manager.open_transport(opt.locator.clone()),
+    )
+    .await
+    .unwrap();
     let barrier = Arc::new(Barrier::new(2));
     let mut count: u64 = 0;
     loop {
@@ -176,8 +239,8 @@ async fn main() {
         let key = ResKey::RName("/test/query".to_string());
         let predicate = "".to_string();
         let qid = count;
-        let target = Some(QueryTarget::default());
-        let consolidation = QueryConsolidation::default();
+        let target = Some(query_target(opt.kind, opt.target.clone()));
+        let consolidation = opt.consolidation.clone();
         let routing_context = None;
         let attachment = None;
 
@@ -195,9 +258,9 @@ async fn main() {
         pending
             .lock()
             .unwrap()
-            .insert(count, (Instant::now(), barrier.clone()));
+            .insert(count, Replies::new(barrier.clone()));
         session.handle_message(message).unwrap();
-        // Wait for the reply to arrive
+        // Wait for the final reply to arrive
         barrier.wait();
 
         count += 1;
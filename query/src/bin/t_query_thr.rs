@@ -16,26 +16,37 @@ use std::any::Any;
 use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::{Arc, Barrier, Mutex};
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::link::{EndPoint, Link};
 use zenoh::net::protocol::core::{whatami, QueryConsolidation, QueryTarget, ResKey};
-use zenoh::net::protocol::proto::{Data, ZenohBody, ZenohMessage};
+use zenoh::net::protocol::proto::{Unit, ZenohBody, ZenohMessage};
 use zenoh::net::transport::*;
+use zenoh_perf_query::Window;
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
-type Pending = Arc<Mutex<HashMap<u64, Arc<Barrier>>>>;
+type Pending = Arc<Mutex<HashMap<u64, Instant>>>;
+
+// The queries in flight and the statistics of the completed ones
+#[derive(Clone)]
+struct Queries {
+    pending: Pending,
+    window: Arc<Window>,
+    rtt: Arc<AtomicUsize>,
+    counter: Arc<AtomicUsize>,
+}
 
 // Transport Handler for the blocking locator
 struct MySH {
-    pending: Pending,
+    queries: Queries,
 }
 
 impl MySH {
-    fn new(pending: Pending) -> Self {
-        Self { pending }
+    fn new(queries: Queries) -> Self {
+        Self { queries }
     }
 }
 
@@ -45,7 +56,7 @@ impl TransportEventHandler for MySH {
         _peer: TransportPeer,
         _transport: TransportUnicast,
     ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
-        Ok(Arc::new(MyMH::new(self.pending.clone())))
+        Ok(Arc::new(MyMH::new(self.queries.clone())))
     }
 
     fn new_multicast(
@@ -58,27 +69,33 @@ impl TransportEventHandler for MySH {
 
 // Message Handler for the locator
 struct MyMH {
-    pending: Pending,
+    queries: Queries,
 }
 
 impl MyMH {
-    fn new(pending: Pending) -> Self {
-        Self { pending }
+    fn new(queries: Queries) -> Self {
+        Self { queries }
     }
 }
 
 impl TransportPeerEventHandler for MyMH {
     fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
         match message.body {
-            ZenohBody::Data(Data { reply_context, .. }) => {
+            // The query is complete once the final reply is received
+            ZenohBody::Data(_) => {}
+            ZenohBody::Unit(Unit { reply_context, .. }) => {
                 let reply_context = reply_context.unwrap();
-                let barrier = self
+                let q = &self.queries;
+                let start = q
                     .pending
                     .lock()
                     .unwrap()
                     .remove(&reply_context.qid)
                     .unwrap();
-                barrier.wait();
+                q.rtt
+                    .fetch_add(start.elapsed().as_micros() as usize, Ordering::Relaxed);
+                q.counter.fetch_add(1, Ordering::Relaxed);
+                q.window.release();
             }
             _ => panic!("Invalid message"),
         }
@@ -107,6 +124,10 @@ struct Opt {
     scenario: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(short = "w", long = "window", default_value = "1")]
+    window: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -121,9 +142,12 @@ async fn main() {
 
     let whatami = whatami::parse(opt.mode.as_str()).unwrap();
 
-    let rtt = Arc::new(AtomicUsize::new(0));
-    let counter = Arc::new(AtomicUsize::new(0));
-    let pending: Pending = Arc::new(Mutex::new(HashMap::new()));
+    let queries = Queries {
+        pending: Arc::new(Mutex::new(HashMap::new())),
+        window: Arc::new(Window::new(opt.window)),
+        rtt: Arc::new(AtomicUsize::new(0)),
+        counter: Arc::new(AtomicUsize::new(0)),
+    };
 
     let bc = match opt.config.as_ref() {
         Some(f) => {
@@ -137,14 +161,19 @@ async fn main() {
         }
         None => TransportManagerConfig::builder().whatami(whatami),
     };
-    let config = bc.build(Arc::new(MySH::new(pending.clone())));
+    let config = bc.build(Arc::new(MySH::new(queries.clone())));
     let manager = TransportManager::new(config);
 
     // Connect to publisher
-    let session = manager.open_transport(opt.locator.clone()).await.unwrap();
-
-    let c_rtt = rtt.clone();
-    let c_counter = counter.clone();
+    let session = ready::retry(
+        Duration::from_secs_f64(opt.wait_ready.unwrap_or(0.0)),
+        || manager.open_transport(opt.locator.clone()),
+    )
+    .await
+    .unwrap();
+
+    let c_rtt = queries.rtt.clone();
+    let c_counter = queries.counter.clone();
     task::spawn(async move {
         loop {
             let now = Instant::now();
@@ -156,12 +185,13 @@ async fn main() {
             if c > 0 {
                 let interval = 1_000_000.0 / elapsed;
                 println!(
-                    "session,{},query.throughput,{},{},{},{}",
+                    "session,{},query.throughput,{},{},{},{},{}",
                     opt.scenario,
                     opt.name,
                     opt.payload,
                     (c as f64 / interval).floor() as usize,
                     (r as f64 / c as f64).floor() as usize,
+                    opt.window
                 );
             }
         }
@@ -188,15 +218,14 @@ async fn main() {
             attachment,
         );
 
-        // Insert the pending query
-        let barrier = Arc::new(Barrier::new(2));
-        pending.lock().unwrap().insert(count, barrier.clone());
-        let now = Instant::now();
+        // Wait for a slot in the window and insert the pending query
+        queries.window.acquire();
+        queries
+            .pending
+            .lock()
+            .unwrap()
+            .insert(count, Instant::now());
         session.handle_message(message).unwrap();
-        // Wait for the reply to arrive
-        barrier.wait();
-        rtt.fetch_add(now.elapsed().as_micros() as usize, Ordering::Relaxed);
-        counter.fetch_add(1, Ordering::Relaxed);
 
         count += 1;
     }
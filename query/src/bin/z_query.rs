@@ -13,9 +13,11 @@
 //
 use async_std::stream::StreamExt;
 use std::convert::TryInto;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::*;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "z_query")]
@@ -28,6 +30,10 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -38,7 +44,17 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
@@ -19,6 +19,7 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::*;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "z_query")]
@@ -33,6 +34,8 @@ struct Opt {
     scenario: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
 }
 
 #[async_std::main]
@@ -43,6 +46,10 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     let mut config = Properties::default();
     config.insert("mode".to_string(), opt.mode.clone());
 
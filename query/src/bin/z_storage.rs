@@ -0,0 +1,265 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::stream::StreamExt;
+use async_std::task;
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::process::{Child, Command};
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh::net::ZBuf;
+use zenoh::*;
+use zenoh_perf_throughput::value;
+
+// The storage is created on this path expression
+const STORAGE: &str = "/test/storage/**";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "z_storage")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = "tcp/127.0.0.1:7447")]
+    locator: String,
+    #[structopt(short = "m", long = "mode", default_value = "client")]
+    mode: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "k", long = "keys", default_value = "1000")]
+    keys: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(
+        short = "t",
+        long = "test",
+        default_value = "get,range,visibility,delete",
+        use_delimiter = true
+    )]
+    tests: Vec<String>,
+    #[structopt(long = "count", default_value = "1000")]
+    count: u64,
+    #[structopt(long = "duration", default_value = "5")]
+    duration: f64,
+    #[structopt(long = "zenohd", parse(from_os_str))]
+    zenohd: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+// A router started by the benchmark, killed on exit even on a panic
+struct Router(Child);
+
+impl Drop for Router {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+// Start a router with an in-memory storage listening on the locator
+fn start_router(zenohd: &PathBuf, locator: &str) -> Router {
+    let child = Command::new(zenohd)
+        .args(&[
+            "-l",
+            locator,
+            "--no-multicast-scouting",
+            "--mem-storage",
+            STORAGE,
+        ])
+        .spawn()
+        .unwrap_or_else(|e| panic!("Failed to start {}: {}", zenohd.display(), e));
+    Router(child)
+}
+
+// Returns the number of samples and the amount of bytes received
+async fn get(workspace: &Workspace<'_>, selector: &str) -> (usize, usize) {
+    let selector = Selector::try_from(selector.to_string()).unwrap();
+    let mut data_stream = workspace.get(&selector).await.unwrap();
+    let (mut count, mut payload) = (0, 0);
+    while let Some(data) = data_stream.next().await {
+        count += 1;
+        payload += value::len(&data.value);
+    }
+    (count, payload)
+}
+
+fn key(i: usize) -> Path {
+    Path::try_from(format!("/test/storage/{}", i)).unwrap()
+}
+
+// The keys are spread over the lookups, the ranges cover the keys starting with a digit
+fn point(n: u64, keys: usize) -> String {
+    format!("/test/storage/{}", (n as usize).wrapping_mul(7919) % keys)
+}
+
+fn range(n: u64) -> String {
+    format!("/test/storage/{}*", n % 10)
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    // The lookups are spread over the keys
+    assert!(opt.keys > 0, "At least one key is required");
+
+    let _router = opt.zenohd.as_ref().map(|z| start_router(z, &opt.locator));
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    config.insert("mode".to_string(), opt.mode.clone());
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), opt.locator.clone());
+
+    // The router may still be starting
+    let mut attempts = 0;
+    let zenoh = loop {
+        match Zenoh::new(config.clone().into()).await {
+            Ok(zenoh) => break zenoh,
+            Err(e) if attempts < 50 => {
+                log::debug!("Router not ready yet: {}", e);
+                attempts += 1;
+                task::sleep(Duration::from_millis(100)).await;
+            }
+            Err(e) => panic!("Unable to connect to {}: {}", opt.locator, e),
+        }
+    };
+    let workspace = zenoh.workspace(None).await.unwrap();
+
+    let data: ZBuf = (0usize..opt.payload)
+        .map(|i| (i % 10) as u8)
+        .collect::<Vec<u8>>()
+        .into();
+    let value = Value::from(data);
+
+    // Preload the storage and wait for all the keys to be stored
+    let now = Instant::now();
+    for i in 0..opt.keys {
+        workspace.put(&key(i), value.clone()).await.unwrap();
+    }
+    while get(&workspace, STORAGE).await.0 < opt.keys {
+        assert!(
+            now.elapsed() < Duration::from_secs(30),
+            "The storage did not receive all the keys, is it running on {}?",
+            STORAGE
+        );
+        task::sleep(Duration::from_millis(10)).await;
+    }
+    log::info!("Preloaded {} keys in {:?}", opt.keys, now.elapsed());
+
+    let duration = Duration::from_secs_f64(opt.duration);
+    for test in opt.tests.iter() {
+        match test.as_str() {
+            "get" | "range" => {
+                let selector = |n| match test.as_str() {
+                    "get" => point(n, opt.keys),
+                    _ => range(n),
+                };
+                for n in 0..opt.count {
+                    let now = Instant::now();
+                    let (count, payload) = get(&workspace, &selector(n)).await;
+                    println!(
+                        "zenoh,{},storage.{}.latency,{},{},{},{},{}",
+                        opt.scenario,
+                        test,
+                        opt.name,
+                        payload,
+                        n,
+                        count,
+                        now.elapsed().as_micros()
+                    );
+                }
+                let (now, mut n) = (Instant::now(), 0);
+                while now.elapsed() < duration {
+                    get(&workspace, &selector(n)).await;
+                    n += 1;
+                }
+                println!(
+                    "zenoh,{},storage.{}.throughput,{},{},{}",
+                    opt.scenario,
+                    test,
+                    opt.name,
+                    opt.payload,
+                    (n as f64 / now.elapsed().as_secs_f64()).floor() as usize
+                );
+            }
+            "visibility" => {
+                // Time for a put to be returned by a get on the same key
+                let path = Path::try_from("/test/storage/visibility").unwrap();
+                for n in 0..opt.count {
+                    let value = Value::from(format!("{:0width$}", n, width = opt.payload));
+                    let expected = value::len(&value);
+                    let now = Instant::now();
+                    workspace.put(&path, value).await.unwrap();
+                    loop {
+                        assert!(now.elapsed() < Duration::from_secs(30));
+                        let selector = Selector::try_from(path.to_string()).unwrap();
+                        let mut data_stream = workspace.get(&selector).await.unwrap();
+                        let mut visible = false;
+                        while let Some(data) = data_stream.next().await {
+                            if let Value::StringUtf8(s) = &data.value {
+                                visible |= s.parse::<u64>() == Ok(n);
+                            }
+                        }
+                        if visible {
+                            break;
+                        }
+                    }
+                    println!(
+                        "zenoh,{},storage.visibility,{},{},{},{}",
+                        opt.scenario,
+                        opt.name,
+                        expected,
+                        n,
+                        now.elapsed().as_micros()
+                    );
+                }
+            }
+            "delete" => {
+                let now = Instant::now();
+                for i in 0..opt.keys {
+                    workspace.delete(&key(i)).await.unwrap();
+                }
+                let sent = now.elapsed();
+                let visibility = Path::try_from("/test/storage/visibility").unwrap();
+                workspace.delete(&visibility).await.unwrap();
+                // Wait for the storage to be empty
+                while get(&workspace, STORAGE).await.0 > 0 {
+                    assert!(now.elapsed() < Duration::from_secs(30));
+                    task::sleep(Duration::from_millis(1)).await;
+                }
+                println!(
+                    "zenoh,{},storage.delete.throughput,{},{},{},{}",
+                    opt.scenario,
+                    opt.name,
+                    opt.payload,
+                    (opt.keys as f64 / sent.as_secs_f64()).floor() as usize,
+                    now.elapsed().as_micros()
+                );
+            }
+            _ => panic!("Unsupported test: {}", test),
+        }
+    }
+
+    zenoh.close().await.unwrap();
+}
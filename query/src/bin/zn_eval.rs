@@ -11,10 +11,14 @@
 // Contributors:
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
+use std::path::PathBuf;
 use structopt::StructOpt;
-use zenoh::net::queryable::EVAL;
+use zenoh::net::protocol::core::ZInt;
+use zenoh::net::runtime::Runtime;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_query::parse_kind;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_eval")]
@@ -25,6 +29,19 @@ struct Opt {
     mode: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(short = "q", long = "queryables", default_value = "1")]
+    queryables: usize,
+    #[structopt(
+        long = "kind",
+        default_value = "eval",
+        use_delimiter = true,
+        parse(try_from_str = parse_kind)
+    )]
+    kinds: Vec<ZInt>,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -35,27 +52,54 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
     match opt.mode.as_str() {
-        "peer" => config.insert("listener".to_string(), opt.locator),
-        "client" => config.insert("peer".to_string(), opt.locator),
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let session = open(config.into()).await.unwrap();
+    // Open the session over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let session = Session::init(runtime, true, vec![], vec![]).await;
 
     // The resource to read the data from
     let path = "/test/query".to_string();
     let reskey = ResKey::RName(path.clone());
-    let mut queryable = session.declare_queryable(&reskey, EVAL).await.unwrap();
-    while let Ok(query) = queryable.receiver().recv() {
-        query.reply(Sample {
-            res_name: path.clone(),
-            payload: vec![0u8; opt.payload].into(),
-            data_info: None,
-        });
+    // The kinds are assigned to the queryables in a round-robin fashion
+    let mut queryables = vec![];
+    for i in 0..opt.queryables {
+        let kind = opt.kinds[i % opt.kinds.len()];
+        queryables.push(session.declare_queryable(&reskey, kind).await.unwrap());
+    }
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
     }
+    std::thread::scope(|s| {
+        for queryable in queryables.iter_mut() {
+            let (path, payload) = (&path, opt.payload);
+            s.spawn(move || {
+                while let Ok(query) = queryable.receiver().recv() {
+                    query.reply(Sample {
+                        res_name: path.clone(),
+                        payload: vec![0u8; payload].into(),
+                        data_info: None,
+                    });
+                }
+            });
+        }
+    });
 }
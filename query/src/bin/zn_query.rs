@@ -12,11 +12,15 @@
 //   ADLINK zenoh team, <zenoh@adlink-labs.tech>
 //
 use async_std::stream::StreamExt;
-use std::time::Instant;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use structopt::StructOpt;
+use zenoh::net::protocol::core::{QueryConsolidation, Target, ZInt};
 use zenoh::net::ResKey;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_query::{parse_consolidation, parse_kind, parse_target, query_target};
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_query")]
@@ -29,6 +33,31 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(
+        short = "k",
+        long = "kind",
+        default_value = "all",
+        parse(try_from_str = parse_kind)
+    )]
+    kind: ZInt,
+    #[structopt(
+        short = "t",
+        long = "target",
+        default_value = "best-matching",
+        parse(try_from_str = parse_target)
+    )]
+    target: Target,
+    #[structopt(
+        short = "c",
+        long = "consolidation",
+        default_value = "lazy,lazy,full",
+        parse(try_from_str = parse_consolidation)
+    )]
+    consolidation: QueryConsolidation,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
 }
 
 #[async_std::main]
@@ -39,7 +68,17 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
-    let mut config = Properties::default();
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
@@ -51,8 +90,8 @@ async fn main() {
     loop {
         let reskey = ResKey::RName("/test/query".to_string());
         let predicate = "";
-        let target = QueryTarget::default();
-        let consolidation = QueryConsolidation::default();
+        let target = query_target(opt.kind, opt.target.clone());
+        let consolidation = opt.consolidation.clone();
 
         let now = Instant::now();
         let mut replies = session
@@ -61,16 +100,27 @@ async fn main() {
             .unwrap();
 
         let mut payload: usize = 0;
+        let mut first: Option<u128> = None;
+        let mut received: usize = 0;
         while let Some(reply) = replies.next().await {
+            first.get_or_insert_with(|| now.elapsed().as_micros());
             payload += reply.data.payload.len();
+            received += 1;
         }
+        let elapsed = now.elapsed().as_micros();
         println!(
             "zenoh-net,{},query.latency,{},{},{},{}",
+            opt.scenario, opt.name, payload, count, elapsed
+        );
+        println!(
+            "zenoh-net,{},query.replies,{},{},{},{},{},{}",
             opt.scenario,
             opt.name,
             payload,
             count,
-            now.elapsed().as_micros()
+            received,
+            first.map_or_else(|| "-".to_string(), |f| f.to_string()),
+            elapsed
         );
 
         count += 1;
@@ -20,6 +20,7 @@ use structopt::StructOpt;
 use zenoh::net::ResKey;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_query")]
@@ -34,6 +35,10 @@ struct Opt {
     scenario: String,
     #[structopt(short = "p", long = "payload")]
     payload: usize,
+    #[structopt(short = "w", long = "window", default_value = "1")]
+    window: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
 }
 
 #[async_std::main]
@@ -43,6 +48,12 @@ async fn main() {
 
     // Parse the args
     let opt = Opt::from_args();
+    // Each task keeps one query in flight, none would be sent with an empty window
+    assert!(opt.window > 0, "The window must be at least 1");
+
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
 
     let mut config = Properties::default();
     config.insert("mode".to_string(), opt.mode.clone());
@@ -50,10 +61,11 @@ async fn main() {
     config.insert("multicast_scouting".to_string(), "false".to_string());
     config.insert("peer".to_string(), opt.locator.clone());
 
-    let session = open(config.into()).await.unwrap();
+    let session = Arc::new(open(config.into()).await.unwrap());
 
     let rtt = Arc::new(AtomicUsize::new(0));
     let counter = Arc::new(AtomicUsize::new(0));
+    let window = opt.window;
 
     let c_rtt = rtt.clone();
     let c_counter = counter.clone();
@@ -68,31 +80,42 @@ async fn main() {
             if c > 0 {
                 let interval = 1_000_000.0 / elapsed;
                 println!(
-                    "zenoh-net,{},query.throughput,{},{},{},{}",
+                    "zenoh-net,{},query.throughput,{},{},{},{},{}",
                     opt.scenario,
                     opt.name,
                     opt.payload,
                     (c as f64 / interval).floor() as usize,
-                    (r as f64 / c as f64).floor() as usize
+                    (r as f64 / c as f64).floor() as usize,
+                    opt.window
                 );
             }
         }
     });
 
-    loop {
-        let reskey = ResKey::RName("/test/query".to_string());
-        let predicate = "";
-        let target = QueryTarget::default();
-        let consolidation = QueryConsolidation::default();
+    // Each task keeps one query in flight
+    let mut tasks = vec![];
+    for _ in 0..window {
+        let (session, rtt, counter) = (session.clone(), rtt.clone(), counter.clone());
+        tasks.push(task::spawn(async move {
+            loop {
+                let reskey = ResKey::RName("/test/query".to_string());
+                let predicate = "";
+                let target = QueryTarget::default();
+                let consolidation = QueryConsolidation::default();
 
-        let now = Instant::now();
-        let mut replies = session
-            .query(&reskey, predicate, target, consolidation)
-            .await
-            .unwrap();
-        while replies.next().await.is_some() {}
+                let now = Instant::now();
+                let mut replies = session
+                    .query(&reskey, predicate, target, consolidation)
+                    .await
+                    .unwrap();
+                while replies.next().await.is_some() {}
 
-        rtt.fetch_add(now.elapsed().as_micros() as usize, Ordering::Relaxed);
-        counter.fetch_add(1, Ordering::Relaxed);
+                rtt.fetch_add(now.elapsed().as_micros() as usize, Ordering::Relaxed);
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+        }));
+    }
+    for t in tasks.into_iter() {
+        t.await;
     }
 }
@@ -0,0 +1,188 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zenoh::net::protocol::core::{rname, ZInt};
+use zenoh::net::runtime::Runtime;
+use zenoh::net::*;
+use zenoh::{Properties, Selector};
+use zenoh_perf_query::parse_kind;
+use zenoh_perf_throughput::ready;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_workload_eval")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "k", long = "keys", default_value = "1000")]
+    keys: usize,
+    #[structopt(long = "kind", default_value = "eval", parse(try_from_str = parse_kind))]
+    kind: ZInt,
+    // The samples per query, one per matching key if not set
+    #[structopt(short = "r", long = "replies")]
+    replies: Option<usize>,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Op {
+    fn eval(self, id: usize, value: usize) -> bool {
+        match self {
+            Op::Lt => id < value,
+            Op::Le => id <= value,
+            Op::Eq => id == value,
+            Op::Ge => id >= value,
+            Op::Gt => id > value,
+        }
+    }
+}
+
+// The filter of a selector, a conjunction of comparisons of the index of the keys,
+// e.g. `id>=10&id<20`. An empty filter matches all the keys.
+fn parse_filter(filter: &str) -> Result<Vec<(Op, usize)>, String> {
+    filter
+        .split('&')
+        .map(str::trim)
+        .filter(|term| !term.is_empty())
+        .map(|term| {
+            let rest = term
+                .strip_prefix("id")
+                .ok_or_else(|| format!("Unsupported filter: {}", term))?;
+            // The two characters operators first
+            let ops = [
+                ("<=", Op::Le),
+                (">=", Op::Ge),
+                ("<", Op::Lt),
+                (">", Op::Gt),
+                ("=", Op::Eq),
+            ];
+            let (op, value) = ops
+                .iter()
+                .find_map(|(s, op)| rest.strip_prefix(s).map(|v| (*op, v)))
+                .ok_or_else(|| format!("Unsupported filter: {}", term))?;
+            let value = value
+                .trim()
+                .parse()
+                .map_err(|e| format!("Invalid filter {}: {}", term, e))?;
+            Ok((op, value))
+        })
+        .collect()
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let mut config = Properties::default();
+    config.insert("mode".to_string(), opt.mode.clone());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    match opt.mode.as_str() {
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
+        _ => panic!("Unsupported mode: {}", opt.mode),
+    };
+
+    // Open the session over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let session = Session::init(runtime, true, vec![], vec![]).await;
+
+    // The keys served by this queryable
+    let keys: Vec<String> = (0..opt.keys)
+        .map(|i| format!("/test/workload/{}", i))
+        .collect();
+    let reskey = ResKey::RName("/test/workload/**".to_string());
+    let mut queryable = session.declare_queryable(&reskey, opt.kind).await.unwrap();
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+    while let Ok(query) = queryable.receiver().recv() {
+        // Parse the selector as a storage would do
+        let selector = format!("{}?{}", query.res_name, query.predicate);
+        let selector = match Selector::try_from(selector) {
+            Ok(selector) => selector,
+            Err(e) => {
+                log::warn!("Invalid selector: {}", e);
+                continue;
+            }
+        };
+        let filter = match parse_filter(selector.filter.as_deref().unwrap_or("")) {
+            Ok(filter) => filter,
+            Err(e) => {
+                log::warn!("{}", e);
+                continue;
+            }
+        };
+        let matching: Vec<&String> = keys
+            .iter()
+            .enumerate()
+            .filter(|(i, key)| {
+                rname::intersect(selector.path_expr.as_str(), key)
+                    && filter.iter().all(|(op, value)| op.eval(*i, *value))
+            })
+            .map(|(_, key)| key)
+            .collect();
+        // The samples are taken in turn from the matching keys: the queries must not
+        // be fully consolidated to receive more samples than matching keys
+        let count = opt.replies.unwrap_or_else(|| matching.len());
+        for key in matching.iter().cycle().take(count) {
+            query.reply(Sample {
+                res_name: key.to_string(),
+                payload: vec![0u8; opt.payload].into(),
+                data_info: None,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter() {
+        assert_eq!(parse_filter(""), Ok(vec![]));
+        assert_eq!(
+            parse_filter("id>=10 & id<20"),
+            Ok(vec![(Op::Ge, 10), (Op::Lt, 20)])
+        );
+        assert_eq!(parse_filter("id=3"), Ok(vec![(Op::Eq, 3)]));
+        assert!(parse_filter("id~3").is_err());
+        assert!(parse_filter("value<3").is_err());
+        assert!(parse_filter("id<x").is_err());
+        assert!(Op::Le.eval(3, 3) && !Op::Lt.eval(3, 3) && Op::Gt.eval(4, 3));
+    }
+}
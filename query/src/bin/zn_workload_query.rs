@@ -0,0 +1,170 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::stream::StreamExt;
+use async_std::sync::Arc;
+use async_std::task;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh::net::protocol::core::QueryConsolidation;
+use zenoh::net::ResKey;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_common::cpu;
+use zenoh_perf_query::parse_consolidation;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_workload_query")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(short = "k", long = "keys", default_value = "1000")]
+    keys: usize,
+    #[structopt(long = "selector", default_value = "exact")]
+    selector: String,
+    #[structopt(long = "predicate", default_value = "")]
+    predicate: String,
+    #[structopt(
+        short = "c",
+        long = "consolidation",
+        default_value = "lazy,lazy,full",
+        parse(try_from_str = parse_consolidation)
+    )]
+    consolidation: QueryConsolidation,
+    #[structopt(long = "count", default_value = "1000")]
+    count: u64,
+    #[structopt(long = "router-pid")]
+    router_pid: Option<u32>,
+}
+
+// The kind and the selector of the n-th query, the keys are those served by zn_workload_eval
+fn selector(kind: &str, n: u64, keys: usize) -> (&str, String) {
+    let i = n as usize % keys.max(1);
+    match kind {
+        // A single key
+        "exact" => (kind, format!("/test/workload/{}", i)),
+        // The keys starting with the same digit
+        "prefix" => (kind, format!("/test/workload/{}*", i % 10)),
+        // All the keys, matched chunk by chunk
+        "star" => (kind, "/test/workload/*".to_string()),
+        // All the keys, matched across chunks
+        "double-star" => (kind, "/test/**".to_string()),
+        // A mix of all of the above
+        "mixed" => {
+            let kinds = ["exact", "prefix", "star", "double-star"];
+            selector(
+                kinds[n as usize % kinds.len()],
+                n / kinds.len() as u64,
+                keys,
+            )
+        }
+        _ => panic!("Unsupported selector: {}", kind),
+    }
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let mut config = Properties::default();
+    config.insert("mode".to_string(), opt.mode.clone());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), opt.locator.clone());
+
+    let session = open(config.into()).await.unwrap();
+
+    // The payload of the replies, as served by zn_workload_eval
+    let size = Arc::new(AtomicUsize::new(0));
+
+    // Sample the load of the router while the workload runs
+    let running = Arc::new(AtomicBool::new(true));
+    let sampler = opt.router_pid.map(|pid| {
+        let (c_running, c_size) = (running.clone(), size.clone());
+        let (scenario, name) = (opt.scenario.clone(), opt.name.clone());
+        task::spawn(async move {
+            let mut router = cpu::Load::new(pid).unwrap();
+            while c_running.load(Ordering::Relaxed) {
+                task::sleep(Duration::from_secs(1)).await;
+                let load = router.sample().unwrap();
+                println!(
+                    "zenoh-net,{},cpu.router,{},{},{:.3}",
+                    scenario,
+                    name,
+                    c_size.load(Ordering::Relaxed),
+                    load
+                );
+            }
+        })
+    });
+
+    for n in 0..opt.count {
+        let (kind, selector) = selector(&opt.selector, n, opt.keys);
+        let reskey = ResKey::RName(selector);
+        let target = QueryTarget::default();
+        let consolidation = opt.consolidation.clone();
+
+        let now = Instant::now();
+        let mut replies = session
+            .query(&reskey, &opt.predicate, target, consolidation)
+            .await
+            .unwrap();
+
+        let mut payload: usize = 0;
+        let mut received: usize = 0;
+        let mut first: Option<u128> = None;
+        while let Some(reply) = replies.next().await {
+            first.get_or_insert_with(|| now.elapsed().as_micros());
+            payload += reply.data.payload.len();
+            received += 1;
+        }
+        let elapsed = now.elapsed().as_micros();
+        if received > 0 {
+            size.store(payload / received, Ordering::Relaxed);
+        }
+        // One row per metric, each kind of selector is a series of its own
+        let row = |test: &str, value: String| {
+            println!(
+                "zenoh-net,{},{}.{},{},{},{}",
+                opt.scenario,
+                test,
+                kind,
+                opt.name,
+                size.load(Ordering::Relaxed),
+                value
+            );
+        };
+        row("query.latency", elapsed.to_string());
+        if let Some(first) = first {
+            row("query.latency.first", first.to_string());
+        }
+        row("query.replies", received.to_string());
+    }
+
+    running.store(false, Ordering::Relaxed);
+    if let Some(sampler) = sampler {
+        sampler.await;
+    }
+    session.close().await.unwrap();
+}
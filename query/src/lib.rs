@@ -0,0 +1,119 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Options shared by the query benchmarks to select the queryable kinds, the query
+//! target and the consolidation applied at each stage of the routing.
+use std::sync::{Condvar, Mutex};
+use zenoh::net::protocol::core::{
+    ConsolidationMode, QueryConsolidation, QueryTarget, Target, ZInt,
+};
+use zenoh::net::queryable::{ALL_KINDS, EVAL, STORAGE};
+
+pub fn parse_kind(s: &str) -> Result<ZInt, String> {
+    match s {
+        "eval" => Ok(EVAL),
+        "storage" => Ok(STORAGE),
+        "all" => Ok(ALL_KINDS),
+        _ => Err(format!("Unsupported queryable kind: {}", s)),
+    }
+}
+
+pub fn kind_name(kind: ZInt) -> &'static str {
+    match kind {
+        EVAL => "eval",
+        STORAGE => "storage",
+        ALL_KINDS => "all",
+        _ => "other",
+    }
+}
+
+pub fn parse_target(s: &str) -> Result<Target, String> {
+    match s {
+        "best-matching" => Ok(Target::BestMatching),
+        "all" => Ok(Target::All),
+        "all-complete" => Ok(Target::AllComplete),
+        "none" => Ok(Target::None),
+        _ => Err(format!("Unsupported query target: {}", s)),
+    }
+}
+
+pub fn query_target(kind: ZInt, target: Target) -> QueryTarget {
+    QueryTarget { kind, target }
+}
+
+fn parse_mode(s: &str) -> Result<ConsolidationMode, String> {
+    match s {
+        "none" => Ok(ConsolidationMode::None),
+        "lazy" => Ok(ConsolidationMode::Lazy),
+        "full" => Ok(ConsolidationMode::Full),
+        _ => Err(format!("Unsupported consolidation mode: {}", s)),
+    }
+}
+
+/// Either a single mode applied to all the stages, e.g. `full`, or one mode per
+/// stage in the `first_routers,last_router,reception` order, e.g. `lazy,lazy,full`.
+pub fn parse_consolidation(s: &str) -> Result<QueryConsolidation, String> {
+    let modes = s
+        .split(',')
+        .map(|m| parse_mode(m.trim()))
+        .collect::<Result<Vec<ConsolidationMode>, String>>()?;
+    match modes.as_slice() {
+        [mode] => Ok(QueryConsolidation {
+            first_routers: mode.clone(),
+            last_router: mode.clone(),
+            reception: mode.clone(),
+        }),
+        [first_routers, last_router, reception] => Ok(QueryConsolidation {
+            first_routers: first_routers.clone(),
+            last_router: last_router.clone(),
+            reception: reception.clone(),
+        }),
+        _ => Err(format!(
+            "Expected one or three consolidation modes, got: {}",
+            s
+        )),
+    }
+}
+
+/// Bounds the number of queries in flight.
+pub struct Window {
+    size: usize,
+    in_flight: Mutex<usize>,
+    cv: Condvar,
+}
+
+impl Window {
+    pub fn new(size: usize) -> Window {
+        assert!(size > 0, "The window must be at least 1");
+        Window {
+            size,
+            in_flight: Mutex::new(0),
+            cv: Condvar::new(),
+        }
+    }
+
+    /// Blocks until a new query can be issued.
+    pub fn acquire(&self) {
+        let mut guard = self.in_flight.lock().unwrap();
+        while *guard >= self.size {
+            guard = self.cv.wait(guard).unwrap();
+        }
+        *guard += 1;
+    }
+
+    /// Called once the final reply of a query is received.
+    pub fn release(&self) {
+        *self.in_flight.lock().unwrap() -= 1;
+        self.cv.notify_one();
+    }
+}
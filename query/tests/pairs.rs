@@ -0,0 +1,121 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each query benchmark against its queryable on an ephemeral port.
+use zenoh_perf_throughput::testing::*;
+
+fn eval(eval: &str) -> (Process, String) {
+    let eval = Process::spawn(eval, &["-l", "tcp/127.0.0.1:0", "-m", "peer", "-p", "64"]);
+    let locator = eval.ready();
+    (eval, locator)
+}
+
+fn latency(layer: &str, query: &str, eval: &str) {
+    let (_eval, locator) = self::eval(eval);
+    let query = Process::spawn(
+        query,
+        &["-l", &locator, "-m", "peer", "-n", "pair", "-s", "test"],
+    );
+    for row in query.test(5, "query.latency").iter() {
+        assert_eq!(row.len(), 7, "Unexpected line: {:?}", row);
+        assert_eq!(row[0], layer);
+        let _us: u128 = row[6].parse().unwrap();
+    }
+}
+
+fn throughput(layer: &str, query: &str, eval: &str) {
+    let (_eval, locator) = self::eval(eval);
+    let query = Process::spawn(
+        query,
+        &[
+            "-l", &locator, "-m", "peer", "-p", "64", "-n", "pair", "-s", "test",
+        ],
+    );
+    for row in query.test(3, "query.throughput").iter() {
+        assert_eq!(row[0], layer);
+        assert_eq!(row[4], "64");
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+    }
+}
+
+#[test]
+fn t_query_t_eval() {
+    latency(
+        "session",
+        env!("CARGO_BIN_EXE_t_query"),
+        env!("CARGO_BIN_EXE_t_eval"),
+    );
+}
+
+#[test]
+fn r_query_r_eval() {
+    latency(
+        "router",
+        env!("CARGO_BIN_EXE_r_query"),
+        env!("CARGO_BIN_EXE_r_eval"),
+    );
+}
+
+#[test]
+fn zn_query_zn_eval() {
+    latency(
+        "zenoh-net",
+        env!("CARGO_BIN_EXE_zn_query"),
+        env!("CARGO_BIN_EXE_zn_eval"),
+    );
+}
+
+#[test]
+fn z_query_z_eval() {
+    latency(
+        "zenoh",
+        env!("CARGO_BIN_EXE_z_query"),
+        env!("CARGO_BIN_EXE_z_eval"),
+    );
+}
+
+#[test]
+fn t_query_thr_t_eval() {
+    throughput(
+        "session",
+        env!("CARGO_BIN_EXE_t_query_thr"),
+        env!("CARGO_BIN_EXE_t_eval"),
+    );
+}
+
+#[test]
+fn r_query_thr_r_eval() {
+    throughput(
+        "router",
+        env!("CARGO_BIN_EXE_r_query_thr"),
+        env!("CARGO_BIN_EXE_r_eval"),
+    );
+}
+
+#[test]
+fn zn_query_thr_zn_eval() {
+    throughput(
+        "zenoh-net",
+        env!("CARGO_BIN_EXE_zn_query_thr"),
+        env!("CARGO_BIN_EXE_zn_eval"),
+    );
+}
+
+#[test]
+fn z_query_thr_z_eval() {
+    throughput(
+        "zenoh",
+        env!("CARGO_BIN_EXE_z_query_thr"),
+        env!("CARGO_BIN_EXE_z_eval"),
+    );
+}
@@ -0,0 +1,60 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Runs the storage benchmark against a zenohd with an in-memory storage. zenohd
+//! is not part of this workspace, so the test is ignored unless run with
+//! `ZENOHD=<path to zenohd> cargo test --features unixsock-stream -- --ignored`,
+//! zenohd being built with unix sockets too.
+#![cfg(feature = "unixsock-stream")]
+use zenoh_perf_throughput::testing::*;
+
+#[test]
+#[ignore]
+fn z_storage_zenohd() {
+    let zenohd = std::env::var("ZENOHD").expect("ZENOHD must be the path of a zenohd binary");
+    // A socket unique to this run, no port has to be picked before zenohd binds it
+    let locator = unixsock("storage");
+    let mut storage = Process::spawn(
+        env!("CARGO_BIN_EXE_z_storage"),
+        &[
+            "-l",
+            &locator,
+            "--zenohd",
+            &zenohd,
+            "-p",
+            "64",
+            "-k",
+            "100",
+            "--count",
+            "10",
+            "--duration",
+            "1",
+            "-n",
+            "storage",
+            "-s",
+            "test",
+        ],
+    );
+    for row in storage.test(10, "storage.get.latency").iter() {
+        assert_eq!(row.len(), 8, "Unexpected line: {:?}", row);
+        assert_eq!(row[6], "1", "Not a single key: {:?}", row);
+    }
+    for test in ["range", "delete"].iter() {
+        let row = storage
+            .test(1, &format!("storage.{}.throughput", test))
+            .remove(0);
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+    }
+    assert!(storage.status().success());
+}
@@ -0,0 +1,150 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Runs each query benchmark against its queryable over TLS or QUIC with
+//! throwaway certificates, next to plain TCP for every reply size.
+#![cfg(any(feature = "tls", feature = "quic"))]
+use std::path::Path;
+use zenoh_perf_throughput::certs;
+use zenoh_perf_throughput::testing::*;
+
+const PAYLOADS: [&str; 3] = ["64", "8192", "65536"];
+
+// The latency lines of a pair, the queryable listening on `locator`
+fn run(query: &str, eval: &str, locator: &str, conf: &Path, payload: &str) -> Vec<String> {
+    let conf = conf.to_str().unwrap();
+    let eval = Process::spawn(
+        eval,
+        &["-l", locator, "-m", "peer", "-p", payload, "--conf", conf],
+    );
+    let locator = eval.ready();
+    let query = Process::spawn(
+        query,
+        &[
+            "-l", &locator, "-m", "peer", "-n", "pair", "-s", "test", "--conf", conf,
+        ],
+    );
+    query.lines(5, "query.latency")
+}
+
+// Query latencies in microseconds, the last value of the latency lines
+fn latencies(lines: &[String]) -> Vec<u128> {
+    lines
+        .iter()
+        .map(|l| l.rsplit(',').next().unwrap().parse().unwrap())
+        .collect()
+}
+
+fn median(lines: &[String]) -> u128 {
+    let mut latencies = latencies(lines);
+    latencies.sort_unstable();
+    latencies[latencies.len() / 2]
+}
+
+// Prints the latencies side by side and saves both series for `zperf-compare`
+fn compare(proto: &str, query: &str, eval: &str) {
+    let conf = certs::generate(&certs::temp_dir());
+    let (mut secure, mut tcp) = (vec![], vec![]);
+    println!("payload,tcp latency us,{} latency us", proto);
+    for payload in PAYLOADS.iter() {
+        let s = run(
+            query,
+            eval,
+            &format!("{}/localhost:0", proto),
+            &conf,
+            payload,
+        );
+        let t = run(query, eval, "tcp/127.0.0.1:0", &conf, payload);
+        assert!(
+            latencies(&s)
+                .iter()
+                .chain(latencies(&t).iter())
+                .all(|l| *l > 0),
+            "Null latency for {} B: {:?} over {}, {:?} over TCP",
+            payload,
+            s,
+            proto,
+            t
+        );
+        println!("{},{},{}", payload, median(&t), median(&s));
+        secure.extend(s);
+        tcp.extend(t);
+    }
+    let name = Path::new(query).file_stem().unwrap().to_str().unwrap();
+    println!(
+        "zperf-compare -b {} -c {}",
+        save(&format!("{}-tcp", name), &tcp).display(),
+        save(&format!("{}-{}", name, proto), &secure).display()
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn t_query_t_eval_tls() {
+    compare(
+        "tls",
+        env!("CARGO_BIN_EXE_t_query"),
+        env!("CARGO_BIN_EXE_t_eval"),
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn zn_query_zn_eval_tls() {
+    compare(
+        "tls",
+        env!("CARGO_BIN_EXE_zn_query"),
+        env!("CARGO_BIN_EXE_zn_eval"),
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn z_query_z_eval_tls() {
+    compare(
+        "tls",
+        env!("CARGO_BIN_EXE_z_query"),
+        env!("CARGO_BIN_EXE_z_eval"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn t_query_t_eval_quic() {
+    compare(
+        "quic",
+        env!("CARGO_BIN_EXE_t_query"),
+        env!("CARGO_BIN_EXE_t_eval"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn zn_query_zn_eval_quic() {
+    compare(
+        "quic",
+        env!("CARGO_BIN_EXE_zn_query"),
+        env!("CARGO_BIN_EXE_zn_eval"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn z_query_z_eval_quic() {
+    compare(
+        "quic",
+        env!("CARGO_BIN_EXE_z_query"),
+        env!("CARGO_BIN_EXE_z_eval"),
+    );
+}
@@ -0,0 +1,74 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Runs the query workloads against their queryable on an ephemeral port.
+use std::collections::BTreeSet;
+use zenoh_perf_throughput::testing::*;
+
+#[test]
+fn zn_workload_query_zn_workload_eval() {
+    let eval = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_workload_eval"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-k",
+            "100",
+        ],
+    );
+    let locator = eval.ready();
+    let mut query = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_workload_query"),
+        &[
+            "-l",
+            &locator,
+            "-m",
+            "peer",
+            "-k",
+            "100",
+            "--selector",
+            "mixed",
+            "--count",
+            "20",
+            "-n",
+            "workload",
+            "-s",
+            "test",
+        ],
+    );
+    // One row of replies per query, each kind of selector is a series of its own
+    let rows: Vec<Vec<String>> = query
+        .take(20, |l| l.contains(",query.replies."))
+        .iter()
+        .map(|l| l.split(',').map(|f| f.to_string()).collect())
+        .collect();
+    let mut kinds = BTreeSet::new();
+    let mut replies = 0;
+    for row in rows.iter() {
+        assert_eq!(row.len(), 6, "Unexpected line: {:?}", row);
+        kinds.insert(row[2].trim_start_matches("query.replies.").to_string());
+        replies += row[5].parse::<usize>().unwrap();
+    }
+    let expected: BTreeSet<String> = ["double-star", "exact", "prefix", "star"]
+        .iter()
+        .map(|k| k.to_string())
+        .collect();
+    assert_eq!(kinds, expected);
+    assert!(replies > 0, "No reply: {:?}", rows);
+    assert!(query.status().success());
+}
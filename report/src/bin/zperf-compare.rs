@@ -0,0 +1,138 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zenoh_perf_report::compare::{self, Method, Options, Status};
+use zenoh_perf_report::record;
+use zenoh_perf_report::table::Table;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zperf-compare")]
+struct Opt {
+    #[structopt(short = "b", long = "baseline", parse(from_os_str), required = true)]
+    baseline: Vec<PathBuf>,
+    #[structopt(short = "c", long = "candidate", parse(from_os_str), required = true)]
+    candidate: Vec<PathBuf>,
+    #[structopt(short = "t", long = "threshold", default_value = "5.0")]
+    threshold: f64,
+    #[structopt(short = "a", long = "alpha", default_value = "0.05")]
+    alpha: f64,
+    #[structopt(long = "method", default_value = "mann-whitney")]
+    method: Method,
+    #[structopt(long = "rounds", default_value = "2000")]
+    rounds: usize,
+    #[structopt(long = "min-samples", default_value = "5")]
+    min_samples: usize,
+    #[structopt(long = "allow-missing")]
+    allow_missing: bool,
+    #[structopt(short = "j", long = "json", parse(from_os_str))]
+    json: Option<PathBuf>,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let baseline = record::load_all(&opt.baseline).unwrap();
+    let candidate = record::load_all(&opt.candidate).unwrap();
+    if baseline.is_empty() || candidate.is_empty() {
+        eprintln!("No result found in the baseline or in the candidate");
+        std::process::exit(2);
+    }
+
+    let options = Options {
+        method: opt.method,
+        threshold: opt.threshold,
+        alpha: opt.alpha,
+        rounds: opt.rounds,
+        min_samples: opt.min_samples,
+        allow_missing: opt.allow_missing,
+    };
+    if !options.reachable() {
+        eprintln!(
+            "{} samples can never be significant at alpha {} with {:?}, raise --min-samples",
+            opt.min_samples, opt.alpha, opt.method
+        );
+        std::process::exit(2);
+    }
+    let report = compare::compare(&baseline, &candidate, &options);
+
+    let mut table = Table::new(
+        &format!(
+            "Candidate vs baseline (threshold {}%, alpha {})",
+            opt.threshold, opt.alpha
+        ),
+        &[
+            "protocol",
+            "layer",
+            "scenario",
+            "test",
+            "payload",
+            "n",
+            "baseline",
+            "candidate",
+            "delta %",
+            "CI %",
+            "p",
+            "status",
+        ],
+    );
+    for c in report.comparisons.iter() {
+        let status = match c.status {
+            Status::Regression => "REGRESSION".to_string(),
+            s => s.to_string(),
+        };
+        table.push(vec![
+            c.key.protocol.clone(),
+            c.key.layer.clone(),
+            c.key.scenario.clone(),
+            c.key.test.clone(),
+            c.key.payload.to_string(),
+            format!("{}/{}", c.base_samples, c.cand_samples),
+            format!("{:.1}", c.base_median),
+            format!("{:.1}", c.cand_median),
+            format!("{:+.2}", c.delta),
+            format!("[{:+.2}, {:+.2}]", c.ci.0, c.ci.1),
+            format!("{:.4}", c.p_value),
+            status,
+        ]);
+    }
+    println!("{}", table.to_text());
+    for k in report.missing.iter() {
+        println!("Missing in candidate: {} payload {}", k.series(), k.payload);
+    }
+    for k in report.new.iter() {
+        println!("New in candidate: {} payload {}", k.series(), k.payload);
+    }
+
+    let regressions = report.regressions();
+    println!(
+        "{} comparisons, {} regressions, {} missing",
+        report.comparisons.len(),
+        regressions,
+        report.missing.len()
+    );
+
+    if let Some(path) = opt.json.as_ref() {
+        fs::write(path, report.to_json(&options)).unwrap();
+    }
+
+    if report.failures(&options) > 0 {
+        std::process::exit(1);
+    }
+}
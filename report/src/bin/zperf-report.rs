@@ -0,0 +1,262 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zenoh_perf_report::chart::{self, Axis, Chart, Series};
+use zenoh_perf_report::record::{self, Key};
+use zenoh_perf_report::stats;
+use zenoh_perf_report::table::Table;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zperf-report")]
+struct Opt {
+    #[structopt(short = "o", long = "output", parse(from_os_str))]
+    output: Option<PathBuf>,
+    #[structopt(parse(from_os_str), required = true)]
+    files: Vec<PathBuf>,
+}
+
+fn throughput_table(groups: &BTreeMap<Key, Vec<f64>>) -> Table {
+    let mut table = Table::new(
+        "Throughput",
+        &[
+            "protocol",
+            "layer",
+            "scenario",
+            "test",
+            "payload",
+            "samples",
+            "median msg/s",
+            "Gbit/s",
+        ],
+    );
+    for (k, v) in groups
+        .iter()
+        .filter(|(k, _)| record::is_throughput(&k.test))
+    {
+        let median = stats::median(v);
+        table.push(vec![
+            k.protocol.clone(),
+            k.layer.clone(),
+            k.scenario.clone(),
+            k.test.clone(),
+            k.payload.to_string(),
+            v.len().to_string(),
+            format!("{:.0}", median),
+            format!("{:.3}", stats::gbps(median, k.payload)),
+        ]);
+    }
+    table
+}
+
+fn latency_table(groups: &BTreeMap<Key, Vec<f64>>) -> Table {
+    let mut table = Table::new(
+        "Latency (us)",
+        &[
+            "protocol", "layer", "scenario", "test", "payload", "samples", "p50", "p90", "p99",
+            "p99.9", "max",
+        ],
+    );
+    for (k, v) in groups.iter().filter(|(k, _)| record::is_latency(&k.test)) {
+        let sorted = stats::sorted(v);
+        let mut row = vec![
+            k.protocol.clone(),
+            k.layer.clone(),
+            k.scenario.clone(),
+            k.test.clone(),
+            k.payload.to_string(),
+            v.len().to_string(),
+        ];
+        for p in [50.0, 90.0, 99.0, 99.9, 100.0].iter() {
+            row.push(format!("{:.0}", stats::percentile(&sorted, *p)));
+        }
+        table.push(row);
+    }
+    table
+}
+
+fn overhead_table(groups: &BTreeMap<Key, Vec<f64>>) -> Table {
+    let mut table = Table::new(
+        "Overhead (bytes/msg)",
+        &[
+            "protocol", "layer", "test", "payload", "samples", "wire", "overhead",
+        ],
+    );
+    for (k, v) in groups.iter().filter(|(k, _)| record::is_overhead(&k.test)) {
+        let wire = stats::median(v);
+        table.push(vec![
+            k.protocol.clone(),
+            k.layer.clone(),
+            k.test.clone(),
+            k.payload.to_string(),
+            v.len().to_string(),
+            format!("{:.1}", wire),
+            format!("{:.1}", wire - k.payload as f64),
+        ]);
+    }
+    table
+}
+
+fn cpu_table(groups: &BTreeMap<Key, Vec<f64>>) -> Table {
+    let mut table = Table::new(
+        "CPU load (cores)",
+        &[
+            "protocol", "layer", "scenario", "test", "payload", "samples", "median", "max",
+        ],
+    );
+    for (k, v) in groups.iter().filter(|(k, _)| record::is_cpu(&k.test)) {
+        let sorted = stats::sorted(v);
+        table.push(vec![
+            k.protocol.clone(),
+            k.layer.clone(),
+            k.scenario.clone(),
+            k.test.clone(),
+            k.payload.to_string(),
+            v.len().to_string(),
+            format!("{:.3}", stats::median(v)),
+            format!("{:.3}", stats::percentile(&sorted, 100.0)),
+        ]);
+    }
+    table
+}
+
+fn throughput_charts(groups: &BTreeMap<Key, Vec<f64>>) -> Vec<(String, String)> {
+    let mut msgs: BTreeMap<String, Vec<(f64, f64)>> = BTreeMap::new();
+    let mut gbps: BTreeMap<String, Vec<(f64, f64)>> = BTreeMap::new();
+    for (k, v) in groups
+        .iter()
+        .filter(|(k, _)| record::is_throughput(&k.test))
+    {
+        let median = stats::median(v);
+        msgs.entry(k.series())
+            .or_default()
+            .push((k.payload as f64, median));
+        gbps.entry(k.series())
+            .or_default()
+            .push((k.payload as f64, stats::gbps(median, k.payload)));
+    }
+    if msgs.is_empty() {
+        return vec![];
+    }
+    let to_series = |m: BTreeMap<String, Vec<(f64, f64)>>| -> Vec<Series> {
+        m.into_iter()
+            .map(|(label, points)| Series { label, points })
+            .collect()
+    };
+
+    let chart = Chart {
+        title: "Throughput vs payload".to_string(),
+        x: Axis::log("payload (bytes)"),
+        y: Axis::log("msg/s"),
+        markers: true,
+    };
+    let c_msgs = chart.render(&to_series(msgs));
+    let chart = Chart {
+        title: "Throughput vs payload".to_string(),
+        x: Axis::log("payload (bytes)"),
+        y: Axis::linear("Gbit/s"),
+        markers: true,
+    };
+    let c_gbps = chart.render(&to_series(gbps));
+    vec![
+        ("throughput_msgs.svg".to_string(), c_msgs),
+        ("throughput_gbps.svg".to_string(), c_gbps),
+    ]
+}
+
+fn latency_charts(groups: &BTreeMap<Key, Vec<f64>>) -> Vec<(String, String)> {
+    // One CDF chart per payload
+    let mut payloads: BTreeMap<usize, Vec<Series>> = BTreeMap::new();
+    for (k, v) in groups.iter().filter(|(k, _)| record::is_latency(&k.test)) {
+        payloads.entry(k.payload).or_default().push(Series {
+            label: k.series(),
+            points: chart::cdf(v),
+        });
+    }
+    payloads
+        .into_iter()
+        .map(|(payload, series)| {
+            let chart = Chart {
+                title: format!("Latency CDF, payload {} bytes", payload),
+                x: Axis::log("latency (us)"),
+                y: Axis::linear("fraction"),
+                markers: false,
+            };
+            (format!("latency_{}.svg", payload), chart.render(&series))
+        })
+        .collect()
+}
+
+fn html(tables: &[Table], charts: &[(String, String)]) -> String {
+    let mut s = String::new();
+    s.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    s.push_str("<title>zenoh-perf report</title>\n<style>\n");
+    s.push_str("body { font-family: sans-serif; margin: 2em; }\n");
+    s.push_str("table { border-collapse: collapse; margin-bottom: 2em; }\n");
+    s.push_str("th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: right; }\n");
+    s.push_str("th { background: #eee; }\n</style>\n</head>\n<body>\n");
+    s.push_str("<h1>zenoh-perf report</h1>\n");
+    for t in tables.iter().filter(|t| !t.is_empty()) {
+        s.push_str(&t.to_html());
+    }
+    if !charts.is_empty() {
+        s.push_str("<h2>Charts</h2>\n");
+    }
+    for (_, c) in charts.iter() {
+        s.push_str("<div>\n");
+        s.push_str(c);
+        s.push_str("</div>\n");
+    }
+    s.push_str("</body>\n</html>\n");
+    s
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let records = record::load_all(&opt.files).unwrap();
+    if records.is_empty() {
+        eprintln!("No result found in {:?}", opt.files);
+        std::process::exit(1);
+    }
+    let groups = record::group(&records);
+
+    let tables = vec![
+        throughput_table(&groups),
+        latency_table(&groups),
+        overhead_table(&groups),
+        cpu_table(&groups),
+    ];
+    for t in tables.iter().filter(|t| !t.is_empty()) {
+        println!("{}", t.to_text());
+    }
+
+    if let Some(dir) = opt.output.as_ref() {
+        let mut charts = throughput_charts(&groups);
+        charts.append(&mut latency_charts(&groups));
+
+        fs::create_dir_all(dir).unwrap();
+        for (file, svg) in charts.iter() {
+            fs::write(dir.join(file), svg).unwrap();
+        }
+        fs::write(dir.join("report.html"), html(&tables, &charts)).unwrap();
+        println!("Report written to {}", dir.join("report.html").display());
+    }
+}
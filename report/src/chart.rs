@@ -0,0 +1,291 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Self-contained SVG charts, without any script nor external resource so that
+//! they can be embedded as is in the HTML report.
+use crate::stats;
+use crate::table::escape;
+use std::fmt::Write;
+
+const WIDTH: f64 = 900.0;
+const HEIGHT: f64 = 500.0;
+const LEFT: f64 = 80.0;
+const RIGHT: f64 = 640.0;
+const TOP: f64 = 40.0;
+const BOTTOM: f64 = 440.0;
+const COLORS: [&str; 10] = [
+    "#1f77b4", "#ff7f0e", "#2ca02c", "#d62728", "#9467bd", "#8c564b", "#e377c2", "#7f7f7f",
+    "#bcbd22", "#17becf",
+];
+const CDF_POINTS: usize = 500;
+
+pub struct Series {
+    pub label: String,
+    pub points: Vec<(f64, f64)>,
+}
+
+pub struct Axis {
+    pub label: String,
+    pub log: bool,
+}
+
+impl Axis {
+    pub fn linear(label: &str) -> Axis {
+        Axis {
+            label: label.to_string(),
+            log: false,
+        }
+    }
+
+    pub fn log(label: &str) -> Axis {
+        Axis {
+            label: label.to_string(),
+            log: true,
+        }
+    }
+}
+
+pub struct Chart {
+    pub title: String,
+    pub x: Axis,
+    pub y: Axis,
+    pub markers: bool,
+}
+
+struct Scale {
+    lo: f64,
+    hi: f64,
+    log: bool,
+}
+
+impl Scale {
+    fn new(values: &[f64], log: bool) -> Scale {
+        let min = values.iter().cloned().fold(f64::INFINITY, f64::min);
+        let max = values.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        if !min.is_finite() || !max.is_finite() {
+            return Scale {
+                lo: 0.0,
+                hi: 1.0,
+                log,
+            };
+        }
+        if log {
+            let lo = min.log10().floor();
+            let mut hi = max.log10().ceil();
+            if hi <= lo {
+                hi = lo + 1.0;
+            }
+            Scale { lo, hi, log }
+        } else {
+            let lo = if min >= 0.0 { 0.0 } else { min };
+            let mut hi = max * 1.05;
+            if hi <= lo {
+                hi = lo + 1.0;
+            }
+            Scale { lo, hi, log }
+        }
+    }
+
+    /// Position of `v` in [0, 1].
+    fn map(&self, v: f64) -> f64 {
+        if self.log {
+            (v.log10() - self.lo) / (self.hi - self.lo)
+        } else {
+            (v - self.lo) / (self.hi - self.lo)
+        }
+    }
+
+    fn ticks(&self) -> Vec<f64> {
+        if self.log {
+            (self.lo as i32..=self.hi as i32)
+                .map(|e| 10f64.powi(e))
+                .collect()
+        } else {
+            (0..=5)
+                .map(|i| self.lo + (self.hi - self.lo) * i as f64 / 5.0)
+                .collect()
+        }
+    }
+}
+
+pub fn format_number(v: f64) -> String {
+    let a = v.abs();
+    if a >= 1e9 {
+        format!("{:.1}G", v / 1e9)
+    } else if a >= 1e6 {
+        format!("{:.1}M", v / 1e6)
+    } else if a >= 1e3 {
+        format!("{:.1}k", v / 1e3)
+    } else if a >= 1.0 || a == 0.0 {
+        format!("{:.0}", v)
+    } else {
+        format!("{:.2}", v)
+    }
+}
+
+impl Chart {
+    pub fn render(&self, series: &[Series]) -> String {
+        let valid = |v: f64, log: bool| v.is_finite() && (!log || v > 0.0);
+        let xs: Vec<f64> = series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|p| p.0))
+            .filter(|v| valid(*v, self.x.log))
+            .collect();
+        let ys: Vec<f64> = series
+            .iter()
+            .flat_map(|s| s.points.iter().map(|p| p.1))
+            .filter(|v| valid(*v, self.y.log))
+            .collect();
+        let sx = Scale::new(&xs, self.x.log);
+        let sy = Scale::new(&ys, self.y.log);
+        let px = |v: f64| LEFT + sx.map(v) * (RIGHT - LEFT);
+        let py = |v: f64| BOTTOM - sy.map(v) * (BOTTOM - TOP);
+
+        let mut s = String::new();
+        writeln!(
+            s,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="sans-serif" font-size="12">"#,
+            w = WIDTH,
+            h = HEIGHT
+        )
+        .unwrap();
+        writeln!(
+            s,
+            r#"<rect width="{}" height="{}" fill="white"/>"#,
+            WIDTH, HEIGHT
+        )
+        .unwrap();
+        writeln!(
+            s,
+            r#"<text x="{}" y="24" text-anchor="middle" font-size="16">{}</text>"#,
+            (LEFT + RIGHT) / 2.0,
+            escape(&self.title)
+        )
+        .unwrap();
+
+        // Grid and ticks
+        for t in sx.ticks() {
+            let x = px(t);
+            writeln!(
+                s,
+                r##"<line x1="{x:.1}" y1="{}" x2="{x:.1}" y2="{}" stroke="#ddd"/><text x="{x:.1}" y="{}" text-anchor="middle">{}</text>"##,
+                TOP,
+                BOTTOM,
+                BOTTOM + 16.0,
+                format_number(t),
+                x = x
+            )
+            .unwrap();
+        }
+        for t in sy.ticks() {
+            let y = py(t);
+            writeln!(
+                s,
+                r##"<line x1="{}" y1="{y:.1}" x2="{}" y2="{y:.1}" stroke="#ddd"/><text x="{}" y="{y:.1}" text-anchor="end" dominant-baseline="middle">{}</text>"##,
+                LEFT,
+                RIGHT,
+                LEFT - 6.0,
+                format_number(t),
+                y = y
+            )
+            .unwrap();
+        }
+        writeln!(
+            s,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" fill="none" stroke="black"/>"#,
+            LEFT,
+            TOP,
+            RIGHT - LEFT,
+            BOTTOM - TOP
+        )
+        .unwrap();
+        writeln!(
+            s,
+            r#"<text x="{}" y="{}" text-anchor="middle">{}</text>"#,
+            (LEFT + RIGHT) / 2.0,
+            BOTTOM + 40.0,
+            escape(&self.x.label)
+        )
+        .unwrap();
+        writeln!(
+            s,
+            r#"<text x="20" y="{y}" text-anchor="middle" transform="rotate(-90 20 {y})">{}</text>"#,
+            escape(&self.y.label),
+            y = (TOP + BOTTOM) / 2.0
+        )
+        .unwrap();
+
+        // Series and legend
+        for (i, serie) in series.iter().enumerate() {
+            let color = COLORS[i % COLORS.len()];
+            let points: Vec<(f64, f64)> = serie
+                .points
+                .iter()
+                .filter(|p| valid(p.0, self.x.log) && valid(p.1, self.y.log))
+                .map(|p| (px(p.0), py(p.1)))
+                .collect();
+            let path = points
+                .iter()
+                .map(|(x, y)| format!("{:.1},{:.1}", x, y))
+                .collect::<Vec<String>>()
+                .join(" ");
+            writeln!(
+                s,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="2"/>"#,
+                path, color
+            )
+            .unwrap();
+            if self.markers {
+                for (x, y) in points.iter() {
+                    writeln!(
+                        s,
+                        r#"<circle cx="{:.1}" cy="{:.1}" r="3" fill="{}"/>"#,
+                        x, y, color
+                    )
+                    .unwrap();
+                }
+            }
+            let ly = TOP + 10.0 + 18.0 * i as f64;
+            writeln!(
+                s,
+                r#"<line x1="{}" y1="{ly}" x2="{}" y2="{ly}" stroke="{}" stroke-width="3"/><text x="{}" y="{ly}" dominant-baseline="middle">{}</text>"#,
+                RIGHT + 15.0,
+                RIGHT + 35.0,
+                color,
+                RIGHT + 40.0,
+                escape(&serie.label),
+                ly = ly
+            )
+            .unwrap();
+        }
+        s.push_str("</svg>\n");
+        s
+    }
+}
+
+/// Empirical cumulative distribution of `values`, down-sampled to a few hundred points.
+pub fn cdf(values: &[f64]) -> Vec<(f64, f64)> {
+    let sorted = stats::sorted(values);
+    let n = sorted.len();
+    let step = (n / CDF_POINTS).max(1);
+    let mut points: Vec<(f64, f64)> = sorted
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| i % step == 0)
+        .map(|(i, v)| (*v, (i + 1) as f64 / n as f64))
+        .collect();
+    if let Some(last) = sorted.last() {
+        points.push((*last, 1.0));
+    }
+    points
+}
@@ -0,0 +1,405 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Comparison of a candidate result set against a baseline.
+use crate::record::{self, Key, Record};
+use crate::stats;
+use std::fmt::{self, Write};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    Bootstrap,
+    MannWhitney,
+}
+
+impl std::str::FromStr for Method {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Method, String> {
+        match s {
+            "bootstrap" => Ok(Method::Bootstrap),
+            "mann-whitney" => Ok(Method::MannWhitney),
+            _ => Err(format!("Unsupported method: {}", s)),
+        }
+    }
+}
+
+pub struct Options {
+    pub method: Method,
+    /// Relative change in % beyond which a significant change is reported.
+    pub threshold: f64,
+    pub alpha: f64,
+    pub rounds: usize,
+    pub min_samples: usize,
+    /// Whether keys of the baseline may be missing in the candidate.
+    pub allow_missing: bool,
+}
+
+impl Options {
+    /// Whether a change can be significant at all with `min_samples` on each side:
+    /// Mann-Whitney cannot go below a certain p-value with few samples, e.g. 0.08 for 3.
+    pub fn reachable(&self) -> bool {
+        match self.method {
+            Method::Bootstrap => true,
+            Method::MannWhitney => {
+                let n = self.min_samples;
+                let a: Vec<f64> = (0..n).map(|i| i as f64).collect();
+                let b: Vec<f64> = (n..2 * n).map(|i| i as f64).collect();
+                stats::mann_whitney(&a, &b) < self.alpha
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    Regression,
+    Improvement,
+    Unchanged,
+    Insufficient,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Status::Regression => "regression",
+            Status::Improvement => "improvement",
+            Status::Unchanged => "unchanged",
+            Status::Insufficient => "insufficient",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+pub struct Comparison {
+    pub key: Key,
+    pub base_samples: usize,
+    pub cand_samples: usize,
+    pub base_median: f64,
+    pub cand_median: f64,
+    /// Relative change of the median in %.
+    pub delta: f64,
+    pub ci: (f64, f64),
+    pub p_value: f64,
+    pub status: Status,
+}
+
+/// Throughputs should go up, latencies and overheads down.
+pub fn higher_is_better(test: &str) -> bool {
+    record::is_throughput(test)
+}
+
+fn seed(key: &Key) -> u64 {
+    // FNV-1a, so that the resampling is reproducible for a given key
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in format!("{}/{}", key.series(), key.payload).bytes() {
+        h ^= b as u64;
+        h = h.wrapping_mul(0x0100_0000_01b3);
+    }
+    h
+}
+
+fn compare_one(key: Key, base: &[f64], cand: &[f64], opt: &Options) -> Comparison {
+    let base_median = stats::median(base);
+    let cand_median = stats::median(cand);
+    let delta = if base_median != 0.0 {
+        (cand_median - base_median) / base_median * 100.0
+    } else {
+        f64::NAN
+    };
+
+    let enough = base.len() >= opt.min_samples && cand.len() >= opt.min_samples;
+    let (ci, p_value) = if enough {
+        (
+            stats::bootstrap_ci(base, cand, opt.alpha, opt.rounds, seed(&key)),
+            stats::mann_whitney(base, cand),
+        )
+    } else {
+        ((f64::NAN, f64::NAN), f64::NAN)
+    };
+    let significant = match opt.method {
+        Method::Bootstrap => ci.0 > 0.0 || ci.1 < 0.0,
+        Method::MannWhitney => p_value < opt.alpha,
+    };
+
+    let worse = if higher_is_better(&key.test) {
+        -delta
+    } else {
+        delta
+    };
+    let status = if !enough || delta.is_nan() {
+        Status::Insufficient
+    } else if significant && worse > opt.threshold {
+        Status::Regression
+    } else if significant && -worse > opt.threshold {
+        Status::Improvement
+    } else {
+        Status::Unchanged
+    };
+
+    Comparison {
+        key,
+        base_samples: base.len(),
+        cand_samples: cand.len(),
+        base_median,
+        cand_median,
+        delta,
+        ci,
+        p_value,
+        status,
+    }
+}
+
+pub struct Report {
+    pub comparisons: Vec<Comparison>,
+    /// Keys only present in the baseline.
+    pub missing: Vec<Key>,
+    /// Keys only present in the candidate.
+    pub new: Vec<Key>,
+}
+
+impl Report {
+    pub fn regressions(&self) -> usize {
+        self.comparisons
+            .iter()
+            .filter(|c| c.status == Status::Regression)
+            .count()
+    }
+
+    /// The regressions, plus the keys missing in the candidate unless allowed.
+    pub fn failures(&self, opt: &Options) -> usize {
+        let missing = if opt.allow_missing {
+            0
+        } else {
+            self.missing.len()
+        };
+        self.regressions() + missing
+    }
+}
+
+pub fn compare(base: &[Record], cand: &[Record], opt: &Options) -> Report {
+    let base = record::group(base);
+    let mut cand = record::group(cand);
+
+    let mut comparisons = vec![];
+    let mut missing = vec![];
+    for (k, b) in base.into_iter() {
+        match cand.remove(&k) {
+            Some(c) => comparisons.push(compare_one(k, &b, &c, opt)),
+            None => missing.push(k),
+        }
+    }
+    let new = cand.into_keys().collect();
+
+    Report {
+        comparisons,
+        missing,
+        new,
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn json_number(v: f64) -> String {
+    if v.is_finite() {
+        format!("{}", v)
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_key(k: &Key) -> String {
+    format!(
+        r#"{{"protocol":{},"layer":{},"scenario":{},"test":{},"payload":{}}}"#,
+        json_string(&k.protocol),
+        json_string(&k.layer),
+        json_string(&k.scenario),
+        json_string(&k.test),
+        k.payload
+    )
+}
+
+impl Report {
+    pub fn to_json(&self, opt: &Options) -> String {
+        let mut s = String::new();
+        s.push_str("{\n");
+        writeln!(
+            s,
+            r#"  "method": {}, "threshold": {}, "alpha": {}, "regressions": {},"#,
+            json_string(match opt.method {
+                Method::Bootstrap => "bootstrap",
+                Method::MannWhitney => "mann-whitney",
+            }),
+            json_number(opt.threshold),
+            json_number(opt.alpha),
+            self.regressions()
+        )
+        .unwrap();
+        s.push_str("  \"comparisons\": [\n");
+        let entries: Vec<String> = self
+            .comparisons
+            .iter()
+            .map(|c| {
+                format!(
+                    r#"    {{"key":{},"status":{},"base_samples":{},"cand_samples":{},"base_median":{},"cand_median":{},"delta":{},"ci":[{},{}],"p_value":{}}}"#,
+                    json_key(&c.key),
+                    json_string(&c.status.to_string()),
+                    c.base_samples,
+                    c.cand_samples,
+                    json_number(c.base_median),
+                    json_number(c.cand_median),
+                    json_number(c.delta),
+                    json_number(c.ci.0),
+                    json_number(c.ci.1),
+                    json_number(c.p_value)
+                )
+            })
+            .collect();
+        s.push_str(&entries.join(",\n"));
+        s.push_str("\n  ],\n");
+        let keys = |keys: &[Key]| -> String {
+            keys.iter().map(json_key).collect::<Vec<String>>().join(",")
+        };
+        writeln!(s, "  \"missing\": [{}],", keys(&self.missing)).unwrap();
+        writeln!(s, "  \"new\": [{}]", keys(&self.new)).unwrap();
+        s.push_str("}\n");
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(method: Method) -> Options {
+        Options {
+            method,
+            threshold: 5.0,
+            alpha: 0.05,
+            rounds: 1_000,
+            min_samples: 5,
+            allow_missing: false,
+        }
+    }
+
+    // `count` samples spread by 1% around `value`
+    fn records(test: &str, payload: usize, value: f64, count: usize) -> Vec<Record> {
+        (0..count)
+            .map(|i| Record {
+                protocol: "zenoh".to_string(),
+                layer: "zn".to_string(),
+                scenario: "local".to_string(),
+                test: test.to_string(),
+                name: "pair".to_string(),
+                payload,
+                value: value * (1.0 + ((i % 5) as f64 - 2.0) / 200.0),
+            })
+            .collect()
+    }
+
+    fn status(base: &[Record], cand: &[Record], method: Method) -> Status {
+        let report = compare(base, cand, &options(method));
+        assert_eq!(report.comparisons.len(), 1);
+        report.comparisons[0].status
+    }
+
+    #[test]
+    fn regression() {
+        for method in [Method::Bootstrap, Method::MannWhitney].iter() {
+            let base = records("throughput", 64, 1_000.0, 20);
+            let cand = records("throughput", 64, 800.0, 20);
+            assert_eq!(status(&base, &cand, *method), Status::Regression);
+            assert_eq!(status(&cand, &base, *method), Status::Improvement);
+
+            // Lower is better for latencies
+            let base = records("latency.sequential", 64, 100.0, 20);
+            let cand = records("latency.sequential", 64, 120.0, 20);
+            assert_eq!(status(&base, &cand, *method), Status::Regression);
+        }
+        let report = compare(
+            &records("throughput", 64, 1_000.0, 20),
+            &records("throughput", 64, 800.0, 20),
+            &options(Method::Bootstrap),
+        );
+        assert_eq!(report.regressions(), 1);
+        assert!((report.comparisons[0].delta + 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn no_change() {
+        for method in [Method::Bootstrap, Method::MannWhitney].iter() {
+            let base = records("throughput", 64, 1_000.0, 20);
+            assert_eq!(status(&base, &base, *method), Status::Unchanged);
+
+            // Significant, but below the threshold
+            let cand = records("throughput", 64, 980.0, 20);
+            assert_eq!(status(&base, &cand, *method), Status::Unchanged);
+        }
+    }
+
+    #[test]
+    fn tiny_samples() {
+        for method in [Method::Bootstrap, Method::MannWhitney].iter() {
+            let base = records("throughput", 64, 1_000.0, 4);
+            let cand = records("throughput", 64, 500.0, 20);
+            assert_eq!(status(&base, &cand, *method), Status::Insufficient);
+            assert_eq!(status(&cand, &base, *method), Status::Insufficient);
+        }
+        let base = records("throughput", 64, 0.0, 20);
+        let cand = records("throughput", 64, 10.0, 20);
+        assert_eq!(
+            status(&base, &cand, Method::Bootstrap),
+            Status::Insufficient
+        );
+    }
+
+    #[test]
+    fn missing_and_new() {
+        let mut base = records("throughput", 64, 1_000.0, 5);
+        base.extend(records("throughput", 128, 1_000.0, 5));
+        let mut cand = records("throughput", 64, 1_000.0, 5);
+        cand.extend(records("throughput", 256, 1_000.0, 5));
+        let report = compare(&base, &cand, &options(Method::Bootstrap));
+        assert_eq!(report.comparisons.len(), 1);
+        assert_eq!(report.missing[0].payload, 128);
+        assert_eq!(report.new[0].payload, 256);
+        // A missing key fails the gate unless allowed, a new one does not
+        let mut opt = options(Method::Bootstrap);
+        assert_eq!(report.failures(&opt), 1);
+        opt.allow_missing = true;
+        assert_eq!(report.failures(&opt), 0);
+    }
+
+    #[test]
+    fn reachable() {
+        let mut opt = options(Method::MannWhitney);
+        assert!(opt.reachable());
+        opt.min_samples = 3;
+        assert!(!opt.reachable());
+        opt.method = Method::Bootstrap;
+        assert!(opt.reachable());
+    }
+}
@@ -0,0 +1,420 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Parsing of the records printed by the benchmark binaries.
+//!
+//! Two formats are understood:
+//!  - the result lines `<protocol>,<scenario>,<test>,<name>,<payload>,<values>...` printed
+//!    by the throughput, latency and query binaries and by the comparison programs;
+//!  - the CSV header + row pairs printed by the overhead analyzers, whose first column
+//!    is `protocol`.
+//!
+//! Any other line (e.g. `1234 msg/s`) is ignored.
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+
+pub const THROUGHPUT: &str = "throughput";
+pub const LATENCY: &str = "latency";
+pub const QUERY_THROUGHPUT: &str = "query.throughput";
+pub const QUERY_LATENCY: &str = "query.latency";
+pub const OVERHEAD: &str = "overhead";
+pub const CPU: &str = "cpu";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub protocol: String,
+    pub layer: String,
+    pub scenario: String,
+    pub test: String,
+    pub name: String,
+    pub payload: usize,
+    /// msg/s for throughputs, microseconds for latencies, wire bytes per message for overheads,
+    /// cores for CPU loads.
+    pub value: f64,
+}
+
+/// Records are normalized by protocol, layer, scenario, test and payload.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Key {
+    pub protocol: String,
+    pub layer: String,
+    pub scenario: String,
+    pub test: String,
+    pub payload: usize,
+}
+
+impl Key {
+    /// The series a key belongs to, i.e. everything but the payload.
+    pub fn series(&self) -> String {
+        format!(
+            "{}/{}/{}/{}",
+            self.protocol, self.layer, self.scenario, self.test
+        )
+    }
+}
+
+impl Record {
+    pub fn key(&self) -> Key {
+        Key {
+            protocol: self.protocol.clone(),
+            layer: self.layer.clone(),
+            scenario: self.scenario.clone(),
+            test: self.test.clone(),
+            payload: self.payload,
+        }
+    }
+
+    pub fn is_throughput(&self) -> bool {
+        is_throughput(&self.test)
+    }
+
+    pub fn is_latency(&self) -> bool {
+        is_latency(&self.test)
+    }
+}
+
+/// A throughput test, possibly a variant of it, e.g. `throughput.b8192.sn256.qos`
+/// for a configuration of the transport sweep.
+pub fn is_throughput(test: &str) -> bool {
+    is_variant(test, THROUGHPUT) || test == QUERY_THROUGHPUT
+}
+
+/// A latency test, the query latencies may be split by kind of selector,
+/// e.g. `query.latency.prefix`.
+pub fn is_latency(test: &str) -> bool {
+    test.starts_with(LATENCY) || is_variant(test, QUERY_LATENCY)
+}
+
+/// The CPU load of a process sampled during a test, e.g. `cpu.router`.
+pub fn is_cpu(test: &str) -> bool {
+    is_variant(test, CPU)
+}
+
+fn is_variant(test: &str, base: &str) -> bool {
+    match test.strip_prefix(base) {
+        Some(variant) => variant.is_empty() || variant.starts_with('.'),
+        None => false,
+    }
+}
+
+pub fn is_overhead(test: &str) -> bool {
+    test.starts_with(OVERHEAD)
+}
+
+/// The zenoh binaries identify the layer in the protocol column.
+fn protocol_layer(protocol: &str) -> (String, String) {
+    match protocol {
+        "zenoh-net" => ("zenoh".to_string(), "zn".to_string()),
+        "zenoh" => ("zenoh".to_string(), "z".to_string()),
+        "router" => ("zenoh".to_string(), "r".to_string()),
+        "session" => ("zenoh".to_string(), "t".to_string()),
+        p => (p.to_string(), p.to_string()),
+    }
+}
+
+/// Some comparison programs use slightly different test names.
+fn normalize_test(test: &str) -> &str {
+    match test {
+        "throughout" => THROUGHPUT,
+        "query.throughout" => QUERY_THROUGHPUT,
+        "latency" => "latency.sequential",
+        t => t,
+    }
+}
+
+#[derive(Default)]
+pub struct Parser {
+    header: Option<Vec<String>>,
+}
+
+impl Parser {
+    pub fn new() -> Parser {
+        Parser::default()
+    }
+
+    pub fn parse_line(&mut self, line: &str) -> Option<Record> {
+        let fields: Vec<&str> = line.trim().split(',').map(|f| f.trim()).collect();
+        if fields.len() < 2 {
+            return None;
+        }
+        if fields[0] == "protocol" {
+            self.header = Some(fields.iter().map(|f| f.to_string()).collect());
+            return None;
+        }
+        if fields.len() >= 6 && fields[4].parse::<usize>().is_ok() {
+            if let Some(record) = parse_result(&fields) {
+                return Some(record);
+            }
+        }
+        match self.header.take() {
+            Some(header) if header.len() == fields.len() => parse_overhead(&header, &fields),
+            header => {
+                self.header = header;
+                None
+            }
+        }
+    }
+}
+
+fn parse_result(fields: &[&str]) -> Option<Record> {
+    let test = normalize_test(fields[2]);
+    let value: f64 = if is_throughput(test) || is_cpu(test) {
+        fields[5].parse().ok()?
+    } else if is_latency(test) {
+        fields.last()?.parse().ok()?
+    } else {
+        return None;
+    };
+    // e.g. a `NaN` printed by a run that received nothing
+    if !value.is_finite() {
+        return None;
+    }
+    let (protocol, layer) = protocol_layer(fields[0]);
+    Some(Record {
+        protocol,
+        layer,
+        scenario: fields[1].to_string(),
+        test: test.to_string(),
+        name: fields[3].to_string(),
+        payload: fields[4].parse().ok()?,
+        value,
+    })
+}
+
+fn parse_overhead(header: &[String], fields: &[&str]) -> Option<Record> {
+    let row: BTreeMap<&str, &str> = header
+        .iter()
+        .map(|h| h.as_str())
+        .zip(fields.iter().copied())
+        .collect();
+    let get = |k: &str| -> Option<f64> {
+        row.get(k)
+            .and_then(|v| v.parse().ok())
+            .filter(|v: &f64| v.is_finite())
+    };
+
+    let payload = get("payload")?;
+    let wire = get("total_wire")?;
+    let messages = match get("ops").or_else(|| get("messages")) {
+        Some(m) => m,
+        None if payload > 0.0 => get("total_payload")? / payload,
+        None => return None,
+    };
+    if messages <= 0.0 {
+        return None;
+    }
+    let test = match row.get("op") {
+        Some(op) => format!("{}.{}", OVERHEAD, op),
+        None => OVERHEAD.to_string(),
+    };
+    let (protocol, layer) = protocol_layer(row.get("protocol")?);
+    Some(Record {
+        protocol,
+        layer,
+        scenario: "-".to_string(),
+        test,
+        name: "-".to_string(),
+        payload: payload as usize,
+        value: wire / messages,
+    })
+}
+
+pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Vec<Record>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut parser = Parser::new();
+    let mut records = vec![];
+    for line in reader.lines() {
+        if let Some(r) = parser.parse_line(&line?) {
+            records.push(r);
+        }
+    }
+    Ok(records)
+}
+
+/// Loads all the given files; directories are read non-recursively.
+pub fn load_all<P: AsRef<Path>>(paths: &[P]) -> io::Result<Vec<Record>> {
+    let mut records = vec![];
+    for p in paths.iter() {
+        let p = p.as_ref();
+        if p.is_dir() {
+            let mut entries = std::fs::read_dir(p)?
+                .map(|e| e.map(|e| e.path()))
+                .collect::<io::Result<Vec<_>>>()?;
+            entries.sort();
+            for e in entries.iter().filter(|e| e.is_file()) {
+                records.append(&mut load(e)?);
+            }
+        } else {
+            records.append(&mut load(p)?);
+        }
+    }
+    Ok(records)
+}
+
+pub fn group(records: &[Record]) -> BTreeMap<Key, Vec<f64>> {
+    let mut groups: BTreeMap<Key, Vec<f64>> = BTreeMap::new();
+    for r in records.iter() {
+        groups.entry(r.key()).or_default().push(r.value);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(lines: &[&str]) -> Vec<Record> {
+        let mut parser = Parser::new();
+        lines.iter().filter_map(|l| parser.parse_line(l)).collect()
+    }
+
+    #[test]
+    fn result_lines() {
+        let records = parse(&[
+            "zenoh-net,local,throughput,pair,64,123456",
+            "session,local,latency.sequential,pair,1024,10,20,35.5",
+        ]);
+        assert_eq!(
+            records[0],
+            Record {
+                protocol: "zenoh".to_string(),
+                layer: "zn".to_string(),
+                scenario: "local".to_string(),
+                test: THROUGHPUT.to_string(),
+                name: "pair".to_string(),
+                payload: 64,
+                value: 123_456.0,
+            }
+        );
+        // The latency is the last value
+        assert_eq!(records[1].layer, "t");
+        assert_eq!(records[1].value, 35.5);
+    }
+
+    #[test]
+    fn normalized_test_names() {
+        let records = parse(&[
+            "mqtt,local,throughout,pair,8,100",
+            "mqtt,local,query.throughout,pair,8,100",
+            "zeromq,local,latency,pair,8,20",
+        ]);
+        let tests: Vec<&str> = records.iter().map(|r| r.test.as_str()).collect();
+        assert_eq!(
+            tests,
+            vec![THROUGHPUT, QUERY_THROUGHPUT, "latency.sequential"]
+        );
+        assert_eq!(records[0].protocol, "mqtt");
+        assert_eq!(records[0].layer, "mqtt");
+    }
+
+    #[test]
+    fn throughput_variants() {
+        let records = parse(&[
+            "session,local,throughput.b8192.sn256.qos,pair,64,100,0.1,5,8192,256,true",
+            "session,local,throughput.b1024.sn256.qos,pair,64,200,0.1,5,1024,256,true",
+        ]);
+        assert_eq!(records.len(), 2);
+        assert!(records.iter().all(|r| r.is_throughput() && r.value > 0.0));
+        // Each configuration is a series of its own
+        assert_ne!(records[0].key(), records[1].key());
+        assert!(!is_throughput("throughputs"));
+    }
+
+    #[test]
+    fn query_workload() {
+        let records = parse(&[
+            "zenoh-net,local,query.latency.prefix,workload,64,350",
+            "zenoh-net,local,query.latency.first.prefix,workload,64,120",
+            "zenoh-net,local,query.replies.prefix,workload,64,10",
+            "zenoh-net,local,cpu.router,workload,64,0.750",
+        ]);
+        let tests: Vec<&str> = records.iter().map(|r| r.test.as_str()).collect();
+        assert_eq!(
+            tests,
+            vec![
+                "query.latency.prefix",
+                "query.latency.first.prefix",
+                "cpu.router"
+            ]
+        );
+        assert!(records[0].is_latency() && records[1].is_latency());
+        assert!(is_cpu(&records[2].test));
+        assert_eq!(records[2].value, 0.75);
+        assert!(!is_cpu("cpus"));
+    }
+
+    #[test]
+    fn ignored_lines() {
+        assert!(parse(&[
+            "1234 msg/s",
+            "",
+            "zenoh-net,local,unknown,pair,64,1",
+            "zenoh-net,local,throughput,pair,big,1",
+            "zenoh-net,local,throughput,pair,64,fast",
+            "zenoh-net,local,latency.sequential,pair,64,NaN",
+            "zenoh-net,local,throughput,pair,64,inf",
+        ])
+        .is_empty());
+    }
+
+    #[test]
+    fn overhead_rows() {
+        let records = parse(&[
+            "protocol,payload,total_payload,total_wire",
+            "zenoh,100,1000,1500",
+            "protocol,op,payload,ops,total_wire",
+            "mqtt,publish,8,4,100",
+            // A row not matching the header
+            "mqtt,publish,8,4",
+            "protocol,payload,messages,total_wire",
+            "zenoh,8,NaN,100",
+        ]);
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            (
+                records[0].test.as_str(),
+                records[0].payload,
+                records[0].value
+            ),
+            (OVERHEAD, 100, 150.0)
+        );
+        assert_eq!(
+            (
+                records[1].test.as_str(),
+                records[1].payload,
+                records[1].value
+            ),
+            ("overhead.publish", 8, 25.0)
+        );
+    }
+
+    #[test]
+    fn grouped_by_key() {
+        let records = parse(&[
+            "zenoh-net,local,throughput,a,64,1",
+            "zenoh-net,local,throughput,b,64,2",
+            "zenoh-net,local,throughput,a,128,3",
+        ]);
+        let groups = group(&records);
+        assert_eq!(
+            groups.values().cloned().collect::<Vec<_>>(),
+            vec![vec![1.0, 2.0], vec![3.0]]
+        );
+        assert_eq!(
+            groups.keys().next().unwrap().series(),
+            "zenoh/zn/local/throughput"
+        );
+    }
+}
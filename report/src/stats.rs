@@ -0,0 +1,227 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+pub fn sorted(values: &[f64]) -> Vec<f64> {
+    let mut v = values.to_vec();
+    v.sort_by(f64::total_cmp);
+    v
+}
+
+/// Percentile with linear interpolation between the closest ranks, `p` in [0, 100].
+/// The values must be sorted.
+pub fn percentile(sorted: &[f64], p: f64) -> f64 {
+    if sorted.is_empty() {
+        return f64::NAN;
+    }
+    let rank = (p / 100.0) * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+pub fn median(values: &[f64]) -> f64 {
+    percentile(&sorted(values), 50.0)
+}
+
+pub fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return f64::NAN;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Throughput in Gbit/s of `msgs` messages per second of `payload` bytes.
+pub fn gbps(msgs: f64, payload: usize) -> f64 {
+    msgs * payload as f64 * 8.0 / 1_000_000_000.0
+}
+
+/// A small deterministic PRNG (SplitMix64), good enough for resampling and
+/// reproducible across runs.
+pub struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> SplitMix64 {
+        SplitMix64(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+}
+
+fn resample_median(values: &[f64], rng: &mut SplitMix64, buf: &mut Vec<f64>) -> f64 {
+    buf.clear();
+    for _ in 0..values.len() {
+        buf.push(values[rng.below(values.len())]);
+    }
+    buf.sort_by(f64::total_cmp);
+    percentile(buf, 50.0)
+}
+
+/// Bootstrap confidence interval at level `1 - alpha` of the relative change (in %)
+/// of the median from `base` to `cand`.
+pub fn bootstrap_ci(
+    base: &[f64],
+    cand: &[f64],
+    alpha: f64,
+    rounds: usize,
+    seed: u64,
+) -> (f64, f64) {
+    let mut rng = SplitMix64::new(seed);
+    let mut buf = Vec::with_capacity(base.len().max(cand.len()));
+    let mut deltas = Vec::with_capacity(rounds);
+    for _ in 0..rounds {
+        let b = resample_median(base, &mut rng, &mut buf);
+        let c = resample_median(cand, &mut rng, &mut buf);
+        if b != 0.0 {
+            deltas.push((c - b) / b * 100.0);
+        }
+    }
+    let deltas = sorted(&deltas);
+    (
+        percentile(&deltas, alpha / 2.0 * 100.0),
+        percentile(&deltas, (1.0 - alpha / 2.0) * 100.0),
+    )
+}
+
+/// Standard normal cumulative distribution function.
+pub fn normal_cdf(z: f64) -> f64 {
+    // Abramowitz and Stegun 7.1.26, absolute error below 1.5e-7
+    let x = z.abs() / std::f64::consts::SQRT_2;
+    let t = 1.0 / (1.0 + 0.327_591_1 * x);
+    let poly = t
+        * (0.254_829_592
+            + t * (-0.284_496_736
+                + t * (1.421_413_741 + t * (-1.453_152_027 + t * 1.061_405_429))));
+    let erf = 1.0 - poly * (-x * x).exp();
+    if z >= 0.0 {
+        0.5 * (1.0 + erf)
+    } else {
+        0.5 * (1.0 - erf)
+    }
+}
+
+/// Two-sided Mann-Whitney U test, using the normal approximation with tie correction.
+/// Returns the p-value.
+pub fn mann_whitney(a: &[f64], b: &[f64]) -> f64 {
+    let n1 = a.len() as f64;
+    let n2 = b.len() as f64;
+    if a.is_empty() || b.is_empty() {
+        return f64::NAN;
+    }
+
+    // Rank the pooled samples, averaging the ranks of ties
+    let mut pooled: Vec<(f64, bool)> = a
+        .iter()
+        .map(|v| (*v, true))
+        .chain(b.iter().map(|v| (*v, false)))
+        .collect();
+    pooled.sort_by(|x, y| x.0.total_cmp(&y.0));
+    let n = pooled.len();
+    let mut rank_a = 0.0;
+    let mut ties = 0.0;
+    let mut i = 0;
+    while i < n {
+        let mut j = i;
+        while j + 1 < n && pooled[j + 1].0 == pooled[i].0 {
+            j += 1;
+        }
+        let rank = (i + j) as f64 / 2.0 + 1.0;
+        let t = (j - i + 1) as f64;
+        ties += t * t * t - t;
+        rank_a += rank * pooled[i..=j].iter().filter(|p| p.1).count() as f64;
+        i = j + 1;
+    }
+
+    let u = rank_a - n1 * (n1 + 1.0) / 2.0;
+    let mu = n1 * n2 / 2.0;
+    let nt = n1 + n2;
+    let sigma = (n1 * n2 / 12.0 * ((nt + 1.0) - ties / (nt * (nt - 1.0)))).sqrt();
+    if sigma == 0.0 {
+        return 1.0;
+    }
+    // Continuity correction
+    let z = ((u - mu).abs() - 0.5).max(0.0) / sigma;
+    (2.0 * (1.0 - normal_cdf(z))).min(1.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentiles() {
+        let values = sorted(&[4.0, 1.0, 3.0, 2.0]);
+        assert_eq!(values, vec![1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(percentile(&values, 0.0), 1.0);
+        assert_eq!(percentile(&values, 50.0), 2.5);
+        assert_eq!(percentile(&values, 100.0), 4.0);
+        assert!(percentile(&[], 50.0).is_nan());
+        assert_eq!(median(&[5.0, 1.0, 3.0]), 3.0);
+        assert_eq!(mean(&[1.0, 2.0, 6.0]), 3.0);
+    }
+
+    #[test]
+    fn nan_does_not_panic() {
+        let values = sorted(&[2.0, f64::NAN, 1.0]);
+        assert_eq!(&values[..2], &[1.0, 2.0]);
+        assert!(mann_whitney(&[1.0, f64::NAN], &[2.0, 3.0]).is_finite());
+    }
+
+    #[test]
+    fn bootstrap() {
+        let base: Vec<f64> = (0..30).map(|i| 100.0 + (i % 7) as f64).collect();
+        let (lo, hi) = bootstrap_ci(&base, &base, 0.05, 1_000, 42);
+        assert!(lo <= 0.0 && hi >= 0.0, "({}, {})", lo, hi);
+
+        // A 10% increase
+        let cand: Vec<f64> = base.iter().map(|v| v * 1.1).collect();
+        let (lo, hi) = bootstrap_ci(&base, &cand, 0.05, 1_000, 42);
+        assert!(lo > 5.0 && hi < 15.0 && lo <= hi, "({}, {})", lo, hi);
+
+        // The interval is reproducible for a given seed
+        assert_eq!(bootstrap_ci(&base, &cand, 0.05, 1_000, 42), (lo, hi));
+    }
+
+    #[test]
+    fn normal() {
+        assert!((normal_cdf(0.0) - 0.5).abs() < 1e-6);
+        assert!((normal_cdf(1.96) - 0.975).abs() < 1e-3);
+        assert!((normal_cdf(-1.96) - 0.025).abs() < 1e-3);
+    }
+
+    #[test]
+    fn mann_whitney_u() {
+        // U = 0, z = (4.5 - 0.5) / sqrt(5.25)
+        let p = mann_whitney(&[1.0, 2.0, 3.0], &[4.0, 5.0, 6.0]);
+        assert!((p - 0.0808).abs() < 1e-3, "{}", p);
+        assert_eq!(p, mann_whitney(&[4.0, 5.0, 6.0], &[1.0, 2.0, 3.0]));
+
+        let a: Vec<f64> = (0..20).map(|i| i as f64).collect();
+        let b: Vec<f64> = (0..20).map(|i| 100.0 + i as f64).collect();
+        assert!(mann_whitney(&a, &b) < 0.001);
+        assert!(mann_whitney(&a, &a) > 0.9);
+
+        // All ties, and no sample
+        assert_eq!(mann_whitney(&[1.0, 1.0], &[1.0, 1.0]), 1.0);
+        assert!(mann_whitney(&[], &[1.0]).is_nan());
+    }
+}
@@ -0,0 +1,92 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::fmt::Write;
+
+/// A simple table rendered either aligned for the terminal or as HTML.
+pub struct Table {
+    pub title: String,
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(title: &str, headers: &[&str]) -> Table {
+        Table {
+            title: title.to_string(),
+            headers: headers.iter().map(|h| h.to_string()).collect(),
+            rows: vec![],
+        }
+    }
+
+    pub fn push(&mut self, row: Vec<String>) {
+        self.rows.push(row);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut widths: Vec<usize> = self.headers.iter().map(|h| h.len()).collect();
+        for r in self.rows.iter() {
+            for (w, c) in widths.iter_mut().zip(r.iter()) {
+                *w = (*w).max(c.len());
+            }
+        }
+        let line = |cells: &[String]| -> String {
+            cells
+                .iter()
+                .zip(widths.iter())
+                .map(|(c, w)| format!("{:>w$}", c, w = w))
+                .collect::<Vec<String>>()
+                .join("  ")
+        };
+
+        let mut s = String::new();
+        writeln!(s, "{}", self.title).unwrap();
+        writeln!(s, "{}", line(&self.headers)).unwrap();
+        let total: usize = widths.iter().sum::<usize>() + 2 * widths.len().saturating_sub(1);
+        writeln!(s, "{}", "-".repeat(total)).unwrap();
+        for r in self.rows.iter() {
+            writeln!(s, "{}", line(r)).unwrap();
+        }
+        s
+    }
+
+    pub fn to_html(&self) -> String {
+        let mut s = String::new();
+        writeln!(s, "<h2>{}</h2>", escape(&self.title)).unwrap();
+        s.push_str("<table>\n<tr>");
+        for h in self.headers.iter() {
+            write!(s, "<th>{}</th>", escape(h)).unwrap();
+        }
+        s.push_str("</tr>\n");
+        for r in self.rows.iter() {
+            s.push_str("<tr>");
+            for c in r.iter() {
+                write!(s, "<td>{}</td>", escape(c)).unwrap();
+            }
+            s.push_str("</tr>\n");
+        }
+        s.push_str("</table>\n");
+        s
+    }
+}
+
+pub fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,24 @@
+#!/usr/bin/env bash
+# Sweeps the lease and keepalive configuration over a fleet of idle sessions.
+# Usage: ./keepalive.sh [sessions] [scenario]
+
+SESSIONS=${1:-1000}
+SCENARIO=${2:-keepalive}
+
+# Each session uses four sockets in the same process: client, router and both proxy sides
+ulimit -n $((SESSIONS * 4 + 1024)) || exit 1
+# The proxy also runs four threads per session, see zenoh_perf_proxy::tcp::run
+THREADS=$((SESSIONS * 4 + 1024))
+ulimit -u $THREADS || exit 1
+if [ "$(cat /proc/sys/kernel/threads-max)" -lt $THREADS ]; then
+    echo "kernel.threads-max is below the $THREADS threads needed, raise it with sysctl" >&2
+    exit 1
+fi
+
+for LEASE in 2000 5000 10000 30000; do
+    for DIVISOR in 2 4 8; do
+        KEEP_ALIVE=$((LEASE / DIVISOR))
+        ../target/release/t_keepalive -c $SESSIONS --lease $LEASE --keep-alive $KEEP_ALIVE \
+            --timeout $((LEASE / 1000 * 3)) -n "lease${LEASE}_ka${KEEP_ALIVE}" -s $SCENARIO
+    done
+done
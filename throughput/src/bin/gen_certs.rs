@@ -0,0 +1,48 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::path::PathBuf;
+use structopt::StructOpt;
+use zenoh_perf_throughput::certs;
+
+const USAGE: &str = "\
+Run both sides with `--conf <printed path>` and a tls/localhost:<port> or
+quic/localhost:<port> locator, the binaries being built with the `tls` or
+`quic` feature. To see the cost of encryption per payload size, run the same
+scenario over tcp/ into one file and over tls/ into another, then:
+
+    zperf-compare -b tcp.log -c tls.log";
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "gen_certs", after_help = USAGE)]
+struct Opt {
+    #[structopt(short = "d", long = "dir", parse(from_os_str))]
+    dir: Option<PathBuf>,
+}
+
+fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let dir = match opt.dir {
+        Some(dir) => {
+            std::fs::create_dir_all(&dir).unwrap();
+            dir
+        }
+        None => certs::temp_dir(),
+    };
+    println!("{}", certs::generate(&dir).display());
+}
@@ -17,11 +17,13 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use structopt::StructOpt;
-use zenoh::net::protocol::core::{Channel, CongestionControl, Priority, Reliability, ResKey};
+use zenoh::net::protocol::core::{Channel, CongestionControl, Priority, Reliability};
 use zenoh::net::protocol::io::ZBuf;
 use zenoh::net::runtime::Runtime;
 use zenoh::net::transport::DummyPrimitives;
 use zenoh::net::transport::Primitives;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::reskey::{self, Form};
 use zenoh_util::properties::config::{
     ConfigProperties, ZN_ADD_TIMESTAMP_KEY, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
@@ -38,6 +40,12 @@ struct Opt {
     payload: usize,
     #[structopt(short = "t", long = "print")]
     print: bool,
+    #[structopt(long = "reskey", default_value = "rid")]
+    reskey: Form,
+    #[structopt(long = "key-length", default_value = "0")]
+    key_length: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -50,6 +58,10 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     let mut config = match opt.config.as_ref() {
         Some(f) => {
             let config = async_std::fs::read_to_string(f).await.unwrap();
@@ -69,9 +81,19 @@ async fn main() {
     let runtime = Runtime::new(0u8, config, None).await.unwrap();
     let primitives = runtime.router.new_primitives(my_primitives);
 
-    primitives.decl_resource(1, &"/test/thr".to_string().into());
-    let rid = ResKey::RId(1);
+    let name = reskey::name(opt.key_length);
+    if let Some(declared) = opt.reskey.declared(&name) {
+        primitives.decl_resource(1, &declared.into());
+    }
+    let rid = opt.reskey.reskey(1, &name);
     primitives.decl_publisher(&rid, None);
+    println!(
+        "router,reskey,{},{},{},{}",
+        opt.reskey,
+        name.len(),
+        opt.payload,
+        reskey::wire_bytes(rid.clone(), opt.payload)
+    );
 
     // @TODO: Fix writer starvation in the RwLock and remove this sleep
     // Wait for the declare to arrive
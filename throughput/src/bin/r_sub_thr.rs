@@ -18,15 +18,18 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::protocol::core::{
-    Channel, CongestionControl, PeerId, QueryConsolidation, QueryTarget, Reliability, ResKey,
-    SubInfo, SubMode, ZInt,
+    Channel, CongestionControl, PeerId, Priority, QueryConsolidation, QueryTarget, Reliability,
+    ResKey, SubInfo, SubMode, ZInt,
 };
 use zenoh::net::protocol::io::ZBuf;
 use zenoh::net::protocol::proto::{DataInfo, RoutingContext};
 use zenoh::net::runtime::Runtime;
-use zenoh::net::transport::Primitives;
+use zenoh::net::transport::{DummyPrimitives, Primitives};
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::reskey;
 use zenoh_util::properties::config::{
-    ConfigProperties, ZN_LISTENER_KEY, ZN_MODE_KEY, ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
+    ConfigProperties, ZN_ADD_TIMESTAMP_KEY, ZN_LISTENER_KEY, ZN_MODE_KEY,
+    ZN_MULTICAST_SCOUTING_KEY, ZN_PEER_KEY,
 };
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
@@ -138,6 +141,49 @@ impl Primitives for ThroughputPrimitives {
     }
 }
 
+async fn publish(mut config: ConfigProperties, locator: String, payload: usize, key_length: usize) {
+    // Keep the subscriber configuration, e.g. the TLS certificates
+    config.remove(&ZN_LISTENER_KEY);
+    config.insert(ZN_MODE_KEY, "peer".to_string());
+    config.insert(ZN_ADD_TIMESTAMP_KEY, "false".to_string());
+    config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
+    config.insert(ZN_PEER_KEY, locator);
+
+    let runtime = Runtime::new(0u8, config, None).await.unwrap();
+    let primitives = runtime
+        .router
+        .new_primitives(Arc::new(DummyPrimitives::new()));
+
+    primitives.decl_resource(1, &reskey::name(key_length).into());
+    let rid = ResKey::RId(1);
+    primitives.decl_publisher(&rid, None);
+
+    // @TODO: Fix writer starvation in the RwLock and remove this sleep
+    // Wait for the declare to arrive
+    task::sleep(Duration::from_millis(1_000)).await;
+
+    let channel = Channel {
+        priority: Priority::Data,
+        reliability: Reliability::Reliable,
+    };
+    let payload = ZBuf::from(vec![0u8; payload]);
+
+    // Publish from a dedicated thread, the congestion control may block
+    std::thread::spawn(move || {
+        let _runtime = runtime;
+        loop {
+            primitives.send_data(
+                &rid,
+                payload.clone(),
+                channel,
+                CongestionControl::Block,
+                None,
+                None,
+            );
+        }
+    });
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "r_sub_thr")]
 struct Opt {
@@ -151,6 +197,12 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(long = "key-length", default_value = "0")]
+    key_length: usize,
+    #[structopt(long = "loopback")]
+    loopback: bool,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -176,10 +228,11 @@ async fn main() {
     config.insert(ZN_MULTICAST_SCOUTING_KEY, "false".to_string());
     match opt.mode.as_str() {
         "peer" | "router" => {
-            config.insert(ZN_LISTENER_KEY, opt.locator);
+            ready::remove_stale(&opt.locator);
+            config.insert(ZN_LISTENER_KEY, opt.locator.clone());
         }
         "client" => {
-            config.insert(ZN_PEER_KEY, opt.locator);
+            config.insert(ZN_PEER_KEY, opt.locator.clone());
         }
         _ => {
             panic!("Unsupported mode: {}", opt.mode);
@@ -189,10 +242,11 @@ async fn main() {
     let count = Arc::new(AtomicUsize::new(0));
     let my_primitives = Arc::new(ThroughputPrimitives::new(count.clone()));
 
-    let runtime = Runtime::new(0u8, config, None).await.unwrap();
+    let runtime = Runtime::new(0u8, config.clone(), None).await.unwrap();
+    let locator = (opt.mode != "client").then(|| ready::listening(&runtime, &opt.locator));
     let primitives = runtime.router.new_primitives(my_primitives);
 
-    primitives.decl_resource(1, &"/test/thr".to_string().into());
+    primitives.decl_resource(1, &reskey::name(opt.key_length).into());
 
     let rid = ResKey::RId(1);
     let sub_info = SubInfo {
@@ -202,6 +256,18 @@ async fn main() {
     };
     primitives.decl_subscriber(&rid, &sub_info, None);
 
+    // Run the publisher in this process, connecting to the listener
+    if opt.loopback {
+        let locator = locator
+            .clone()
+            .expect("Loopback requires the peer or router mode");
+        publish(config, locator, opt.payload, opt.key_length).await;
+    }
+
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+
     loop {
         let now = Instant::now();
         task::sleep(Duration::from_secs(1)).await;
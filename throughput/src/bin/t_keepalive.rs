@@ -0,0 +1,262 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::any::Any;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh::net::link::{EndPoint, Link};
+use zenoh::net::protocol::proto::ZenohMessage;
+use zenoh::net::transport::*;
+use zenoh_perf_proxy::control::Control;
+use zenoh_perf_proxy::link::LinkConfig;
+use zenoh_perf_proxy::profile::Profile;
+use zenoh_perf_throughput::ready;
+use zenoh_util::core::ZResult;
+use zenoh_util::properties::{IntKeyProperties, Properties};
+
+type Closed = Arc<Mutex<Vec<Instant>>>;
+
+// Transport Handler recording when each session is closed
+struct MySH {
+    closed: Closed,
+}
+
+impl MySH {
+    fn new(closed: Closed) -> Self {
+        Self { closed }
+    }
+}
+
+impl TransportEventHandler for MySH {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(MyMH::new(self.closed.clone())))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+// Message Handler for the idle sessions
+struct MyMH {
+    closed: Closed,
+}
+
+impl MyMH {
+    fn new(closed: Closed) -> Self {
+        Self { closed }
+    }
+}
+
+impl TransportPeerEventHandler for MyMH {
+    fn handle_message(&self, _message: ZenohMessage) -> ZResult<()> {
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {
+        self.closed.lock().unwrap().push(Instant::now());
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "t_keepalive")]
+struct Opt {
+    #[structopt(short = "l", long = "listen", default_value = "127.0.0.1:7447")]
+    listen: SocketAddr,
+    #[structopt(long = "proxy", default_value = "127.0.0.1:7448")]
+    proxy: SocketAddr,
+    #[structopt(short = "c", long = "sessions", default_value = "1000")]
+    sessions: usize,
+    #[structopt(long = "batch", default_value = "100")]
+    batch: usize,
+    #[structopt(long = "lease")]
+    lease: Option<u64>,
+    #[structopt(long = "keep-alive")]
+    keep_alive: Option<u64>,
+    #[structopt(long = "idle", default_value = "10")]
+    idle: f64,
+    #[structopt(long = "timeout", default_value = "60")]
+    timeout: f64,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+async fn manager(config: &Properties, mode: &str, closed: Closed) -> TransportManager {
+    let mut properties = config.clone();
+    properties.insert("mode".to_string(), mode.to_string());
+    let int_props = IntKeyProperties::from(properties);
+    let config = TransportManagerConfig::builder()
+        .from_config(&int_props)
+        .await
+        .unwrap()
+        .build(Arc::new(MySH::new(closed)));
+    TransportManager::new(config)
+}
+
+// Median and maximum detection latency in microseconds
+fn latencies(from: Instant, closed: &Closed) -> (String, String) {
+    let mut us: Vec<u128> = closed
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|t| t.saturating_duration_since(from).as_micros())
+        .collect();
+    if us.is_empty() {
+        return ("-".to_string(), "-".to_string());
+    }
+    us.sort_unstable();
+    (us[us.len() / 2].to_string(), us[us.len() - 1].to_string())
+}
+
+fn or_default(value: Option<u64>) -> String {
+    value.map_or_else(|| "default".to_string(), |v| v.to_string())
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    if let Some(lease) = opt.lease {
+        config.insert("link_lease".to_string(), lease.to_string());
+    }
+    if let Some(keep_alive) = opt.keep_alive {
+        config.insert("link_keep_alive".to_string(), keep_alive.to_string());
+    }
+
+    // The router all the idle sessions connect to
+    let router_closed: Closed = Arc::new(Mutex::new(vec![]));
+    let router = manager(&config, "router", router_closed.clone()).await;
+    let requested = format!("tcp/{}", opt.listen);
+    let bound = router
+        .add_listener(requested.parse().unwrap())
+        .await
+        .unwrap();
+    // Port 0 lets zenoh pick a free port, read it back for the proxy
+    let target: SocketAddr = ready::bound(&requested, &[bound])["tcp/".len()..]
+        .parse()
+        .unwrap();
+
+    // The proxy accounts for the bytes exchanged and emulates the failure
+    let control = Control::new();
+    let proxy_config = Arc::new(LinkConfig::new(
+        Profile::constant(Default::default()),
+        control.clone(),
+    ));
+    let listener = std::net::TcpListener::bind(opt.proxy).unwrap();
+    let proxy = listener.local_addr().unwrap();
+    std::thread::spawn(move || {
+        zenoh_perf_proxy::tcp::forward(listener, target, proxy_config).unwrap()
+    });
+
+    // Open the idle sessions, one transport manager per client
+    let client_closed: Closed = Arc::new(Mutex::new(vec![]));
+    let endpoint: EndPoint = format!("tcp/{}", proxy).parse().unwrap();
+    let mut clients = Vec::with_capacity(opt.sessions);
+    let mut transports = Vec::with_capacity(opt.sessions);
+    while clients.len() < opt.sessions {
+        let n = opt.batch.min(opt.sessions - clients.len());
+        let mut opening = Vec::with_capacity(n);
+        for _ in 0..n {
+            let client = manager(&config, "client", client_closed.clone()).await;
+            let e = endpoint.clone();
+            let c_client = client.clone();
+            opening.push(task::spawn(async move {
+                c_client.open_transport_unicast(e).await.unwrap()
+            }));
+            clients.push(client);
+        }
+        for o in opening.into_iter() {
+            transports.push(o.await);
+        }
+    }
+    log::info!("Opened {} sessions", transports.len());
+
+    // Measure the background traffic of the idle sessions
+    task::sleep(Duration::from_secs(1)).await;
+    let (start, before) = (Instant::now(), control.bytes());
+    task::sleep(Duration::from_secs_f64(opt.idle)).await;
+    let bytes = control.bytes() - before;
+    let bps = 8.0 * bytes as f64 / start.elapsed().as_secs_f64() / opt.sessions as f64;
+
+    // Cut the link, nothing gets through anymore, and wait for both sides to detect it
+    let t0 = Instant::now();
+    control.set_blackhole(true);
+    let timeout = Duration::from_secs_f64(opt.timeout);
+    while t0.elapsed() < timeout {
+        let r = router_closed.lock().unwrap().len();
+        let c = client_closed.lock().unwrap().len();
+        if r >= opt.sessions && c >= opt.sessions {
+            break;
+        }
+        task::sleep(Duration::from_millis(10)).await;
+    }
+    let (router_median, router_max) = latencies(t0, &router_closed);
+    let (client_median, client_max) = latencies(t0, &client_closed);
+    let detected = router_closed
+        .lock()
+        .unwrap()
+        .len()
+        .min(client_closed.lock().unwrap().len());
+
+    println!(
+        "session,{},keepalive,{},{},{},{},{:.3},{},{},{},{},{}",
+        opt.scenario,
+        opt.name,
+        opt.sessions,
+        or_default(opt.lease),
+        or_default(opt.keep_alive),
+        bps,
+        router_median,
+        router_max,
+        client_median,
+        client_max,
+        detected
+    );
+
+    // Tearing down thousands of sessions is not part of the measurement
+    std::process::exit(0);
+}
@@ -22,12 +22,14 @@ use zenoh::net::protocol::core::{
     whatami, Channel, CongestionControl, Priority, Reliability, ResKey,
 };
 use zenoh::net::protocol::io::ZBuf;
-use zenoh::net::protocol::proto::ZenohMessage;
+use zenoh::net::protocol::proto::{Declaration, Resource, ZenohMessage};
 use zenoh::net::transport::{
     DummyTransportPeerEventHandler, TransportEventHandler, TransportManager,
     TransportManagerConfig, TransportMulticast, TransportMulticastEventHandler, TransportPeer,
     TransportPeerEventHandler, TransportUnicast,
 };
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::reskey::{self, Form};
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
@@ -67,6 +69,12 @@ struct Opt {
     payload: usize,
     #[structopt(short = "t", long = "print")]
     print: bool,
+    #[structopt(long = "reskey", default_value = "rid")]
+    reskey: Form,
+    #[structopt(long = "key-length", default_value = "0")]
+    key_length: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -99,17 +107,42 @@ async fn main() {
     // Connect to publisher
     let mut transports: Vec<TransportUnicast> = vec![];
     for e in opt.connect.iter() {
-        let t = manager.open_transport_unicast(e.clone()).await.unwrap();
+        let t = ready::retry(
+            Duration::from_secs_f64(opt.wait_ready.unwrap_or(0.0)),
+            || manager.open_transport_unicast(e.clone()),
+        )
+        .await
+        .unwrap();
         transports.push(t);
     }
 
+    // Bind the resource id to the declared name
+    let name = reskey::name(opt.key_length);
+    if let Some(declared) = opt.reskey.declared(&name) {
+        let resource = Declaration::Resource(Resource {
+            rid: 1,
+            key: ResKey::RName(declared),
+        });
+        let message = ZenohMessage::make_declare(vec![resource], None, None);
+        for t in transports.iter() {
+            t.handle_message(message.clone()).unwrap();
+        }
+    }
+
     // Send reliable messages
     let channel = Channel {
         priority: Priority::Data,
         reliability: Reliability::Reliable,
     };
     let congestion_control = CongestionControl::Block;
-    let key = ResKey::RId(1);
+    let key = opt.reskey.reskey(1, &name);
+    println!(
+        "session,reskey,{},{},{},{}",
+        opt.reskey,
+        name.len(),
+        opt.payload,
+        reskey::wire_bytes(key.clone(), opt.payload)
+    );
     let info = None;
     let payload = ZBuf::from(vec![0u8; opt.payload]);
     let reply_context = None;
@@ -28,6 +28,7 @@ use zenoh::net::transport::{
     TransportEventHandler, TransportManager, TransportManagerConfig, TransportMulticast,
     TransportMulticastEventHandler, TransportPeer, TransportPeerEventHandler, TransportUnicast,
 };
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
@@ -125,6 +126,8 @@ struct Opt {
     scenario: String,
     #[structopt(short = "t", long = "print")]
     print: bool,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -160,19 +163,23 @@ async fn main() {
     )));
     let manager = TransportManager::new(config);
 
-    // Connect to publisher
+    // Listen for the other nodes, with the ports picked by zenoh
+    let mut locators: Vec<String> = vec![];
     for e in opt.listen.iter() {
-        let _ = manager.add_listener(e.clone()).await.unwrap();
+        let requested = e.to_string();
+        ready::remove_stale(&requested);
+        let bound = manager.add_listener(e.clone()).await.unwrap();
+        locators.push(ready::bound(&requested, &[bound]));
+    }
+    if !locators.is_empty() {
+        ready::announce(&locators.join(","), opt.ready_file.as_deref());
     }
 
     let mut transports: Vec<TransportUnicast> = vec![];
     for e in opt.connect.iter() {
-        let t = loop {
-            match manager.open_transport_unicast(e.clone()).await {
-                Ok(t) => break t,
-                Err(_) => task::sleep(Duration::from_secs(1)).await,
-            }
-        };
+        let t = ready::retry(Duration::MAX, || manager.open_transport_unicast(e.clone()))
+            .await
+            .unwrap();
         transports.push(t);
     }
 
@@ -21,6 +21,7 @@ use zenoh::net::link::{EndPoint, Link};
 use zenoh::net::protocol::core::whatami;
 use zenoh::net::protocol::proto::ZenohMessage;
 use zenoh::net::transport::*;
+use zenoh_perf_throughput::ready;
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
@@ -95,6 +96,8 @@ struct Opt {
     listen: Vec<EndPoint>,
     #[structopt(short = "c", long = "connect")]
     connect: Vec<EndPoint>,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -120,14 +123,21 @@ async fn main() {
     let config = bc.build(Arc::new(MySH::new()));
     let manager = TransportManager::new(config);
 
-    // Create listeners
+    // Create listeners, with the ports picked by zenoh
+    let mut locators: Vec<String> = vec![];
     for l in opt.listen.iter() {
-        manager.add_listener(l.clone()).await.unwrap();
+        let requested = l.to_string();
+        ready::remove_stale(&requested);
+        let bound = manager.add_listener(l.clone()).await.unwrap();
+        locators.push(ready::bound(&requested, &[bound]));
     }
     // Connect to other routers
     for l in opt.connect.iter() {
         let _t = manager.open_transport_unicast(l.clone()).await.unwrap();
     }
+    if !locators.is_empty() {
+        ready::announce(&locators.join(","), opt.ready_file.as_deref());
+    }
     // Stop forever
     future::pending::<()>().await;
 }
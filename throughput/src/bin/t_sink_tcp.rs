@@ -16,12 +16,14 @@ use async_std::prelude::*;
 use async_std::sync::Arc;
 use async_std::task;
 use std::convert::TryInto;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use structopt::StructOpt;
-use zenoh::net::protocol::core::{whatami, PeerId};
+use zenoh::net::protocol::core::{whatami, PeerId, ZInt};
 use zenoh::net::protocol::io::{WBuf, ZBuf, ZSlice};
 use zenoh::net::protocol::proto::{InitSyn, OpenSyn, TransportBody, TransportMessage};
+use zenoh_perf_throughput::ready;
 
 macro_rules! zsend {
     ($msg:expr, $stream:expr) => {{
@@ -58,7 +60,10 @@ macro_rules! zrecv {
     }};
 }
 
-async fn handle_client(mut stream: TcpStream) -> Result<(), Box<dyn std::error::Error>> {
+async fn handle_client(
+    mut stream: TcpStream,
+    lease: Lease,
+) -> Result<(), Box<dyn std::error::Error>> {
     let my_whatami = whatami::ROUTER;
     let my_pid = PeerId::rand();
 
@@ -91,10 +96,18 @@ async fn handle_client(mut stream: TcpStream) -> Result<(), Box<dyn std::error::
     let message = zrecv!(stream, buffer);
     match &message.body {
         TransportBody::OpenSyn(OpenSyn {
-            lease, initial_sn, ..
+            lease: peer_lease,
+            initial_sn,
+            ..
         }) => {
+            // The peer expects to hear from us within the lease we announce, not within its own
+            log::debug!(
+                "Peer lease {} ms, announcing {} ms",
+                peer_lease,
+                lease.lease
+            );
             let attachment = None;
-            let mut message = TransportMessage::make_open_ack(*lease, *initial_sn, attachment);
+            let mut message = TransportMessage::make_open_ack(lease.lease, *initial_sn, attachment);
             // Send the OpenAck
             let _ = zsend!(message, stream).unwrap();
         }
@@ -118,7 +131,7 @@ async fn handle_client(mut stream: TcpStream) -> Result<(), Box<dyn std::error::
     let mut c_stream = stream.clone();
     task::spawn(async move {
         loop {
-            task::sleep(Duration::from_secs(1)).await;
+            task::sleep(lease.keep_alive).await;
             let mut message = TransportMessage::make_keep_alive(None, None);
             let _ = zsend!(message, c_stream);
         }
@@ -131,14 +144,20 @@ async fn handle_client(mut stream: TcpStream) -> Result<(), Box<dyn std::error::
     }
 }
 
-async fn run(addr: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
-    let locator = TcpListener::bind(addr).await?;
-    let mut incoming = locator.incoming();
+async fn run(
+    addr: SocketAddr,
+    ready_file: Option<PathBuf>,
+    lease: Lease,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let listener = TcpListener::bind(addr).await?;
+    let locator = format!("tcp/{}", listener.local_addr()?);
+    ready::announce(&locator, ready_file.as_deref());
+    let mut incoming = listener.incoming();
 
     while let Some(stream) = incoming.next().await {
         let stream = stream?;
         task::spawn(async move {
-            let _ = handle_client(stream).await;
+            let _ = handle_client(stream, lease).await;
         });
     }
 
@@ -150,11 +169,32 @@ async fn run(addr: SocketAddr) -> Result<(), Box<dyn std::error::Error>> {
 struct Opt {
     #[structopt(short = "l", long = "listen")]
     listen: SocketAddr,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "lease", default_value = "10000")]
+    lease: ZInt,
+    #[structopt(long = "keep-alive", default_value = "2500")]
+    keep_alive: u64,
+}
+
+// The lease announced to the peer in ms, and the period of our keep alives
+#[derive(Debug, Clone, Copy)]
+struct Lease {
+    lease: ZInt,
+    keep_alive: Duration,
 }
 
 #[async_std::main]
 async fn main() {
     env_logger::init();
     let opt = Opt::from_args();
-    let _ = run(opt.listen).await;
+    assert!(
+        opt.keep_alive > 0 && (opt.keep_alive as ZInt) < opt.lease,
+        "The keep alive period must be shorter than the lease"
+    );
+    let lease = Lease {
+        lease: opt.lease,
+        keep_alive: Duration::from_millis(opt.keep_alive),
+    };
+    let _ = run(opt.listen, opt.ready_file, lease).await;
 }
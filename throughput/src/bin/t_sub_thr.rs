@@ -20,9 +20,14 @@ use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
 use zenoh::net::link::{EndPoint, Link};
-use zenoh::net::protocol::core::whatami;
-use zenoh::net::protocol::proto::ZenohMessage;
+use zenoh::net::protocol::core::{
+    whatami, Channel, CongestionControl, Priority, Reliability, ResKey,
+};
+use zenoh::net::protocol::io::ZBuf;
+use zenoh::net::protocol::proto::{Declaration, Resource, ZenohMessage};
 use zenoh::net::transport::*;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::reskey;
 use zenoh_util::core::ZResult;
 use zenoh_util::properties::{IntKeyProperties, Properties};
 
@@ -116,6 +121,85 @@ impl TransportPeerEventHandler for MyMH {
     }
 }
 
+// Transport Handler for the in-process publisher
+struct MyPH;
+
+impl TransportEventHandler for MyPH {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(DummyTransportPeerEventHandler::default()))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+async fn publish(endpoints: Vec<EndPoint>, payload: usize, properties: Option<&IntKeyProperties>) {
+    // Keep the subscriber configuration, e.g. the TLS certificates
+    let bc = match properties {
+        Some(int_props) => TransportManagerConfig::builder()
+            .from_config(int_props)
+            .await
+            .unwrap(),
+        None => TransportManagerConfig::builder().whatami(whatami::PEER),
+    };
+    let config = bc.build(Arc::new(MyPH));
+    let manager = TransportManager::new(config);
+
+    let mut transports: Vec<TransportUnicast> = vec![];
+    for e in endpoints.into_iter() {
+        let t = manager.open_transport_unicast(e).await.unwrap();
+        transports.push(t);
+    }
+
+    // Bind the resource id to its name, as t_pub_thr does
+    let resource = Declaration::Resource(Resource {
+        rid: 1,
+        key: ResKey::RName(reskey::name(0)),
+    });
+    let message = ZenohMessage::make_declare(vec![resource], None, None);
+    for t in transports.iter() {
+        t.handle_message(message.clone()).unwrap();
+    }
+
+    let channel = Channel {
+        priority: Priority::Data,
+        reliability: Reliability::Reliable,
+    };
+    let payload = ZBuf::from(vec![0u8; payload]);
+
+    // Publish from a dedicated thread, the congestion control may block
+    std::thread::spawn(move || {
+        let _manager = manager;
+        loop {
+            for t in transports.iter() {
+                let message = ZenohMessage::make_data(
+                    ResKey::RId(1),
+                    payload.clone(),
+                    channel,
+                    CongestionControl::Block,
+                    None,
+                    None,
+                    None,
+                    None,
+                );
+                // Stop rather than let the counts silently drop to zero
+                if let Err(e) = t.handle_message(message) {
+                    eprintln!("The in-process publisher failed: {}", e);
+                    std::process::exit(1);
+                }
+            }
+        }
+    });
+}
+
 #[derive(Debug, StructOpt)]
 #[structopt(name = "s_sub_thr")]
 struct Opt {
@@ -129,6 +213,10 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(long = "loopback")]
+    loopback: bool,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -144,16 +232,19 @@ async fn main() {
     let whatami = whatami::parse(opt.mode.as_str()).unwrap();
 
     let count = Arc::new(AtomicUsize::new(0));
-    let bc = match opt.config.as_ref() {
+    let properties = match opt.config.as_ref() {
         Some(f) => {
             let config = async_std::fs::read_to_string(f).await.unwrap();
             let properties = Properties::from(config);
-            let int_props = IntKeyProperties::from(properties);
-            TransportManagerConfig::builder()
-                .from_config(&int_props)
-                .await
-                .unwrap()
+            Some(IntKeyProperties::from(properties))
         }
+        None => None,
+    };
+    let bc = match properties.as_ref() {
+        Some(int_props) => TransportManagerConfig::builder()
+            .from_config(int_props)
+            .await
+            .unwrap(),
         None => TransportManagerConfig::builder().whatami(whatami::ROUTER),
     };
     let config = bc.build(Arc::new(MySH::new(
@@ -164,15 +255,30 @@ async fn main() {
     )));
     let manager = TransportManager::new(config);
 
+    // The locators bound by the listeners, with the ports picked by zenoh
+    let mut locators: Vec<String> = vec![];
     if whatami == whatami::PEER {
         for e in opt.endpoint.iter() {
-            manager.add_listener(e.clone()).await.unwrap();
+            let requested = e.to_string();
+            ready::remove_stale(&requested);
+            let bound = manager.add_listener(e.clone()).await.unwrap();
+            locators.push(ready::bound(&requested, &[bound]));
         }
     } else {
         for e in opt.endpoint.iter() {
             let _t = manager.open_transport_unicast(e.clone()).await.unwrap();
         }
     }
+
+    // Run the publisher in this process, connecting to the listeners
+    if opt.loopback {
+        assert!(whatami == whatami::PEER, "Loopback requires the peer mode");
+        let endpoints = locators.iter().map(|l| l.parse().unwrap()).collect();
+        publish(endpoints, opt.payload, properties.as_ref()).await;
+    }
+    if !locators.is_empty() {
+        ready::announce(&locators.join(","), opt.ready_file.as_deref());
+    }
     // Stop forever
     future::pending::<()>().await;
 }
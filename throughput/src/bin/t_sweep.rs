@@ -0,0 +1,383 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh::net::link::{EndPoint, Link};
+use zenoh::net::protocol::core::{Channel, CongestionControl, Priority, Reliability, ResKey};
+use zenoh::net::protocol::io::ZBuf;
+use zenoh::net::protocol::proto::{Data, ZenohBody, ZenohMessage};
+use zenoh::net::transport::{
+    DummyTransportPeerEventHandler, TransportEventHandler, TransportManager,
+    TransportManagerConfig, TransportMulticast, TransportMulticastEventHandler, TransportPeer,
+    TransportPeerEventHandler, TransportUnicast,
+};
+use zenoh_perf_throughput::ready;
+use zenoh_util::core::ZResult;
+use zenoh_util::properties::{IntKeyProperties, Properties};
+
+// One message every PROBE carries its sending time to measure the latency
+const PROBE: usize = 1_000;
+
+#[derive(Default)]
+struct Stats {
+    messages: AtomicUsize,
+    bytes: AtomicUsize,
+    latency: AtomicU64,
+    probes: AtomicUsize,
+}
+
+// Transport Handler for the subscriber
+struct MySH {
+    epoch: Instant,
+    stats: Arc<Stats>,
+}
+
+impl TransportEventHandler for MySH {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(MyMH {
+            epoch: self.epoch,
+            stats: self.stats.clone(),
+        }))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+// Message Handler for the subscriber
+struct MyMH {
+    epoch: Instant,
+    stats: Arc<Stats>,
+}
+
+impl TransportPeerEventHandler for MyMH {
+    fn handle_message(&self, message: ZenohMessage) -> ZResult<()> {
+        if let ZenohBody::Data(Data { mut payload, .. }) = message.body {
+            self.stats.messages.fetch_add(1, Ordering::Relaxed);
+            self.stats.bytes.fetch_add(payload.len(), Ordering::Relaxed);
+            let mut sent = [0u8; 8];
+            if payload.read_bytes(&mut sent) && sent != [0u8; 8] {
+                let sent = u64::from_le_bytes(sent);
+                let now = self.epoch.elapsed().as_nanos() as u64;
+                self.stats
+                    .latency
+                    .fetch_add(now.saturating_sub(sent), Ordering::Relaxed);
+                self.stats.probes.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        Ok(())
+    }
+
+    fn new_link(&self, _link: Link) {}
+    fn del_link(&self, _link: Link) {}
+    fn closing(&self) {}
+    fn closed(&self) {}
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+// Transport Handler for the publisher
+struct MyPH {}
+
+impl TransportEventHandler for MyPH {
+    fn new_unicast(
+        &self,
+        _peer: TransportPeer,
+        _transport: TransportUnicast,
+    ) -> ZResult<Arc<dyn TransportPeerEventHandler>> {
+        Ok(Arc::new(DummyTransportPeerEventHandler::default()))
+    }
+
+    fn new_multicast(
+        &self,
+        _transport: TransportMulticast,
+    ) -> ZResult<Arc<dyn TransportMulticastEventHandler>> {
+        panic!();
+    }
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "t_sweep")]
+struct Opt {
+    #[structopt(short = "a", long = "address", default_value = "127.0.0.1")]
+    address: String,
+    #[structopt(long = "port", default_value = "7447")]
+    port: u16,
+    #[structopt(
+        short = "p",
+        long = "payload",
+        default_value = "8,64,512,1024,8192,65536",
+        use_delimiter = true
+    )]
+    payloads: Vec<usize>,
+    #[structopt(
+        short = "b",
+        long = "batch-size",
+        default_value = "1024,8192,16384,65535",
+        use_delimiter = true
+    )]
+    batch_sizes: Vec<u16>,
+    #[structopt(
+        long = "sn-resolution",
+        default_value = "256,268435456",
+        use_delimiter = true
+    )]
+    sn_resolutions: Vec<u64>,
+    #[structopt(long = "qos", default_value = "true,false", use_delimiter = true)]
+    qos: Vec<bool>,
+    #[structopt(short = "d", long = "duration", default_value = "3")]
+    duration: f64,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+}
+
+struct Point {
+    config: (u16, u64, bool),
+    payload: usize,
+    msgs: f64,
+    gbps: f64,
+    // Average one-way latency of the probes, in microseconds
+    latency: String,
+}
+
+async fn manager(
+    handler: Arc<dyn TransportEventHandler>,
+    batch_size: u16,
+    sn_resolution: u64,
+    qos: bool,
+) -> TransportManager {
+    let mut properties = Properties::default();
+    properties.insert("mode".to_string(), "peer".to_string());
+    properties.insert("batch_size".to_string(), batch_size.to_string());
+    properties.insert("seq_num_resolution".to_string(), sn_resolution.to_string());
+    properties.insert("qos".to_string(), qos.to_string());
+    let int_props = IntKeyProperties::from(properties);
+    let config = TransportManagerConfig::builder()
+        .from_config(&int_props)
+        .await
+        .unwrap()
+        .build(handler);
+    TransportManager::new(config)
+}
+
+async fn run(opt: &Opt, endpoint: EndPoint, payload: usize, config: (u16, u64, bool)) -> Point {
+    let (batch_size, sn_resolution, qos) = config;
+    let epoch = Instant::now();
+    let stats = Arc::new(Stats::default());
+
+    let handler = Arc::new(MySH {
+        epoch,
+        stats: stats.clone(),
+    });
+    let sub = manager(handler, batch_size, sn_resolution, qos).await;
+    let requested = endpoint.to_string();
+    let bound = sub.add_listener(endpoint).await.unwrap();
+    let endpoint: EndPoint = ready::bound(&requested, &[bound]).parse().unwrap();
+
+    let publ = manager(Arc::new(MyPH {}), batch_size, sn_resolution, qos).await;
+    let transport = publ.open_transport_unicast(endpoint).await.unwrap();
+
+    // Publish from a dedicated thread, the congestion control may block
+    let running = Arc::new(AtomicBool::new(true));
+    let (c_running, c_transport) = (running.clone(), transport.clone());
+    let publisher = task::spawn_blocking(move || {
+        let channel = Channel {
+            priority: Priority::Data,
+            reliability: Reliability::Reliable,
+        };
+        let data = ZBuf::from(vec![0u8; payload]);
+        let mut count: usize = 0;
+        while c_running.load(Ordering::Relaxed) {
+            let payload = if payload >= 8 && count % PROBE == 0 {
+                let mut probe = vec![0u8; payload];
+                let now = epoch.elapsed().as_nanos() as u64;
+                probe[0..8].copy_from_slice(&now.max(1).to_le_bytes());
+                ZBuf::from(probe)
+            } else {
+                data.clone()
+            };
+            let message = ZenohMessage::make_data(
+                ResKey::RId(1),
+                payload,
+                channel,
+                CongestionControl::Block,
+                None,
+                None,
+                None,
+                None,
+            );
+            let _ = c_transport.handle_message(message);
+            count += 1;
+        }
+    });
+
+    // Skip the first second, then measure
+    task::sleep(Duration::from_secs(1)).await;
+    let (m0, b0, l0, p0) = (
+        stats.messages.load(Ordering::Relaxed),
+        stats.bytes.load(Ordering::Relaxed),
+        stats.latency.load(Ordering::Relaxed),
+        stats.probes.load(Ordering::Relaxed),
+    );
+    let now = Instant::now();
+    task::sleep(Duration::from_secs_f64(opt.duration)).await;
+    let elapsed = now.elapsed().as_secs_f64();
+    let messages = stats.messages.load(Ordering::Relaxed) - m0;
+    let bytes = stats.bytes.load(Ordering::Relaxed) - b0;
+    let latency = stats.latency.load(Ordering::Relaxed) - l0;
+    let probes = stats.probes.load(Ordering::Relaxed) - p0;
+
+    running.store(false, Ordering::Relaxed);
+    publisher.await;
+    let _ = transport.close().await;
+
+    let point = Point {
+        config,
+        payload,
+        msgs: messages as f64 / elapsed,
+        gbps: 8.0 * bytes as f64 / elapsed / 1_000_000_000.0,
+        latency: if probes > 0 {
+            (latency / probes as u64 / 1_000).to_string()
+        } else {
+            "-".to_string()
+        },
+    };
+    print(opt, &test(config), &point, "");
+    point
+}
+
+// The test of a configuration, so that each one is a series of its own in the reports
+fn test(config: (u16, u64, bool)) -> String {
+    let (batch_size, sn_resolution, qos) = config;
+    format!(
+        "throughput.b{}.sn{}.{}",
+        batch_size,
+        sn_resolution,
+        if qos { "qos" } else { "noqos" }
+    )
+}
+
+// The values are followed by the configuration: batch size, SN resolution and QoS
+fn print(opt: &Opt, test: &str, point: &Point, suffix: &str) {
+    let (batch_size, sn_resolution, qos) = point.config;
+    println!(
+        "session,{},{},{},{},{},{:.6},{},{},{},{}{}",
+        opt.scenario,
+        test,
+        opt.name,
+        point.payload,
+        point.msgs.floor() as usize,
+        point.gbps,
+        point.latency,
+        batch_size,
+        sn_resolution,
+        qos,
+        suffix
+    );
+}
+
+// The payload bands, by upper bound
+const BANDS: [(&str, usize); 4] = [
+    ("small", 64),
+    ("medium", 1_024),
+    ("large", 16_384),
+    ("huge", usize::MAX),
+];
+
+fn band(payload: usize) -> &'static str {
+    BANDS.iter().find(|(_, max)| payload <= *max).unwrap().0
+}
+
+#[async_std::main]
+async fn main() {
+    // Enable logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    // Each combination uses its own port, not to wait for the previous listener to be released,
+    // port 0 lets zenoh pick a free one each time
+    let mut port = opt.port;
+    let mut points = vec![];
+    for payload in opt.payloads.iter() {
+        for batch_size in opt.batch_sizes.iter() {
+            for sn_resolution in opt.sn_resolutions.iter() {
+                for qos in opt.qos.iter() {
+                    let endpoint: EndPoint =
+                        format!("tcp/{}:{}", opt.address, port).parse().unwrap();
+                    if port != 0 {
+                        port += 1;
+                    }
+                    let config = (*batch_size, *sn_resolution, *qos);
+                    points.push(run(&opt, endpoint, *payload, config).await);
+                }
+            }
+        }
+    }
+
+    // The best configuration for each payload band: the closest to the best throughput
+    // of each payload on average, the payloads of a band are not compared together
+    for (b, _) in BANDS.iter() {
+        let points: Vec<&Point> = points.iter().filter(|p| band(p.payload) == *b).collect();
+        let score = |config: (u16, u64, bool)| -> f64 {
+            let ratios: Vec<f64> = points
+                .iter()
+                .filter(|p| p.config == config)
+                .map(|p| {
+                    let best = points
+                        .iter()
+                        .filter(|q| q.payload == p.payload)
+                        .map(|q| q.msgs)
+                        .fold(0.0, f64::max);
+                    if best > 0.0 {
+                        p.msgs / best
+                    } else {
+                        0.0
+                    }
+                })
+                .collect();
+            ratios.iter().sum::<f64>() / ratios.len() as f64
+        };
+        let best = match points
+            .iter()
+            .map(|p| p.config)
+            .max_by(|x, y| score(*x).total_cmp(&score(*y)))
+        {
+            Some(best) => best,
+            None => continue,
+        };
+        // As the throughput rows, followed by the payload band
+        for p in points.iter().filter(|p| p.config == best) {
+            print(&opt, "sweep.best", p, &format!(",{}", b));
+        }
+    }
+    std::process::exit(0);
+}
@@ -18,9 +18,10 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use structopt::StructOpt;
-use zenoh::net::ZBuf;
 use zenoh::Properties;
 use zenoh::*;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::value::{self, Kind};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "z_put_thr")]
@@ -33,6 +34,10 @@ struct Opt {
     payload: usize,
     #[structopt(short = "t", long = "print")]
     print: bool,
+    #[structopt(long = "value", default_value = "raw")]
+    value: Kind,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -45,6 +50,10 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     let mut config = match opt.config.as_ref() {
         Some(f) => {
             let config = async_std::fs::read_to_string(f).await.unwrap();
@@ -58,16 +67,11 @@ async fn main() {
     config.insert("multicast_scouting".to_string(), "false".to_string());
     config.insert("peer".to_string(), opt.locator);
 
-    let data: ZBuf = (0usize..opt.payload)
-        .map(|i| (i % 10) as u8)
-        .collect::<Vec<u8>>()
-        .into();
-
     let zenoh = Zenoh::new(config.into()).await.unwrap();
     let workspace = zenoh.workspace(None).await.unwrap();
 
     let path: Path = Path::try_from("/test/thr").unwrap();
-    let value = Value::from(data);
+    let value = value::generate(opt.value, opt.payload, 0);
 
     if opt.print {
         let count = Arc::new(AtomicUsize::new(0));
@@ -15,11 +15,15 @@ use async_std::sync::Arc;
 use async_std::task;
 use std::convert::TryFrom;
 use std::path::PathBuf;
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
 use zenoh::Properties;
 use zenoh::*;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::value::Kind;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "z_sub_thr")]
@@ -34,6 +38,10 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(long = "cost")]
+    cost: bool,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -57,25 +65,51 @@ async fn main() {
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
     match opt.mode.as_str() {
-        "peer" => config.insert("listener".to_string(), opt.locator),
-        "client" => config.insert("peer".to_string(), opt.locator),
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let zenoh = Zenoh::new(config.into()).await.unwrap();
+    // Open the zenoh instance over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let zenoh = Zenoh::init(runtime).await;
     let workspace = zenoh.workspace(None).await.unwrap();
     let selector = Selector::try_from("/test/thr").unwrap();
 
     let messages = Arc::new(AtomicUsize::new(0));
     let c_messages = messages.clone();
 
+    // The time spent (in ns) to encode and decode again the received values
+    let kind: Arc<Mutex<Option<Kind>>> = Arc::new(Mutex::new(None));
+    let encoding = Arc::new(AtomicU64::new(0));
+    let decoding = Arc::new(AtomicU64::new(0));
+    let (c_kind, c_encoding, c_decoding) = (kind.clone(), encoding.clone(), decoding.clone());
+    let cost = opt.cost;
+
     let _sub = workspace
-        .subscribe_with_callback(&selector, move |_change| {
+        .subscribe_with_callback(&selector, move |change| {
+            if let (true, Some(value)) = (cost, change.value) {
+                c_kind.lock().unwrap().get_or_insert(Kind::of(&value));
+                let now = Instant::now();
+                let (e, payload) = value.encode();
+                c_encoding.fetch_add(now.elapsed().as_nanos() as u64, Ordering::Relaxed);
+                let now = Instant::now();
+                let _ = Value::decode(e, payload);
+                c_decoding.fetch_add(now.elapsed().as_nanos() as u64, Ordering::Relaxed);
+            }
             c_messages.fetch_add(1, Ordering::Relaxed);
         })
         .await
         .unwrap();
 
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+
     loop {
         let now = Instant::now();
         task::sleep(Duration::from_secs(1)).await;
@@ -92,5 +126,21 @@ async fn main() {
                 (c as f64 / interval).floor() as usize
             );
         }
+
+        let (e, d) = (
+            encoding.swap(0, Ordering::Relaxed),
+            decoding.swap(0, Ordering::Relaxed),
+        );
+        if let (true, Some(kind)) = (c > 0, *kind.lock().unwrap()) {
+            println!(
+                "zenoh,{},codec,{},{},{},{},{}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                kind,
+                e / c as u64,
+                d / c as u64
+            );
+        }
     }
 }
@@ -0,0 +1,105 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+use zenoh::net::ResKey::*;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_throughput::pattern::Generator;
+use zenoh_perf_throughput::ready;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_pub_frag")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload", default_value = "65536")]
+    payload: usize,
+    #[structopt(long = "seed", default_value = "0")]
+    seed: u64,
+    #[structopt(long = "batch-size", default_value = "65535")]
+    batch_size: u16,
+    #[structopt(long = "corrupt")]
+    corrupt: Option<u64>,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    config.insert("mode".to_string(), opt.mode.clone());
+    config.insert("add_timestamp".to_string(), "false".to_string());
+    config.insert("batch_size".to_string(), opt.batch_size.to_string());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), opt.locator.clone());
+
+    // Generate the bodies before connecting, it takes a while for large payloads
+    let generator = Generator::new(opt.seed, opt.payload);
+
+    let session = open(config.into()).await.unwrap();
+
+    let reskey = RId(session
+        .declare_resource(&RName("/test/frag".to_string()))
+        .await
+        .unwrap());
+    let _publ = session.declare_publisher(&reskey).await.unwrap();
+
+    let mut seq: u64 = 0;
+    loop {
+        let sent = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_nanos() as u64;
+        let mut data = generator.payload(seq, sent);
+        // Flip a byte on purpose, to check that the subscriber notices
+        if opt.corrupt == Some(seq) {
+            let offset = data.len() - 1;
+            data[offset] ^= 0xff;
+        }
+        session
+            .write_ext(
+                &reskey,
+                data.into(),
+                encoding::DEFAULT,
+                data_kind::DEFAULT,
+                CongestionControl::Block, // Make sure to not drop messages because of congestion control
+            )
+            .await
+            .unwrap();
+        seq += 1;
+    }
+}
@@ -0,0 +1,173 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::path::PathBuf;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+use zenoh::net::ResKey::*;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_common::cpu;
+use zenoh_perf_throughput::ready;
+
+// Each payload starts with its sequence number and its sending time
+const HEADER: usize = 16;
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_pub_shm")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload", default_value = "1048576")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(long = "shm")]
+    shm: bool,
+    #[structopt(long = "buffers", default_value = "4")]
+    buffers: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+fn header(data: &mut [u8], count: u64) {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos() as u64;
+    data[0..8].copy_from_slice(&count.to_le_bytes());
+    data[8..16].copy_from_slice(&now.to_le_bytes());
+}
+
+async fn alloc(shm: &mut SharedMemoryManager, size: usize) -> SharedMemoryBuf {
+    loop {
+        if let Some(buf) = shm.alloc(size) {
+            return buf;
+        }
+        // All the buffers are still held by the subscriber, reclaim the released ones
+        if shm.garbage_collect() == 0 {
+            task::yield_now().await;
+        }
+    }
+}
+
+async fn reuse(heap: &mut Arc<Vec<u8>>) -> &mut Vec<u8> {
+    // The transport releases the buffer once it has serialized the message
+    while Arc::get_mut(heap).is_none() {
+        task::yield_now().await;
+    }
+    Arc::get_mut(heap).unwrap()
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+    assert!(
+        opt.payload >= HEADER,
+        "The payload must be at least {} bytes",
+        HEADER
+    );
+    assert!(opt.buffers > 0, "At least one buffer is needed");
+
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    config.insert("mode".to_string(), opt.mode.clone());
+    config.insert("add_timestamp".to_string(), "false".to_string());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), opt.locator.clone());
+
+    let session = open(config.into()).await.unwrap();
+    // Publish from a shared memory segment of a few payloads, or from as many
+    // heap buffers allocated upfront
+    let mut shm = if opt.shm {
+        let id = session.id().await;
+        Some(SharedMemoryManager::new(id, opt.payload * opt.buffers).unwrap())
+    } else {
+        None
+    };
+
+    let reskey = RId(session
+        .declare_resource(&RName("/test/shm".to_string()))
+        .await
+        .unwrap());
+    let _publ = session.declare_publisher(&reskey).await.unwrap();
+
+    // Sample the load of this process while publishing
+    let (scenario, name, payload) = (opt.scenario.clone(), opt.name.clone(), opt.payload);
+    task::spawn(async move {
+        let mut publisher = cpu::Load::new(std::process::id()).unwrap();
+        loop {
+            task::sleep(Duration::from_secs(1)).await;
+            let load = publisher.sample().unwrap();
+            println!(
+                "zenoh-net,{},cpu.pub,{},{},{:.3}",
+                scenario, name, payload, load
+            );
+        }
+    });
+
+    let buffers = if opt.shm { 0 } else { opt.buffers };
+    let mut heap: Vec<Arc<Vec<u8>>> = (0..buffers)
+        .map(|_| Arc::new(vec![0u8; opt.payload]))
+        .collect();
+
+    let mut count: u64 = 0;
+    loop {
+        // The next free buffer for every message, as a producer would fill it
+        let data: ZBuf = match shm.as_mut() {
+            Some(shm) => {
+                let mut buf = alloc(shm, opt.payload).await;
+                header(unsafe { buf.as_mut_slice() }, count);
+                buf.into()
+            }
+            None => {
+                let slot = &mut heap[count as usize % opt.buffers];
+                header(reuse(slot).await, count);
+                slot.clone().into()
+            }
+        };
+        session
+            .write_ext(
+                &reskey,
+                data,
+                encoding::DEFAULT,
+                data_kind::DEFAULT,
+                CongestionControl::Block, // Make sure to not drop messages because of congestion control
+            )
+            .await
+            .unwrap();
+        count += 1;
+    }
+}
@@ -20,6 +20,8 @@ use structopt::StructOpt;
 use zenoh::net::ResKey::*;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::reskey::{self, Form};
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_pub_thr")]
@@ -32,6 +34,12 @@ struct Opt {
     payload: usize,
     #[structopt(short = "t", long = "print")]
     print: bool,
+    #[structopt(long = "reskey", default_value = "rid")]
+    reskey: Form,
+    #[structopt(long = "key-length", default_value = "0")]
+    key_length: usize,
+    #[structopt(long = "wait-ready")]
+    wait_ready: Option<f64>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -44,6 +52,10 @@ async fn main() {
     // Parse the args
     let opt = Opt::from_args();
 
+    if let Some(timeout) = opt.wait_ready {
+        ready::wait(&opt.locator, Duration::from_secs_f64(timeout)).await;
+    }
+
     let mut config = match opt.config.as_ref() {
         Some(f) => {
             let config = async_std::fs::read_to_string(f).await.unwrap();
@@ -59,10 +71,21 @@ async fn main() {
 
     let session = open(config.into()).await.unwrap();
 
-    let reskey = RId(session
-        .declare_resource(&RName("/test/thr".to_string()))
-        .await
-        .unwrap());
+    let name = reskey::name(opt.key_length);
+    let reskey = match opt.reskey.declared(&name) {
+        Some(declared) => {
+            let rid = session.declare_resource(&RName(declared)).await.unwrap();
+            opt.reskey.reskey(rid, &name)
+        }
+        None => opt.reskey.reskey(0, &name),
+    };
+    println!(
+        "zenoh-net,reskey,{},{},{},{}",
+        opt.reskey,
+        name.len(),
+        opt.payload,
+        reskey::wire_bytes(reskey.clone(), opt.payload)
+    );
     let _publ = session.declare_publisher(&reskey).await.unwrap();
 
     let data: ZBuf = (0usize..opt.payload)
@@ -0,0 +1,307 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
+use zenoh::net::ResKey::*;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_proxy::control::{Control, Event};
+use zenoh_perf_proxy::link::LinkConfig;
+use zenoh_perf_proxy::profile::Profile;
+use zenoh_perf_throughput::ready;
+
+// Width of the window used to compute the throughput after the recovery
+const WINDOW: Duration = Duration::from_millis(100);
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_recovery")]
+struct Opt {
+    #[structopt(short = "l", long = "locator", default_value = "tcp/127.0.0.1:7447")]
+    locator: String,
+    #[structopt(long = "proxy", default_value = "tcp/127.0.0.1:7448")]
+    proxy: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(short = "f", long = "failure", default_value = "blackhole")]
+    failure: String,
+    #[structopt(long = "outage")]
+    outage: Option<f64>,
+    #[structopt(long = "warmup", default_value = "3")]
+    warmup: f64,
+    #[structopt(long = "timeout", default_value = "30")]
+    timeout: f64,
+    #[structopt(long = "rate", default_value = "0")]
+    rate: f64,
+    #[structopt(long = "lease")]
+    lease: Option<u64>,
+    #[structopt(long = "keep-alive")]
+    keep_alive: Option<u64>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+fn socket_addr(locator: &str) -> std::net::SocketAddr {
+    locator
+        .strip_prefix("tcp/")
+        .unwrap_or_else(|| panic!("Only TCP locators are supported: {}", locator))
+        .parse()
+        .unwrap()
+}
+
+fn elapsed_us(from: Instant, to: Option<Instant>) -> String {
+    match to {
+        Some(t) => t.saturating_duration_since(from).as_micros().to_string(),
+        None => "-".to_string(),
+    }
+}
+
+// Polls the proxy for the first connection closed by an endpoint since `since`
+async fn closed(control: &Control, since: Instant, limit: Duration) -> Option<Instant> {
+    loop {
+        let closed = control
+            .events_since(since)
+            .iter()
+            .find(|(_, e)| matches!(e, Event::Closed(_)))
+            .map(|(t, _)| *t);
+        if closed.is_some() || since.elapsed() >= limit {
+            return closed;
+        }
+        task::sleep(Duration::from_millis(1)).await;
+    }
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+    assert!(opt.payload >= 8, "The payload must be at least 8 bytes");
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("add_timestamp".to_string(), "false".to_string());
+    if let Some(lease) = opt.lease {
+        config.insert("link_lease".to_string(), lease.to_string());
+    }
+    if let Some(keep_alive) = opt.keep_alive {
+        config.insert("link_keep_alive".to_string(), keep_alive.to_string());
+    }
+
+    // The subscriber listens on the locator, it is a router when the publisher is a client
+    let mut sub_config = config.clone();
+    let sub_mode = match opt.mode.as_str() {
+        "client" => "router",
+        "peer" | "router" => opt.mode.as_str(),
+        _ => panic!("Unsupported mode: {}", opt.mode),
+    };
+    sub_config.insert("mode".to_string(), sub_mode.to_string());
+    sub_config.insert("listener".to_string(), opt.locator.clone());
+    // Port 0 lets zenoh pick a free port, read it back for the proxy
+    let runtime = Runtime::new(0u8, sub_config.into(), None).await.unwrap();
+    let target = socket_addr(&ready::listening(&runtime, &opt.locator));
+    let sub_session = Session::init(runtime, true, vec![], vec![]).await;
+
+    // The proxy between the publisher and the subscriber
+    let control = Control::new();
+    let proxy_config = Arc::new(LinkConfig::new(
+        Profile::constant(Default::default()),
+        control.clone(),
+    ));
+    let listener = std::net::TcpListener::bind(socket_addr(&opt.proxy)).unwrap();
+    let proxy = format!("tcp/{}", listener.local_addr().unwrap());
+    std::thread::spawn(move || {
+        zenoh_perf_proxy::tcp::forward(listener, target, proxy_config).unwrap()
+    });
+
+    let arrivals = Arc::new(Mutex::new(Vec::<(Instant, u64)>::new()));
+    let c_arrivals = arrivals.clone();
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let reskey = RName("/test/recovery".to_string());
+    let _sub = sub_session
+        .declare_callback_subscriber(&reskey, &sub_info, move |mut sample| {
+            let mut seq = [0u8; 8];
+            sample.payload.read_bytes(&mut seq);
+            c_arrivals
+                .lock()
+                .unwrap()
+                .push((Instant::now(), u64::from_le_bytes(seq)));
+        })
+        .await
+        .unwrap();
+
+    // The publisher reaches the subscriber through the proxy
+    let mut pub_config = config.clone();
+    pub_config.insert("mode".to_string(), opt.mode.clone());
+    pub_config.insert("peer".to_string(), proxy);
+    let pub_session = open(pub_config.into()).await.unwrap();
+
+    let sent = Arc::new(AtomicU64::new(0));
+    let running = Arc::new(AtomicBool::new(true));
+    let (c_sent, c_running) = (sent.clone(), running.clone());
+    let payload = opt.payload;
+    let rate = opt.rate;
+    let publisher = task::spawn(async move {
+        let reskey = RId(pub_session.declare_resource(&reskey).await.unwrap());
+        let _publ = pub_session.declare_publisher(&reskey).await.unwrap();
+        let padding = vec![0u8; payload - 8];
+        let mut seq: u64 = 0;
+        while c_running.load(Ordering::Relaxed) {
+            let mut data: WBuf = WBuf::new(payload, true);
+            data.write_bytes(&seq.to_le_bytes());
+            data.write_bytes(&padding);
+            // Do not block during the outage, the dropped messages are accounted as lost
+            let _ = pub_session
+                .write_ext(
+                    &reskey,
+                    data.into(),
+                    encoding::DEFAULT,
+                    data_kind::DEFAULT,
+                    CongestionControl::Drop,
+                )
+                .await;
+            seq += 1;
+            c_sent.store(seq, Ordering::Relaxed);
+            if rate > 0.0 {
+                task::sleep(Duration::from_secs_f64(1.0 / rate)).await;
+            }
+        }
+        pub_session.close().await.unwrap();
+    });
+
+    // Steady state throughput before the failure
+    task::sleep(Duration::from_secs_f64(opt.warmup)).await;
+    let t0 = Instant::now();
+    let baseline = {
+        let guard = arrivals.lock().unwrap();
+        let since = t0 - Duration::from_secs(1);
+        guard.iter().filter(|(t, _)| *t >= since).count() as f64
+    };
+    assert!(
+        baseline > 0.0,
+        "Nothing received before the failure, is the publisher connected?"
+    );
+
+    // Inject the failure, then wait for the endpoints to notice it
+    let timeout = Duration::from_secs_f64(opt.timeout);
+    let outage = opt.outage.map(Duration::from_secs_f64);
+    let detected = match opt.failure.as_str() {
+        "blackhole" => {
+            control.set_blackhole(true);
+            closed(&control, t0, outage.unwrap_or(timeout)).await
+        }
+        "kill" => {
+            control.kill();
+            closed(&control, t0, timeout).await
+        }
+        _ => panic!("Unsupported failure: {}", opt.failure),
+    };
+    if let Some(o) = outage {
+        let end = t0 + o;
+        let now = Instant::now();
+        if end > now {
+            task::sleep(end - now).await;
+        }
+    }
+    control.set_blackhole(false);
+    let healed = Instant::now();
+
+    // Wait for the data to flow again and for the throughput to recover
+    let target = 0.9 * baseline * WINDOW.as_secs_f64();
+    let mut reconnected: Option<Instant> = None;
+    let mut steady: Option<Instant> = None;
+    while healed.elapsed() < timeout && steady.is_none() {
+        task::sleep(WINDOW).await;
+        let guard = arrivals.lock().unwrap();
+        if reconnected.is_none() {
+            reconnected = guard.iter().find(|(t, _)| *t >= healed).map(|(t, _)| *t);
+        }
+        if let Some(r) = reconnected {
+            // Slide a window over the arrivals since the reconnection
+            let times: Vec<Instant> = guard
+                .iter()
+                .filter(|(t, _)| *t >= r)
+                .map(|(t, _)| *t)
+                .collect();
+            let mut start = 0;
+            for (end, t) in times.iter().enumerate() {
+                while *t - times[start] > WINDOW {
+                    start += 1;
+                }
+                if (end - start + 1) as f64 >= target {
+                    steady = Some(*t);
+                    break;
+                }
+            }
+        }
+    }
+    let reconnect_attempt = control
+        .events_since(t0)
+        .iter()
+        .find(|(_, e)| matches!(e, Event::Opened(_)))
+        .map(|(t, _)| *t);
+
+    // Stop publishing and let the in-flight messages drain
+    running.store(false, Ordering::Relaxed);
+    publisher.await;
+    task::sleep(Duration::from_secs(1)).await;
+
+    let sent = sent.load(Ordering::Relaxed);
+    let (received, unique) = {
+        let guard = arrivals.lock().unwrap();
+        let unique: HashSet<u64> = guard.iter().map(|(_, s)| *s).collect();
+        (guard.len() as u64, unique.len() as u64)
+    };
+
+    println!(
+        "zenoh-net,{},recovery,{},{},{},{},{},{},{},{},{},{}",
+        opt.scenario,
+        opt.name,
+        opt.payload,
+        opt.mode,
+        opt.failure,
+        elapsed_us(t0, detected),
+        elapsed_us(healed, reconnect_attempt),
+        elapsed_us(healed, reconnected),
+        elapsed_us(healed, steady),
+        sent.saturating_sub(unique),
+        received - unique
+    );
+
+    sub_session.close().await.unwrap();
+}
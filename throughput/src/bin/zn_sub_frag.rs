@@ -0,0 +1,229 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
+use zenoh::net::ResKey::*;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_throughput::pattern::Verifier;
+use zenoh_perf_throughput::ready;
+
+// Bytes of a batch not available to the fragment: frame header and sequence number
+const FRAGMENT_OVERHEAD: usize = 16;
+
+#[derive(Default)]
+struct Stats {
+    messages: AtomicUsize,
+    bytes: AtomicUsize,
+    latency: AtomicU64,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_sub_frag")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload", default_value = "65536")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(long = "batch-size", default_value = "65535")]
+    batch_size: u16,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+// The fragments of a payload, estimated from the batch size: a message
+// larger than a batch is split into batch-sized fragments. The transport does
+// not expose the actual count, hence the `fragments.estimate` row.
+fn fragments(payload: usize, batch_size: u16) -> usize {
+    let room = batch_size as usize - FRAGMENT_OVERHEAD;
+    if payload <= room {
+        1
+    } else {
+        (payload + room - 1) / room
+    }
+}
+
+// Report the corruption and stop, the results of this run are worthless
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+    assert!(
+        opt.batch_size as usize > FRAGMENT_OVERHEAD,
+        "The batch size must be larger than {} bytes",
+        FRAGMENT_OVERHEAD
+    );
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    config.insert("mode".to_string(), opt.mode.clone());
+    config.insert("batch_size".to_string(), opt.batch_size.to_string());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    match opt.mode.as_str() {
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
+        _ => panic!("Unsupported mode: {}", opt.mode),
+    };
+
+    // Open the session over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let session = Session::init(runtime, true, vec![], vec![]).await;
+
+    let reskey = RId(session
+        .declare_resource(&RName("/test/frag".to_string()))
+        .await
+        .unwrap());
+
+    let stats = Arc::new(Stats::default());
+    let c_stats = stats.clone();
+    // The verifier and the next expected sequence number
+    let state = Mutex::new((Verifier::new(), None));
+    let payload = opt.payload;
+
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let _sub = session
+        .declare_callback_subscriber(&reskey, &sub_info, move |sample| {
+            let data = sample.payload.contiguous();
+            let mut guard = state.lock().unwrap();
+            let (verifier, next) = &mut *guard;
+            let header = verifier
+                .verify(&data)
+                .unwrap_or_else(|e| fail(format!("Corrupted payload: {}", e)));
+            if data.len() != payload {
+                fail(format!(
+                    "Message {} has {} bytes instead of {}",
+                    header.seq,
+                    data.len(),
+                    payload
+                ));
+            }
+            // The first message received sets the sequence
+            if let Some(expected) = *next {
+                if header.seq != expected {
+                    fail(format!(
+                        "Message {} received while expecting {}",
+                        header.seq, expected
+                    ));
+                }
+            }
+            *next = Some(header.seq + 1);
+
+            // Reassembly latency, from the write of the whole payload on the publisher
+            // to its verified reassembly here: it covers the fragmentation and the
+            // transmission of every fragment too. Both ends must share a clock, i.e.
+            // run on the same host.
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64;
+            c_stats.messages.fetch_add(1, Ordering::Relaxed);
+            c_stats.bytes.fetch_add(data.len(), Ordering::Relaxed);
+            c_stats
+                .latency
+                .fetch_add(now.saturating_sub(header.sent), Ordering::Relaxed);
+        })
+        .await
+        .unwrap();
+
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+
+    println!(
+        "zenoh-net,{},fragments.estimate,{},{},{}",
+        opt.scenario,
+        opt.name,
+        opt.payload,
+        fragments(opt.payload, opt.batch_size)
+    );
+    loop {
+        let now = Instant::now();
+        task::sleep(Duration::from_secs(1)).await;
+        let elapsed = now.elapsed().as_secs_f64();
+
+        let c = stats.messages.swap(0, Ordering::Relaxed);
+        let bytes = stats.bytes.swap(0, Ordering::Relaxed);
+        let latency = stats.latency.swap(0, Ordering::Relaxed);
+        if c > 0 {
+            // msg/s and verified Gbit/s
+            println!(
+                "zenoh-net,{},throughput,{},{},{},{:.6}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                (c as f64 / elapsed).floor() as usize,
+                8.0 * bytes as f64 / elapsed / 1_000_000_000.0
+            );
+            println!(
+                "zenoh-net,{},latency.reassembly,{},{},{}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                latency / c as u64 / 1_000
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fragments_estimate() {
+        let room = 65_535 - FRAGMENT_OVERHEAD;
+        assert_eq!(fragments(1, 65_535), 1);
+        assert_eq!(fragments(room, 65_535), 1);
+        assert_eq!(fragments(room + 1, 65_535), 2);
+        assert_eq!(fragments(2 * room, 65_535), 2);
+        assert_eq!(fragments(4_194_304, 65_535), 65);
+        assert_eq!(fragments(1_000, 116), 10);
+    }
+}
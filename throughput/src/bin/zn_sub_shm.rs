@@ -0,0 +1,181 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+use async_std::sync::Arc;
+use async_std::task;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
+use zenoh::net::ResKey::*;
+use zenoh::net::*;
+use zenoh::Properties;
+use zenoh_perf_common::cpu;
+use zenoh_perf_throughput::ready;
+
+#[derive(Default)]
+struct Stats {
+    messages: AtomicUsize,
+    // The messages received as shared memory buffers
+    shm: AtomicUsize,
+    latency: AtomicU64,
+}
+
+#[derive(Debug, StructOpt)]
+#[structopt(name = "zn_sub_shm")]
+struct Opt {
+    #[structopt(short = "l", long = "locator")]
+    locator: String,
+    #[structopt(short = "m", long = "mode")]
+    mode: String,
+    #[structopt(short = "p", long = "payload", default_value = "1048576")]
+    payload: usize,
+    #[structopt(short = "n", long = "name")]
+    name: String,
+    #[structopt(short = "s", long = "scenario")]
+    scenario: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
+    #[structopt(long = "conf", parse(from_os_str))]
+    config: Option<PathBuf>,
+}
+
+// Report the unexpected payload and stop, the results of this run are worthless
+fn fail(message: String) -> ! {
+    eprintln!("{}", message);
+    std::process::exit(1);
+}
+
+#[async_std::main]
+async fn main() {
+    // initiate logging
+    env_logger::init();
+
+    // Parse the args
+    let opt = Opt::from_args();
+
+    let mut config = match opt.config.as_ref() {
+        Some(f) => {
+            let config = async_std::fs::read_to_string(f).await.unwrap();
+            Properties::from(config)
+        }
+        None => Properties::default(),
+    };
+    config.insert("mode".to_string(), opt.mode.clone());
+
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    match opt.mode.as_str() {
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
+        _ => panic!("Unsupported mode: {}", opt.mode),
+    };
+
+    // Open the session over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let session = Session::init(runtime, true, vec![], vec![]).await;
+
+    let reskey = RId(session
+        .declare_resource(&RName("/test/shm".to_string()))
+        .await
+        .unwrap());
+
+    let stats = Arc::new(Stats::default());
+    let c_stats = stats.clone();
+    let payload = opt.payload;
+
+    let sub_info = SubInfo {
+        reliability: Reliability::Reliable,
+        mode: SubMode::Push,
+        period: None,
+    };
+    let _sub = session
+        .declare_callback_subscriber(&reskey, &sub_info, move |mut sample| {
+            if sample.payload.len() != payload {
+                fail(format!(
+                    "Payload of {} bytes instead of {}",
+                    sample.payload.len(),
+                    payload
+                ));
+            }
+            if sample.payload.has_shmbuf() {
+                c_stats.shm.fetch_add(1, Ordering::Relaxed);
+            }
+            // The payload starts with its sequence number and its sending time
+            let mut header = [0u8; 16];
+            if !sample.payload.read_bytes(&mut header) {
+                fail(format!("Payload of {} bytes without header", payload));
+            }
+            let mut sent = [0u8; 8];
+            sent.copy_from_slice(&header[8..16]);
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_nanos() as u64;
+            c_stats.messages.fetch_add(1, Ordering::Relaxed);
+            c_stats.latency.fetch_add(
+                now.saturating_sub(u64::from_le_bytes(sent)),
+                Ordering::Relaxed,
+            );
+        })
+        .await
+        .unwrap();
+
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+
+    let mut subscriber = cpu::Load::new(std::process::id()).unwrap();
+    loop {
+        let now = Instant::now();
+        task::sleep(Duration::from_secs(1)).await;
+        let elapsed = now.elapsed().as_secs_f64();
+
+        let c = stats.messages.swap(0, Ordering::Relaxed);
+        let shm = stats.shm.swap(0, Ordering::Relaxed);
+        let latency = stats.latency.swap(0, Ordering::Relaxed);
+        let load = subscriber.sample().unwrap();
+        if c > 0 {
+            println!(
+                "zenoh-net,{},throughput,{},{},{}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                (c as f64 / elapsed).floor() as usize
+            );
+            println!(
+                "zenoh-net,{},latency.oneway,{},{},{}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                latency / c as u64 / 1_000
+            );
+            println!(
+                "zenoh-net,{},cpu.sub,{},{},{:.3}",
+                opt.scenario, opt.name, opt.payload, load
+            );
+            // The share of the messages received as shared memory buffers
+            println!(
+                "zenoh-net,{},shm.ratio,{},{},{:.3}",
+                opt.scenario,
+                opt.name,
+                opt.payload,
+                shm as f64 / c as f64
+            );
+        }
+    }
+}
@@ -17,9 +17,44 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
 use zenoh::net::ResKey::*;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_throughput::ready;
+use zenoh_perf_throughput::reskey;
+
+async fn publish(mut config: Properties, locator: String, payload: usize, key_length: usize) {
+    // Keep the rest of the subscriber configuration, e.g. the TLS certificates
+    config.remove("listener");
+    config.insert("mode".to_string(), "peer".to_string());
+    config.insert("add_timestamp".to_string(), "false".to_string());
+    config.insert("multicast_scouting".to_string(), "false".to_string());
+    config.insert("peer".to_string(), locator);
+
+    let session = open(config.into()).await.unwrap();
+    let reskey = RId(session
+        .declare_resource(&RName(reskey::name(key_length)))
+        .await
+        .unwrap());
+    let data: ZBuf = vec![0u8; payload].into();
+
+    task::spawn(async move {
+        let _publ = session.declare_publisher(&reskey).await.unwrap();
+        loop {
+            session
+                .write_ext(
+                    &reskey,
+                    data.clone(),
+                    encoding::DEFAULT,
+                    data_kind::DEFAULT,
+                    CongestionControl::Block, // Make sure to not drop messages because of congestion control
+                )
+                .await
+                .unwrap();
+        }
+    });
+}
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_sub_thr")]
@@ -34,6 +69,12 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(long = "key-length", default_value = "0")]
+    key_length: usize,
+    #[structopt(long = "loopback")]
+    loopback: bool,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -57,15 +98,23 @@ async fn main() {
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
     match opt.mode.as_str() {
-        "peer" => config.insert("listener".to_string(), opt.locator),
-        "client" => config.insert("peer".to_string(), opt.locator),
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let session = open(config.into()).await.unwrap();
+    // Open the session over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.clone().into(), None)
+        .await
+        .unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let session = Session::init(runtime, true, vec![], vec![]).await;
 
     let reskey = RId(session
-        .declare_resource(&RName("/test/thr".to_string()))
+        .declare_resource(&RName(reskey::name(opt.key_length)))
         .await
         .unwrap());
 
@@ -84,6 +133,16 @@ async fn main() {
         .await
         .unwrap();
 
+    // Run the publisher in this process, connecting to the listener
+    if opt.loopback {
+        let locator = locator.clone().expect("Loopback requires the peer mode");
+        publish(config, locator, opt.payload, opt.key_length).await;
+    }
+
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
+
     loop {
         let now = Instant::now();
         task::sleep(Duration::from_secs(1)).await;
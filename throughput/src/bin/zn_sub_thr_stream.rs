@@ -17,9 +17,11 @@ use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, Instant};
 use structopt::StructOpt;
+use zenoh::net::runtime::Runtime;
 use zenoh::net::ResKey::*;
 use zenoh::net::*;
 use zenoh::Properties;
+use zenoh_perf_throughput::ready;
 
 #[derive(Debug, StructOpt)]
 #[structopt(name = "zn_sub_thr_stream")]
@@ -34,6 +36,8 @@ struct Opt {
     name: String,
     #[structopt(short = "s", long = "scenario")]
     scenario: String,
+    #[structopt(long = "ready-file", parse(from_os_str))]
+    ready_file: Option<PathBuf>,
     #[structopt(long = "conf", parse(from_os_str))]
     config: Option<PathBuf>,
 }
@@ -56,14 +60,19 @@ async fn main() {
     config.insert("mode".to_string(), opt.mode.clone());
 
     config.insert("multicast_scouting".to_string(), "false".to_string());
-    let loc = opt.locator.clone();
     match opt.mode.as_str() {
-        "peer" => config.insert("listener".to_string(), loc),
-        "client" => config.insert("peer".to_string(), loc),
+        "peer" => {
+            ready::remove_stale(&opt.locator);
+            config.insert("listener".to_string(), opt.locator.clone())
+        }
+        "client" => config.insert("peer".to_string(), opt.locator.clone()),
         _ => panic!("Unsupported mode: {}", opt.mode),
     };
 
-    let session = open(config.into()).await.unwrap();
+    // Open the session over its own runtime, to read back the locator it bound
+    let runtime = Runtime::new(0u8, config.into(), None).await.unwrap();
+    let locator = (opt.mode == "peer").then(|| ready::listening(&runtime, &opt.locator));
+    let session = Session::init(runtime, true, vec![], vec![]).await;
 
     let reskey = RId(session
         .declare_resource(&RName("/test/thr".to_string()))
@@ -101,6 +110,9 @@ async fn main() {
         .declare_subscriber(&reskey, &sub_info)
         .await
         .unwrap();
+    if let Some(locator) = locator.as_ref() {
+        ready::announce(locator, opt.ready_file.as_deref());
+    }
 
     while sub.receiver().recv().is_ok() {
         messages.fetch_add(1, Ordering::Relaxed);
@@ -0,0 +1,78 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Throwaway certificates for the TLS and QUIC benchmarks.
+//!
+//! `generate` creates a CA and a server certificate signed by it for
+//! `localhost` and `127.0.0.1`, then writes a zenoh configuration pointing at
+//! them. The same configuration serves both sides: give it with `--conf` to
+//! the listener and to its counterpart, together with a `tls/localhost:<port>`
+//! or `quic/localhost:<port>` locator.
+use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa, SanType};
+use std::net::{IpAddr, Ipv4Addr};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+pub const CA: &str = "ca.pem";
+pub const CERTIFICATE: &str = "server.pem";
+pub const KEY: &str = "server.key";
+pub const CONFIG: &str = "tls.conf";
+
+/// A fresh directory under the system temporary directory.
+pub fn temp_dir() -> PathBuf {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let dir =
+        std::env::temp_dir().join(format!("zenoh-perf-certs-{}-{}", std::process::id(), nanos));
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Writes the CA, the server certificate and key and the configuration into
+/// `dir`, returning the path of the configuration.
+pub fn generate(dir: &Path) -> PathBuf {
+    let mut params = CertificateParams::default();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "zenoh-perf CA");
+    let ca = Certificate::from_params(params).unwrap();
+
+    let mut params = CertificateParams::new(vec!["localhost".to_string()]);
+    params
+        .subject_alt_names
+        .push(SanType::IpAddress(IpAddr::V4(Ipv4Addr::LOCALHOST)));
+    params
+        .distinguished_name
+        .push(DnType::CommonName, "localhost");
+    let server = Certificate::from_params(params).unwrap();
+
+    std::fs::write(dir.join(CA), ca.serialize_pem().unwrap()).unwrap();
+    std::fs::write(
+        dir.join(CERTIFICATE),
+        server.serialize_pem_with_signer(&ca).unwrap(),
+    )
+    .unwrap();
+    std::fs::write(dir.join(KEY), server.serialize_private_key_pem()).unwrap();
+
+    let config = format!(
+        "tls_root_ca_certificate={};tls_server_certificate={};tls_server_private_key={}",
+        dir.join(CA).display(),
+        dir.join(CERTIFICATE).display(),
+        dir.join(KEY).display()
+    );
+    std::fs::write(dir.join(CONFIG), config).unwrap();
+    dir.join(CONFIG)
+}
@@ -0,0 +1,272 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Payloads whose every byte can be checked by the receiver.
+//!
+//! A payload is a header followed by a body:
+//!  - the sequence number, the seed, the sending time in nanoseconds since the
+//!    epoch and the checksum of the base body, as little endian u64;
+//!  - a pseudo-random body: a base derived from the seed, with a key derived
+//!    from the seed and the sequence number XOR-ed into each of its words, so
+//!    every message differs and a mix of their fragments is detected.
+//!
+//! The base body is generated once: a receiver rebuilds the same base from the
+//! seed in the header and compares the payloads with it byte per byte.
+use std::collections::HashMap;
+use std::fmt;
+
+pub const HEADER: usize = 32;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Header {
+    pub seq: u64,
+    pub seed: u64,
+    pub sent: u64,
+    pub checksum: u64,
+}
+
+impl Header {
+    fn read(data: &[u8]) -> Header {
+        let word = |i: usize| {
+            let mut bytes = [0u8; 8];
+            bytes.copy_from_slice(&data[8 * i..8 * i + 8]);
+            u64::from_le_bytes(bytes)
+        };
+        Header {
+            seq: word(0),
+            seed: word(1),
+            sent: word(2),
+            checksum: word(3),
+        }
+    }
+
+    fn write(&self, data: &mut [u8]) {
+        for (i, w) in [self.seq, self.seed, self.sent, self.checksum]
+            .iter()
+            .enumerate()
+        {
+            data[8 * i..8 * i + 8].copy_from_slice(&w.to_le_bytes());
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Corruption {
+    Short {
+        len: usize,
+    },
+    Checksum {
+        seq: u64,
+        expected: u64,
+        actual: u64,
+    },
+    Bytes {
+        seq: u64,
+        offset: usize,
+        expected: u8,
+        actual: u8,
+        count: usize,
+    },
+}
+
+impl fmt::Display for Corruption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Corruption::Short { len } => {
+                write!(f, "payload of {} bytes, shorter than its header", len)
+            }
+            Corruption::Checksum {
+                seq,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "message {}: checksum {:#018x} instead of {:#018x}",
+                seq, actual, expected
+            ),
+            Corruption::Bytes {
+                seq,
+                offset,
+                expected,
+                actual,
+                count,
+            } => write!(
+                f,
+                "message {}: {} corrupted bytes, the first at offset {} is {:#04x} instead of {:#04x}",
+                seq, count, offset, actual, expected
+            ),
+        }
+    }
+}
+
+// SplitMix64, good enough to fill payloads and cheap to replay
+fn next(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+// The base body of `len` bytes for a seed
+fn base(seed: u64, len: usize) -> Vec<u8> {
+    let mut state = seed;
+    let mut body = Vec::with_capacity(len + 8);
+    while body.len() < len {
+        body.extend_from_slice(&next(&mut state).to_le_bytes());
+    }
+    body.truncate(len);
+    body
+}
+
+// The key of message `seq`, XOR-ed into every word of the base body
+fn key(seed: u64, seq: u64) -> [u8; 8] {
+    let mut state = seed ^ seq.wrapping_mul(0xd6e8_feb8_6659_fd93);
+    next(&mut state).to_le_bytes()
+}
+
+fn word(bytes: &[u8]) -> u64 {
+    let mut word = [0u8; 8];
+    word.copy_from_slice(bytes);
+    u64::from_le_bytes(word)
+}
+
+fn mask(data: &mut [u8], key: &[u8; 8]) {
+    let k = u64::from_le_bytes(*key);
+    let mut words = data.chunks_exact_mut(8);
+    for w in words.by_ref() {
+        let masked = word(w) ^ k;
+        w.copy_from_slice(&masked.to_le_bytes());
+    }
+    for (b, k) in words.into_remainder().iter_mut().zip(key.iter()) {
+        *b ^= k;
+    }
+}
+
+// Whether `data` is `base` masked with `key`, compared word per word
+fn masked(data: &[u8], base: &[u8], key: &[u8; 8]) -> bool {
+    let k = u64::from_le_bytes(*key);
+    let (words, base_words) = (data.chunks_exact(8), base.chunks_exact(8));
+    let tail = words.remainder().iter().zip(base_words.remainder());
+    tail.zip(key.iter()).all(|((d, b), k)| *d == b ^ k)
+        && words.zip(base_words).all(|(d, b)| word(d) ^ k == word(b))
+}
+
+/// FNV-1a over 64-bit words, then over the remaining bytes.
+pub fn checksum(data: &[u8]) -> u64 {
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut words = data.chunks_exact(8);
+    for w in words.by_ref() {
+        hash = (hash ^ word(w)).wrapping_mul(PRIME);
+    }
+    for b in words.remainder() {
+        hash = (hash ^ *b as u64).wrapping_mul(PRIME);
+    }
+    hash
+}
+
+/// Builds the payloads of a publisher.
+pub struct Generator {
+    seed: u64,
+    base: Vec<u8>,
+    checksum: u64,
+}
+
+impl Generator {
+    pub fn new(seed: u64, payload: usize) -> Generator {
+        assert!(
+            payload >= HEADER,
+            "The payload must be at least {} bytes",
+            HEADER
+        );
+        let base = base(seed, payload - HEADER);
+        let checksum = checksum(&base);
+        Generator {
+            seed,
+            base,
+            checksum,
+        }
+    }
+
+    /// The payload of message `seq`, sent at `sent` nanoseconds since the epoch.
+    pub fn payload(&self, seq: u64, sent: u64) -> Vec<u8> {
+        let mut data = vec![0u8; HEADER + self.base.len()];
+        let header = Header {
+            seq,
+            seed: self.seed,
+            sent,
+            checksum: self.checksum,
+        };
+        header.write(&mut data[..HEADER]);
+        data[HEADER..].copy_from_slice(&self.base);
+        mask(&mut data[HEADER..], &key(self.seed, seq));
+        data
+    }
+}
+
+/// Checks the payloads of a subscriber, caching the base bodies and their checksums.
+#[derive(Default)]
+pub struct Verifier {
+    bases: HashMap<(u64, usize), (Vec<u8>, u64)>,
+}
+
+impl Verifier {
+    pub fn new() -> Verifier {
+        Verifier::default()
+    }
+
+    pub fn verify(&mut self, data: &[u8]) -> Result<Header, Corruption> {
+        if data.len() < HEADER {
+            return Err(Corruption::Short { len: data.len() });
+        }
+        let header = Header::read(&data[..HEADER]);
+        let received = &data[HEADER..];
+        let (base, sum) = self
+            .bases
+            .entry((header.seed, received.len()))
+            .or_insert_with(|| {
+                let base = base(header.seed, received.len());
+                let sum = checksum(&base);
+                (base, sum)
+            });
+        let key = key(header.seed, header.seq);
+
+        // Locate the corrupted bytes, if any
+        if !masked(received, base, &key) {
+            let mut diff = received
+                .iter()
+                .zip(base.iter())
+                .enumerate()
+                .map(|(i, (r, b))| (i, *r, b ^ key[i % 8]))
+                .filter(|(_, r, e)| r != e);
+            let (offset, actual, expected) = diff.next().unwrap();
+            return Err(Corruption::Bytes {
+                seq: header.seq,
+                offset: HEADER + offset,
+                expected,
+                actual,
+                count: 1 + diff.count(),
+            });
+        }
+        // The body is intact, a wrong checksum is a corrupted header
+        if header.checksum != *sum {
+            return Err(Corruption::Checksum {
+                seq: header.seq,
+                expected: *sum,
+                actual: header.checksum,
+            });
+        }
+        Ok(header)
+    }
+}
@@ -0,0 +1,179 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The readiness handshake between a listening benchmark and its counterpart.
+//!
+//! A listener given port 0 lets zenoh bind an ephemeral port, then prints the
+//! locator zenoh bound as `ready,<locator>` once it accepts connections, and
+//! optionally writes it to a file. A client given `--wait-ready` retries with
+//! backoff instead of failing when the listener is not up yet.
+use async_std::task;
+use std::fmt::Display;
+use std::future::Future;
+use std::net::TcpStream;
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::UnixStream;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use zenoh::net::runtime::Runtime;
+
+const BACKOFF_MIN: Duration = Duration::from_millis(10);
+const BACKOFF_MAX: Duration = Duration::from_secs(1);
+
+/// Removes the socket file a killed listener left behind on a `unixsock-stream/`
+/// locator, zenoh refuses to bind it otherwise. Anything but a socket nobody
+/// listens on, e.g. a regular file behind a mistyped locator, is left alone.
+pub fn remove_stale(locator: &str) {
+    #[cfg(unix)]
+    if let Some(("unixsock-stream", path)) = locator.split_once('/') {
+        let is_socket = std::fs::symlink_metadata(path)
+            .map(|m| m.file_type().is_socket())
+            .unwrap_or(false);
+        if is_socket && UnixStream::connect(path).is_err() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// The locator to announce for a `requested` locator once zenoh bound the
+/// `bound` ones: the requested locator with the port zenoh picked if it was 0.
+/// The host is kept as is, TLS checks it against the certificate.
+///
+/// Only one listener per protocol can be given port 0.
+pub fn bound<L: Display>(requested: &str, bound: &[L]) -> String {
+    let (proto, host) = match requested.split_once('/') {
+        Some((proto, address)) => match address.strip_suffix(":0") {
+            Some(host) => (proto, host),
+            None => return requested.to_string(),
+        },
+        None => return requested.to_string(),
+    };
+    let port = bound.iter().find_map(|l| {
+        let l = l.to_string();
+        let (p, address) = l.split_once('/')?;
+        let port: u16 = address.rsplit_once(':')?.1.parse().ok()?;
+        (p == proto && port != 0).then_some(port)
+    });
+    match port {
+        Some(port) => format!("{}/{}:{}", proto, host, port),
+        None => panic!(
+            "{} is not bound: {:?}",
+            requested,
+            bound.iter().map(|l| l.to_string()).collect::<Vec<String>>()
+        ),
+    }
+}
+
+/// The locator to announce for a `requested` locator of a runtime, see `bound`.
+pub fn listening(runtime: &Runtime, requested: &str) -> String {
+    bound(requested, &runtime.manager().get_locators())
+}
+
+/// Prints the ready line for a bound locator and writes it to `file`, if any.
+/// A listener on several locators announces them at once, comma separated.
+pub fn announce(locator: &str, file: Option<&Path>) {
+    if let Some(file) = file {
+        // Write then rename, not to expose a partial locator to a reader
+        let tmp = file.with_extension("tmp");
+        std::fs::write(&tmp, locator).unwrap();
+        std::fs::rename(&tmp, file).unwrap();
+    }
+    println!("ready,{}", locator);
+}
+
+/// Retries `f` with an exponential backoff until it succeeds or `timeout`
+/// expires, returning the last error in that case. A zero timeout makes a
+/// single attempt, `Duration::MAX` retries forever.
+pub async fn retry<T, E, F, Fut>(timeout: Duration, mut f: F) -> Result<T, E>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, E>>,
+{
+    let deadline = Instant::now().checked_add(timeout);
+    let mut backoff = BACKOFF_MIN;
+    loop {
+        match f().await {
+            Ok(t) => return Ok(t),
+            Err(e) if deadline.is_some_and(|d| Instant::now() + backoff > d) => return Err(e),
+            Err(_) => {
+                task::sleep(backoff).await;
+                backoff = (backoff * 2).min(BACKOFF_MAX);
+            }
+        }
+    }
+}
+
+/// Waits for a `tcp/`, `tls/` or `unixsock-stream/` locator to accept
+/// connections. Other protocols cannot be probed without a handshake and
+/// return immediately.
+pub async fn wait(locator: &str, timeout: Duration) {
+    #[cfg(unix)]
+    if let Some(("unixsock-stream", path)) = locator.split_once('/') {
+        retry(timeout, || async move { UnixStream::connect(path) })
+            .await
+            .unwrap_or_else(|e| panic!("{} is not ready: {}", locator, e));
+        return;
+    }
+    if let Some(("tcp", address)) | Some(("tls", address)) = locator.split_once('/') {
+        retry(timeout, || async move { TcpStream::connect(address) })
+            .await
+            .unwrap_or_else(|e| panic!("{} is not ready: {}", locator, e));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bound_port() {
+        let bound_ = ["udp/127.0.0.1:7000", "tcp/127.0.0.1:7001"];
+        assert_eq!(bound("tcp/127.0.0.1:0", &bound_), "tcp/127.0.0.1:7001");
+        assert_eq!(bound("udp/127.0.0.1:0", &bound_), "udp/127.0.0.1:7000");
+        // The host is kept for the certificate checks
+        let bound_ = ["tls/127.0.0.1:7002"];
+        assert_eq!(bound("tls/localhost:0", &bound_), "tls/localhost:7002");
+        assert_eq!(bound("tcp/[::1]:0", &["tcp/[::1]:7003"]), "tcp/[::1]:7003");
+        // A given port or a path is announced as is
+        assert_eq!(bound("tcp/127.0.0.1:7447", &bound_), "tcp/127.0.0.1:7447");
+        assert_eq!(
+            bound("unixsock-stream//tmp/zenoh.sock", &bound_),
+            "unixsock-stream//tmp/zenoh.sock"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn remove_stale_sockets_only() {
+        let dir = std::env::temp_dir().join(format!("zenoh-perf-ready-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("file");
+        std::fs::write(&file, "data").unwrap();
+        remove_stale(&format!("unixsock-stream/{}", file.display()));
+        assert!(file.exists());
+
+        let socket = dir.join("socket");
+        drop(std::os::unix::net::UnixListener::bind(&socket).unwrap());
+        remove_stale(&format!("unixsock-stream/{}", socket.display()));
+        assert!(!socket.exists());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_bound() {
+        bound("tcp/127.0.0.1:0", &["udp/127.0.0.1:7000"]);
+    }
+}
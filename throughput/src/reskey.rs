@@ -0,0 +1,117 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! The forms used to address a resource, to compare their cost at each layer.
+use std::fmt;
+use std::str::FromStr;
+use zenoh::net::protocol::core::{Channel, CongestionControl, Priority, Reliability, ResKey, ZInt};
+use zenoh::net::protocol::io::{WBuf, ZBuf};
+use zenoh::net::protocol::proto::ZenohMessage;
+
+/// The name of the resource when no length is given.
+pub const NAME: &str = "/test/thr";
+/// The prefix bound to the resource id of the `rid-suffix` form.
+pub const PREFIX: &str = "/test";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Form {
+    RId,
+    RName,
+    RIdWithSuffix,
+}
+
+impl FromStr for Form {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Form, String> {
+        match s {
+            "rid" => Ok(Form::RId),
+            "rname" => Ok(Form::RName),
+            "rid-suffix" => Ok(Form::RIdWithSuffix),
+            _ => Err(format!("Unsupported reskey form: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Form {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Form::RId => "rid",
+            Form::RName => "rname",
+            Form::RIdWithSuffix => "rid-suffix",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Form {
+    /// The name to bind to the resource id before publishing, if any.
+    pub fn declared(&self, name: &str) -> Option<String> {
+        match self {
+            Form::RId => Some(name.to_string()),
+            Form::RName => None,
+            Form::RIdWithSuffix => Some(PREFIX.to_string()),
+        }
+    }
+
+    /// The key to publish on once `rid` is bound to the declared name.
+    pub fn reskey(&self, rid: ZInt, name: &str) -> ResKey {
+        match self {
+            Form::RId => ResKey::RId(rid),
+            Form::RName => ResKey::RName(name.to_string()),
+            Form::RIdWithSuffix => ResKey::RIdWithSuffix(rid, name[PREFIX.len()..].to_string()),
+        }
+    }
+}
+
+/// A resource name of `len` characters, `NAME` padded with `x`, never shorter than `NAME`.
+pub fn name(len: usize) -> String {
+    format!("{}{}", NAME, "x".repeat(len.saturating_sub(NAME.len())))
+}
+
+/// The bytes taken on the wire by a data message, batching excluded.
+pub fn wire_bytes(key: ResKey, payload: usize) -> usize {
+    let mut message = ZenohMessage::make_data(
+        key,
+        ZBuf::from(vec![0u8; payload]),
+        Channel {
+            priority: Priority::Data,
+            reliability: Reliability::Reliable,
+        },
+        CongestionControl::Block,
+        None,
+        None,
+        None,
+        None,
+    );
+    let mut wbuf = WBuf::new(64, false);
+    assert!(wbuf.write_zenoh_message(&mut message));
+    wbuf.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn name_length() {
+        for len in 0..=NAME.len() {
+            assert_eq!(name(len), NAME);
+        }
+        for len in NAME.len()..64 {
+            let name = name(len);
+            assert_eq!(name.len(), len, "{}", name);
+            assert!(name.starts_with(NAME));
+        }
+    }
+}
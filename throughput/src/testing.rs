@@ -0,0 +1,125 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Support for the integration tests of the benchmark crates: runs the binaries
+//! on ephemeral localhost ports and collects their output.
+use std::io::{BufRead, BufReader};
+use std::path::PathBuf;
+use std::process::{self, Child, Command, ExitStatus, Stdio};
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const TIMEOUT: Duration = Duration::from_secs(20);
+
+// A `unixsock-stream/` locator in the temporary directory, unique to this run
+pub fn unixsock(name: &str) -> String {
+    let path = std::env::temp_dir().join(format!("zenoh-perf-{}-{}.sock", process::id(), name));
+    format!("unixsock-stream/{}", path.display())
+}
+
+// Write result lines in the temporary directory, unique to this run, so that
+// two series can be compared with `zperf-compare -b <file> -c <file>`
+pub fn save(name: &str, lines: &[String]) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("zenoh-perf-{}-{}.csv", process::id(), name));
+    let mut content = lines.join("\n");
+    content.push('\n');
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+/// A benchmark binary killed when dropped, with its output lines.
+pub struct Process {
+    child: Child,
+    lines: Receiver<String>,
+}
+
+impl Process {
+    pub fn spawn(bin: &str, args: &[&str]) -> Process {
+        let mut child = Command::new(bin)
+            .args(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (tx, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                if tx.send(line.unwrap()).is_err() {
+                    break;
+                }
+            }
+        });
+        Process { child, lines }
+    }
+
+    // Collect the first `count` lines matching `filter`, panic on timeout
+    pub fn take<F: Fn(&str) -> bool>(&self, count: usize, filter: F) -> Vec<String> {
+        let deadline = Instant::now() + TIMEOUT;
+        let mut output = vec![];
+        while output.len() < count {
+            match self
+                .lines
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(line) if filter(&line) => output.push(line),
+                Ok(_) => {}
+                Err(_) => panic!("Missing output, got only: {:?}", output),
+            }
+        }
+        output
+    }
+
+    // Wait for the ready line and return the bound locator
+    pub fn ready(&self) -> String {
+        let line = self.take(1, |l| l.starts_with("ready,")).remove(0);
+        line["ready,".len()..].to_string()
+    }
+
+    // Wait for the process to exit by itself, panic on timeout
+    pub fn status(&mut self) -> ExitStatus {
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            if let Some(status) = self.child.try_wait().unwrap() {
+                return status;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "Still running after {:?}",
+                TIMEOUT
+            );
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    // Collect the first `count` result lines of the given test, e.g. `throughput`
+    pub fn lines(&self, count: usize, test: &str) -> Vec<String> {
+        self.take(count, |l| l.split(',').nth(2) == Some(test))
+    }
+
+    // Same as `lines`, split in fields
+    pub fn test(&self, count: usize, test: &str) -> Vec<Vec<String>> {
+        self.lines(count, test)
+            .iter()
+            .map(|l| l.split(',').map(|f| f.to_string()).collect())
+            .collect()
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
@@ -0,0 +1,172 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Generation of `Value`s of every type with a content of a given size.
+//!
+//! Each value embeds a sequence number so that the latency tests can match the
+//! replies, and the subscribers can measure the cost of the encoding of any type.
+use std::fmt;
+use std::str::FromStr;
+use zenoh::net::ZBuf;
+use zenoh::{Properties, Value};
+
+// The encoding description of the Custom values
+const CUSTOM_ENCODING: &str = "application/x-zenoh-perf";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Kind {
+    Raw,
+    Custom,
+    StringUtf8,
+    Properties,
+    Json,
+    Integer,
+    Float,
+}
+
+impl FromStr for Kind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Kind, String> {
+        match s {
+            "raw" => Ok(Kind::Raw),
+            "custom" => Ok(Kind::Custom),
+            "string" => Ok(Kind::StringUtf8),
+            "properties" => Ok(Kind::Properties),
+            "json" => Ok(Kind::Json),
+            "integer" => Ok(Kind::Integer),
+            "float" => Ok(Kind::Float),
+            _ => Err(format!("Unsupported value type: {}", s)),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Kind::Raw => "raw",
+            Kind::Custom => "custom",
+            Kind::StringUtf8 => "string",
+            Kind::Properties => "properties",
+            Kind::Json => "json",
+            Kind::Integer => "integer",
+            Kind::Float => "float",
+        };
+        write!(f, "{}", s)
+    }
+}
+
+impl Kind {
+    pub fn of(value: &Value) -> Kind {
+        match value {
+            Value::Raw(..) => Kind::Raw,
+            Value::Custom { .. } => Kind::Custom,
+            Value::StringUtf8(_) => Kind::StringUtf8,
+            Value::Properties(_) => Kind::Properties,
+            Value::Json(_) => Kind::Json,
+            Value::Integer(_) => Kind::Integer,
+            Value::Float(_) => Kind::Float,
+        }
+    }
+}
+
+// The sequence number is only embedded when there is room for it
+fn bytes(seq: u64, size: usize) -> ZBuf {
+    let mut data: Vec<u8> = (0..size).map(|i| (i % 10) as u8).collect();
+    if size >= 8 {
+        data[0..8].copy_from_slice(&seq.to_le_bytes());
+    }
+    data.into()
+}
+
+// A printable filler, so that the textual values have the requested size
+fn filler(len: usize) -> String {
+    (0..len).map(|i| (b'a' + (i % 26) as u8) as char).collect()
+}
+
+/// Generates a value of type `kind` whose content is about `size` bytes long.
+/// The Integer and Float values are always 8 bytes long.
+pub fn generate(kind: Kind, size: usize, seq: u64) -> Value {
+    match kind {
+        Kind::Raw => Value::from(bytes(seq, size)),
+        Kind::Custom => Value::Custom {
+            encoding_descr: CUSTOM_ENCODING.to_string(),
+            data: bytes(seq, size),
+        },
+        Kind::StringUtf8 => {
+            let s = format!("{:020}", seq);
+            let len = size.saturating_sub(s.len());
+            Value::StringUtf8(s + &filler(len))
+        }
+        Kind::Properties => {
+            // Many small properties, as in a configuration or a status
+            let mut ps = Properties::default();
+            ps.insert("seq".to_string(), seq.to_string());
+            let mut i = 0;
+            let mut len = 3 + seq.to_string().len();
+            while len < size {
+                let (k, v) = (format!("k{}", i), filler(8));
+                len += k.len() + v.len();
+                ps.insert(k, v);
+                i += 1;
+            }
+            Value::Properties(ps)
+        }
+        Kind::Json => {
+            let prefix = format!("{{\"seq\":{},\"data\":\"", seq);
+            let len = size.saturating_sub(prefix.len() + 2);
+            Value::Json(format!("{}{}\"}}", prefix, filler(len)))
+        }
+        Kind::Integer => Value::Integer(seq as i64),
+        Kind::Float => Value::Float(seq as f64),
+    }
+}
+
+/// The sequence number embedded by `generate`.
+pub fn seq(value: &Value) -> Option<u64> {
+    let read = |data: &ZBuf| -> Option<u64> {
+        let mut data = data.clone();
+        let mut bytes = [0u8; 8];
+        if data.read_bytes(&mut bytes) {
+            Some(u64::from_le_bytes(bytes))
+        } else {
+            None
+        }
+    };
+    match value {
+        Value::Raw(_, data) => read(data),
+        Value::Custom { data, .. } => read(data),
+        Value::StringUtf8(s) => s.get(0..20)?.parse().ok(),
+        Value::Properties(ps) => ps.get("seq")?.parse().ok(),
+        Value::Json(s) => {
+            let start = s.find(':')? + 1;
+            let end = start + s[start..].find(',')?;
+            s[start..end].parse().ok()
+        }
+        Value::Integer(i) => Some(*i as u64),
+        Value::Float(f) => Some(*f as u64),
+    }
+}
+
+/// The size of the content of a value.
+pub fn len(value: &Value) -> usize {
+    match value {
+        Value::Raw(_, payload) => payload.len(),
+        Value::Custom { data, .. } => data.len(),
+        Value::StringUtf8(s) => s.len(),
+        Value::Properties(ps) => ps.iter().map(|(k, v)| k.len() + v.len()).sum(),
+        Value::Json(s) => s.len(),
+        Value::Integer(_) => std::mem::size_of::<i64>(),
+        Value::Float(_) => std::mem::size_of::<f64>(),
+    }
+}
@@ -0,0 +1,79 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs the fragmenting publisher against its verifying subscriber, and
+//! checks that a corrupted payload stops the subscriber.
+use zenoh_perf_throughput::testing::*;
+
+fn subscriber(payload: &str) -> Process {
+    Process::spawn(
+        env!("CARGO_BIN_EXE_zn_sub_frag"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            payload,
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    )
+}
+
+#[test]
+fn zn_pub_frag_zn_sub_frag() {
+    for payload in ["65536", "4194304"].iter() {
+        let sub = subscriber(payload);
+        let locator = sub.ready();
+        let _publ = Process::spawn(
+            env!("CARGO_BIN_EXE_zn_pub_frag"),
+            &["-l", &locator, "-m", "peer", "-p", payload, "--seed", "7"],
+        );
+        let rows = sub.test(1, "fragments.estimate");
+        assert!(rows[0][5].parse::<usize>().unwrap() > 1, "Not fragmented");
+        for row in sub.test(3, "throughput").iter() {
+            assert_eq!(row.len(), 7, "Unexpected line: {:?}", row);
+            assert_eq!(row[4], *payload);
+            assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+            assert!(
+                row[6].parse::<f64>().unwrap() > 0.0,
+                "Zero goodput: {:?}",
+                row
+            );
+        }
+        sub.test(1, "latency.reassembly");
+    }
+}
+
+#[test]
+fn zn_pub_frag_corrupt() {
+    let mut sub = subscriber("1048576");
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_pub_frag"),
+        &[
+            "-l",
+            &locator,
+            "-m",
+            "peer",
+            "-p",
+            "1048576",
+            "--corrupt",
+            "100",
+        ],
+    );
+    assert_eq!(sub.status().code(), Some(1));
+}
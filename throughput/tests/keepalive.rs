@@ -0,0 +1,54 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! failure within the lease plus the keep-alive period.
+use zenoh_perf_throughput::testing::*;
+
+#[test]
+fn t_keepalive_detects_blackhole() {
+    let (lease, keep_alive) = (1_000u128, 250u128);
+    let mut bench = Process::spawn(
+        env!("CARGO_BIN_EXE_t_keepalive"),
+        &[
+            // Both the router and the proxy bind a free port themselves
+            "-l",
+            "127.0.0.1:0",
+            "--proxy",
+            "127.0.0.1:0",
+            "-c",
+            "4",
+            "--lease",
+            &lease.to_string(),
+            "--keep-alive",
+            &keep_alive.to_string(),
+            "--idle",
+            "1",
+            "--timeout",
+            "5",
+            "-n",
+            "idle",
+            "-s",
+            "test",
+        ],
+    );
+    let row = bench.test(1, "keepalive").remove(0);
+    assert_eq!(row.len(), 13, "Unexpected line: {:?}", row);
+    assert_eq!(row[12], "4", "Not all the sessions detected: {:?}", row);
+    let bound = (lease + keep_alive) * 1_000;
+    for max in [&row[9], &row[11]].iter() {
+        let us: u128 = max.parse().unwrap();
+        assert!(us <= bound, "Detected after {}us: {:?}", us, row);
+    }
+    assert!(bench.status().success());
+}
@@ -0,0 +1,87 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Smoke tests running the subscribers with an in-process publisher.
+use zenoh_perf_throughput::testing::*;
+
+fn check(layer: &str, rows: &[Vec<String>]) {
+    for row in rows.iter() {
+        assert_eq!(row.len(), 6, "Unexpected line: {:?}", row);
+        assert_eq!(row[0], layer);
+        assert_eq!(row[4], "64");
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+    }
+}
+
+#[test]
+fn t_sub_thr_loopback() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_t_sub_thr"),
+        &[
+            "-e",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "smoke",
+            "-s",
+            "loopback",
+            "--loopback",
+        ],
+    );
+    check("session", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn r_sub_thr_loopback() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_r_sub_thr"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "smoke",
+            "-s",
+            "loopback",
+            "--loopback",
+        ],
+    );
+    check("router", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn zn_sub_thr_loopback() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_sub_thr"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "smoke",
+            "-s",
+            "loopback",
+            "--loopback",
+        ],
+    );
+    check("zenoh-net", &sub.test(3, "throughput"));
+}
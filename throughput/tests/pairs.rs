@@ -0,0 +1,226 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each publisher against its subscriber or sink on an ephemeral port.
+use zenoh_perf_throughput::testing::*;
+
+fn check(layer: &str, rows: &[Vec<String>]) {
+    for row in rows.iter() {
+        assert_eq!(row.len(), 6, "Unexpected line: {:?}", row);
+        assert_eq!(row[0], layer);
+        assert_eq!(row[4], "64");
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {:?}", row);
+    }
+}
+
+fn check_sink(lines: &[String]) {
+    for line in lines.iter() {
+        let gbps: f64 = line.trim_end_matches(" Gbit/s").parse().unwrap();
+        assert!(gbps > 0.0, "Zero rate: {}", line);
+    }
+}
+
+#[test]
+fn t_pub_thr_t_sub_thr() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_t_sub_thr"),
+        &[
+            "-e",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let endpoint = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pub_thr"),
+        &["-c", &endpoint, "-m", "peer", "-p", "64"],
+    );
+    check("session", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn r_pub_thr_r_sub_thr() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_r_sub_thr"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_r_pub_thr"),
+        &["-l", &locator, "-m", "peer", "-p", "64"],
+    );
+    check("router", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn zn_pub_thr_zn_sub_thr() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_sub_thr"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_pub_thr"),
+        &["-l", &locator, "-m", "peer", "-p", "64"],
+    );
+    check("zenoh-net", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn z_put_thr_z_sub_thr() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_z_sub_thr"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_z_put_thr"),
+        &["-l", &locator, "-m", "peer", "-p", "64"],
+    );
+    check("zenoh", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn t_pub_thr_t_sink_tcp() {
+    let sink = Process::spawn(env!("CARGO_BIN_EXE_t_sink_tcp"), &["-l", "127.0.0.1:0"]);
+    let locator = sink.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pub_thr"),
+        &["-c", &locator, "-m", "peer", "-p", "64"],
+    );
+    check_sink(&sink.take(3, |l| l.ends_with(" Gbit/s")));
+}
+
+#[test]
+fn t_pub_thr_t_sink_udp() {
+    let sink = Process::spawn(env!("CARGO_BIN_EXE_t_sink_udp"), &["-l", "127.0.0.1:0"]);
+    let locator = sink.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pub_thr"),
+        &["-c", &locator, "-m", "peer", "-p", "64"],
+    );
+    check_sink(&sink.take(3, |l| l.ends_with(" Gbit/s")));
+}
+
+#[test]
+fn zn_pub_thr_zn_sub_thr_stream() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_sub_thr_stream"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_pub_thr"),
+        &["-l", &locator, "-m", "peer", "-p", "64"],
+    );
+    check("zenoh-net", &sub.test(3, "throughput"));
+}
+
+#[test]
+fn t_pubsub_thr_pair() {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pubsub_thr"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let endpoint = sub.ready();
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pubsub_thr"),
+        &[
+            "-c", &endpoint, "-m", "peer", "-p", "64", "-n", "pair", "-s", "test",
+        ],
+    );
+    check("session", &sub.test(3, "throughput"));
+}
+
+// The publisher and the subscriber are both clients of the router
+#[test]
+fn t_pub_thr_t_router_thr_t_sub_thr() {
+    let router = Process::spawn(
+        env!("CARGO_BIN_EXE_t_router_thr"),
+        &["-l", "tcp/127.0.0.1:0"],
+    );
+    let endpoint = router.ready();
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_t_sub_thr"),
+        &[
+            "-e", &endpoint, "-m", "client", "-p", "64", "-n", "router", "-s", "test",
+        ],
+    );
+    let _publ = Process::spawn(
+        env!("CARGO_BIN_EXE_t_pub_thr"),
+        &["-c", &endpoint, "-m", "client", "-p", "64"],
+    );
+    check("session", &sub.test(3, "throughput"));
+}
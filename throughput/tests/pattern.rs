@@ -0,0 +1,94 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Checks that the payload verifier accepts intact payloads and pinpoints
+//! corrupted ones.
+use zenoh_perf_throughput::pattern::{Corruption, Generator, Verifier, HEADER};
+
+#[test]
+fn intact() {
+    let mut verifier = Verifier::new();
+    for payload in [HEADER, HEADER + 1, 65_536, 1_048_579].iter() {
+        let generator = Generator::new(7, *payload);
+        for seq in 0..4 {
+            let data = generator.payload(seq, 1_000 + seq);
+            assert_eq!(data.len(), *payload);
+            let header = verifier.verify(&data).unwrap();
+            assert_eq!(
+                (header.seq, header.seed, header.sent),
+                (seq, 7, 1_000 + seq)
+            );
+        }
+    }
+}
+
+#[test]
+fn bodies_differ() {
+    let generator = Generator::new(7, 65_536);
+    let bodies: Vec<Vec<u8>> = (0..8)
+        .map(|seq| generator.payload(seq, 0)[HEADER..].to_vec())
+        .collect();
+    for (i, a) in bodies.iter().enumerate() {
+        for b in bodies[i + 1..].iter() {
+            assert_ne!(a, b);
+        }
+    }
+}
+
+#[test]
+fn flipped_byte() {
+    let generator = Generator::new(7, 65_536);
+    let mut data = generator.payload(3, 0);
+    data[40_000] ^= 0x01;
+    data[50_000] ^= 0x80;
+    match Verifier::new().verify(&data) {
+        Err(Corruption::Bytes {
+            seq, offset, count, ..
+        }) => assert_eq!((seq, offset, count), (3, 40_000, 2)),
+        r => panic!("Corruption not detected: {:?}", r),
+    }
+}
+
+#[test]
+fn swapped_fragments() {
+    // The second half of the payload comes from a later message
+    let generator = Generator::new(7, 65_536);
+    for later in [1, 2, 3].iter() {
+        let mut data = generator.payload(0, 0);
+        let next = generator.payload(*later, 0);
+        data[32_768..].copy_from_slice(&next[32_768..]);
+        assert!(matches!(
+            Verifier::new().verify(&data),
+            Err(Corruption::Bytes { offset, .. }) if offset >= 32_768
+        ));
+    }
+}
+
+#[test]
+fn checksum() {
+    let generator = Generator::new(7, 1_024);
+    let mut data = generator.payload(0, 0);
+    data[24] ^= 0x01;
+    assert!(matches!(
+        Verifier::new().verify(&data),
+        Err(Corruption::Checksum { seq: 0, .. })
+    ));
+}
+
+#[test]
+fn short() {
+    assert_eq!(
+        Verifier::new().verify(&[0u8; HEADER - 1]).unwrap_err(),
+        Corruption::Short { len: HEADER - 1 }
+    );
+}
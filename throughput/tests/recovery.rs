@@ -0,0 +1,53 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Blackholes the link between a publisher and a subscriber for a moment and
+//! checks that the data flows again once it heals.
+use zenoh_perf_throughput::testing::*;
+
+#[test]
+fn zn_recovery_after_blackhole() {
+    let mut bench = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_recovery"),
+        &[
+            // Both the subscriber and the proxy bind a free port themselves
+            "-l",
+            "tcp/127.0.0.1:0",
+            "--proxy",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            "64",
+            "-n",
+            "blackhole",
+            "-s",
+            "test",
+            "--warmup",
+            "1",
+            "--outage",
+            "0.5",
+            "--timeout",
+            "10",
+            "--rate",
+            "1000",
+        ],
+    );
+    let row = bench.test(1, "recovery").remove(0);
+    assert_eq!(row.len(), 13, "Unexpected line: {:?}", row);
+    assert_eq!(row[6], "blackhole");
+    // The held data is released once healed, well within the default lease
+    let _reconnected: u128 = row[9].parse().unwrap();
+    assert!(bench.status().success());
+}
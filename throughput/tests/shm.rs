@@ -0,0 +1,119 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs the shared memory publisher against its subscriber, next to the same
+//! payloads published from the heap over TCP loopback, and prints both side by
+//! side.
+#![cfg(feature = "shared-memory")]
+use zenoh_perf_throughput::testing::*;
+
+const PAYLOADS: [&str; 3] = ["1048576", "16777216", "67108864"];
+
+// The rows of the subscriber and the CPU rows of the publisher, as (test, value)
+fn run(payload: &str, shm: bool) -> Vec<(String, f64)> {
+    let sub = Process::spawn(
+        env!("CARGO_BIN_EXE_zn_sub_shm"),
+        &[
+            "-l",
+            "tcp/127.0.0.1:0",
+            "-m",
+            "peer",
+            "-p",
+            payload,
+            "-n",
+            "pair",
+            "-s",
+            "test",
+        ],
+    );
+    let locator = sub.ready();
+    // Keep the shared memory segment of the largest payloads within a few buffers
+    let mut args = vec![
+        "-l",
+        &locator,
+        "-m",
+        "peer",
+        "-p",
+        payload,
+        "-n",
+        "pair",
+        "-s",
+        "test",
+        "--buffers",
+        "2",
+    ];
+    if shm {
+        args.push("--shm");
+    }
+    let publ = Process::spawn(env!("CARGO_BIN_EXE_zn_pub_shm"), &args);
+    let parse = |l: &String| {
+        let row: Vec<&str> = l.split(',').collect();
+        assert_eq!(row.len(), 6, "Unexpected line: {}", l);
+        assert_eq!(row[4], payload);
+        (row[2].to_string(), row[5].parse().unwrap())
+    };
+    let mut rows: Vec<(String, f64)> = sub
+        .take(8, |l| l.starts_with("zenoh-net,test,"))
+        .iter()
+        .map(parse)
+        .collect();
+    rows.extend(publ.take(2, |l| l.contains(",cpu.pub,")).iter().map(parse));
+    rows
+}
+
+// The median of the values of a test
+fn median(rows: &[(String, f64)], test: &str) -> f64 {
+    let mut values: Vec<f64> = rows
+        .iter()
+        .filter(|(t, _)| t == test)
+        .map(|(_, v)| *v)
+        .collect();
+    assert!(!values.is_empty(), "Missing {}: {:?}", test, rows);
+    values.sort_by(f64::total_cmp);
+    values[values.len() / 2]
+}
+
+#[test]
+fn zn_pub_shm_vs_heap() {
+    println!("payload,heap msg/s,shm msg/s,heap cpu.pub,shm cpu.pub,heap cpu.sub,shm cpu.sub");
+    for payload in PAYLOADS.iter() {
+        let heap = run(payload, false);
+        let shm = run(payload, true);
+        for rows in [&heap, &shm].iter() {
+            assert!(median(rows, "throughput") > 0.0, "Zero rate: {:?}", rows);
+            median(rows, "latency.oneway");
+        }
+        // Only the shared memory path delivers shared memory buffers
+        assert_eq!(median(&heap, "shm.ratio"), 0.0);
+        assert_eq!(median(&shm, "shm.ratio"), 1.0);
+        // Without the copy through the socket, the largest payloads must go faster
+        if *payload == PAYLOADS[PAYLOADS.len() - 1] {
+            assert!(
+                median(&shm, "throughput") >= median(&heap, "throughput"),
+                "Shared memory slower than the heap: {:?} {:?}",
+                shm,
+                heap
+            );
+        }
+        println!(
+            "{},{},{},{:.3},{:.3},{:.3},{:.3}",
+            payload,
+            median(&heap, "throughput"),
+            median(&shm, "throughput"),
+            median(&heap, "cpu.pub"),
+            median(&shm, "cpu.pub"),
+            median(&heap, "cpu.sub"),
+            median(&shm, "cpu.sub")
+        );
+    }
+}
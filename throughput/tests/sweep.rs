@@ -0,0 +1,53 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Blackholes a few idle sessions and checks that both sides detect the
+//! Sweeps a few transport configurations and checks that the best one of each
+//! payload band is reported.
+use zenoh_perf_throughput::testing::*;
+
+#[test]
+fn t_sweep_ranks_each_band() {
+    let mut sweep = Process::spawn(
+        env!("CARGO_BIN_EXE_t_sweep"),
+        &[
+            // Each configuration binds a free port
+            "--port",
+            "0",
+            "-p",
+            "64,8192",
+            "-b",
+            "8192,65535",
+            "--sn-resolution",
+            "268435456",
+            "--qos",
+            "true",
+            "-d",
+            "1",
+            "-n",
+            "sweep",
+            "-s",
+            "test",
+        ],
+    );
+    let points = sweep.take(4, |l| l.starts_with("session,test,throughput."));
+    for line in points.iter() {
+        let row: Vec<&str> = line.split(',').collect();
+        assert_eq!(row.len(), 11, "Unexpected line: {}", line);
+        assert!(row[5].parse::<usize>().unwrap() > 0, "Zero rate: {}", line);
+    }
+    let best = sweep.test(2, "sweep.best");
+    let bands: Vec<&str> = best.iter().map(|row| row[11].as_str()).collect();
+    assert_eq!(bands, vec!["small", "large"]);
+    assert!(sweep.status().success());
+}
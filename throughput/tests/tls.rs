@@ -0,0 +1,143 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each publisher against its subscriber over TLS or QUIC with throwaway
+//! certificates, next to plain TCP for every payload size.
+#![cfg(any(feature = "tls", feature = "quic"))]
+use std::path::Path;
+use zenoh_perf_throughput::certs;
+use zenoh_perf_throughput::testing::*;
+
+const PAYLOADS: [&str; 3] = ["64", "8192", "65536"];
+
+// The throughput lines of a pair, the subscriber listening on `locator`
+fn run(
+    sub: (&str, &str),
+    publ: (&str, &str),
+    locator: &str,
+    conf: &Path,
+    payload: &str,
+) -> Vec<String> {
+    let conf = conf.to_str().unwrap();
+    let sub = Process::spawn(
+        sub.0,
+        &[
+            sub.1, locator, "-m", "peer", "-p", payload, "-n", "pair", "-s", "test", "--conf", conf,
+        ],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(
+        publ.0,
+        &[
+            publ.1, &locator, "-m", "peer", "-p", payload, "--conf", conf,
+        ],
+    );
+    sub.lines(3, "throughput")
+}
+
+// Average rate of throughput lines
+fn rate(lines: &[String]) -> f64 {
+    lines
+        .iter()
+        .map(|l| l.split(',').nth(5).unwrap().parse::<f64>().unwrap())
+        .sum::<f64>()
+        / lines.len() as f64
+}
+
+// Prints the rates side by side and saves both series for `zperf-compare`
+fn compare(proto: &str, sub: (&str, &str), publ: (&str, &str)) {
+    let conf = certs::generate(&certs::temp_dir());
+    let (mut secure, mut tcp) = (vec![], vec![]);
+    println!("payload,tcp msg/s,{} msg/s", proto);
+    for payload in PAYLOADS.iter() {
+        let s = run(sub, publ, &format!("{}/localhost:0", proto), &conf, payload);
+        let t = run(sub, publ, "tcp/127.0.0.1:0", &conf, payload);
+        assert!(
+            rate(&s) > 0.0 && rate(&t) > 0.0,
+            "Zero rate for {} B: {:?} over {}, {:?} over TCP",
+            payload,
+            s,
+            proto,
+            t
+        );
+        println!("{},{:.0},{:.0}", payload, rate(&t), rate(&s));
+        secure.extend(s);
+        tcp.extend(t);
+    }
+    let name = Path::new(sub.0).file_stem().unwrap().to_str().unwrap();
+    println!(
+        "zperf-compare -b {} -c {}",
+        save(&format!("{}-tcp", name), &tcp).display(),
+        save(&format!("{}-{}", name, proto), &secure).display()
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn t_pub_thr_t_sub_thr_tls() {
+    compare(
+        "tls",
+        (env!("CARGO_BIN_EXE_t_sub_thr"), "-e"),
+        (env!("CARGO_BIN_EXE_t_pub_thr"), "-c"),
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn zn_pub_thr_zn_sub_thr_tls() {
+    compare(
+        "tls",
+        (env!("CARGO_BIN_EXE_zn_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_zn_pub_thr"), "-l"),
+    );
+}
+
+#[cfg(feature = "tls")]
+#[test]
+fn z_put_thr_z_sub_thr_tls() {
+    compare(
+        "tls",
+        (env!("CARGO_BIN_EXE_z_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_z_put_thr"), "-l"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn t_pub_thr_t_sub_thr_quic() {
+    compare(
+        "quic",
+        (env!("CARGO_BIN_EXE_t_sub_thr"), "-e"),
+        (env!("CARGO_BIN_EXE_t_pub_thr"), "-c"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn zn_pub_thr_zn_sub_thr_quic() {
+    compare(
+        "quic",
+        (env!("CARGO_BIN_EXE_zn_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_zn_pub_thr"), "-l"),
+    );
+}
+
+#[cfg(feature = "quic")]
+#[test]
+fn z_put_thr_z_sub_thr_quic() {
+    compare(
+        "quic",
+        (env!("CARGO_BIN_EXE_z_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_z_put_thr"), "-l"),
+    );
+}
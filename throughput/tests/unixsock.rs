@@ -0,0 +1,83 @@
+//
+// Copyright (c) 2017, 2020 ADLINK Technology Inc.
+//
+// This program and the accompanying materials are made available under the
+// terms of the Eclipse Public License 2.0 which is available at
+// http://www.eclipse.org/legal/epl-2.0, or the Apache License, Version 2.0
+// which is available at https://www.apache.org/licenses/LICENSE-2.0.
+//
+// SPDX-License-Identifier: EPL-2.0 OR Apache-2.0
+//
+// Contributors:
+//   ADLINK zenoh team, <zenoh@adlink-labs.tech>
+//
+//! Runs each publisher against its subscriber over a Unix socket, then over
+//! TCP loopback, and compares their rates.
+#![cfg(all(unix, feature = "unixsock-stream"))]
+use zenoh_perf_throughput::testing::*;
+
+// Average rate of a pair, the subscriber listening on `locator`
+fn rate(sub: (&str, &str), publ: (&str, &str), locator: &str) -> f64 {
+    let sub = Process::spawn(
+        sub.0,
+        &[
+            sub.1, locator, "-m", "peer", "-p", "64", "-n", "pair", "-s", "test",
+        ],
+    );
+    let locator = sub.ready();
+    let _publ = Process::spawn(publ.0, &[publ.1, &locator, "-m", "peer", "-p", "64"]);
+    let rows = sub.test(3, "throughput");
+    rows.iter()
+        .map(|r| r[5].parse::<f64>().unwrap())
+        .sum::<f64>()
+        / rows.len() as f64
+}
+
+fn compare(name: &str, sub: (&str, &str), publ: (&str, &str)) {
+    let unixsock = rate(sub, publ, &unixsock(name));
+    let tcp = rate(sub, publ, "tcp/127.0.0.1:0");
+    assert!(unixsock > 0.0, "Zero rate over the Unix socket");
+    // Loose bound, the two runs share a possibly busy host
+    assert!(
+        unixsock * 10.0 > tcp,
+        "{} msg/s over the Unix socket against {} msg/s over TCP",
+        unixsock,
+        tcp
+    );
+}
+
+#[test]
+fn t_pub_thr_t_sub_thr() {
+    compare(
+        "t_thr",
+        (env!("CARGO_BIN_EXE_t_sub_thr"), "-e"),
+        (env!("CARGO_BIN_EXE_t_pub_thr"), "-c"),
+    );
+}
+
+#[test]
+fn r_pub_thr_r_sub_thr() {
+    compare(
+        "r_thr",
+        (env!("CARGO_BIN_EXE_r_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_r_pub_thr"), "-l"),
+    );
+}
+
+#[test]
+fn zn_pub_thr_zn_sub_thr() {
+    compare(
+        "zn_thr",
+        (env!("CARGO_BIN_EXE_zn_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_zn_pub_thr"), "-l"),
+    );
+}
+
+#[test]
+fn z_put_thr_z_sub_thr() {
+    compare(
+        "z_thr",
+        (env!("CARGO_BIN_EXE_z_sub_thr"), "-l"),
+        (env!("CARGO_BIN_EXE_z_put_thr"), "-l"),
+    );
+}